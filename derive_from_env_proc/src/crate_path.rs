@@ -0,0 +1,67 @@
+use proc_macro2::{Group, Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+
+/// Replaces the `::derive_from_env` paths of the generated code by `crate_path`, given by
+/// `#[from_env(crate = "...")]` when the crate is re-exported by another one.
+pub fn replace(tokens: TokenStream, crate_path: Option<&syn::Path>) -> TokenStream {
+    match crate_path {
+        Some(crate_path) => replace_in(tokens, &crate_path.to_token_stream()),
+        None => tokens,
+    }
+}
+
+fn replace_in(tokens: TokenStream, crate_path: &TokenStream) -> TokenStream {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    let mut replaced = Vec::with_capacity(tokens.len());
+    let mut index = 0;
+    while index < tokens.len() {
+        match &tokens[index..] {
+            [TokenTree::Punct(first), TokenTree::Punct(second), TokenTree::Ident(ident), ..]
+                if first.as_char() == ':'
+                    && first.spacing() == Spacing::Joint
+                    && second.as_char() == ':'
+                    && ident == "derive_from_env" =>
+            {
+                replaced.extend(crate_path.clone());
+                index += 3;
+            }
+            [TokenTree::Group(group), ..] => {
+                let mut new_group =
+                    Group::new(group.delimiter(), replace_in(group.stream(), crate_path));
+                new_group.set_span(group.span());
+                replaced.push(TokenTree::Group(new_group));
+                index += 1;
+            }
+            [token, ..] => {
+                replaced.push(token.clone());
+                index += 1;
+            }
+            [] => unreachable!(),
+        }
+    }
+    replaced.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    #[test]
+    fn test_replace() {
+        let crate_path: syn::Path = syn::parse_quote!(platform::config);
+        let tokens = quote! {
+            impl ::derive_from_env::_inner_trait::FromEnv for Config {
+                fn f() -> Result<(), ::derive_from_env::FromEnvError> { derive_from_env::x() }
+            }
+        };
+        assert_eq!(
+            super::replace(tokens, Some(&crate_path)).to_string(),
+            quote! {
+                impl platform::config::_inner_trait::FromEnv for Config {
+                    fn f() -> Result<(), platform::config::FromEnvError> { derive_from_env::x() }
+                }
+            }
+            .to_string()
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Ident, ItemFn, LitStr, Token};
+
+/// Arguments of `#[from_env_test(vars("KEY" = "value", ...), unset("KEY", ...))]`.
+pub struct TestEnv {
+    vars: Vec<(LitStr, LitStr)>,
+    unset: Vec<LitStr>,
+}
+
+struct VarAssignment {
+    key: LitStr,
+    value: LitStr,
+}
+
+impl Parse for VarAssignment {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        Ok(VarAssignment { key, value })
+    }
+}
+
+impl Parse for TestEnv {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut env = TestEnv {
+            vars: Vec::new(),
+            unset: Vec::new(),
+        };
+        while !input.is_empty() {
+            let section: Ident = input.parse()?;
+            let content;
+            syn::parenthesized!(content in input);
+            match section.to_string().as_str() {
+                "vars" => env.vars.extend(
+                    Punctuated::<VarAssignment, Token![,]>::parse_terminated(&content)?
+                        .into_iter()
+                        .map(|var| (var.key, var.value)),
+                ),
+                "unset" => env
+                    .unset
+                    .extend(Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?),
+                _ => {
+                    return Err(syn::Error::new(
+                        section.span(),
+                        "expected `vars(...)` or `unset(...)`",
+                    ))
+                }
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(env)
+    }
+}
+
+pub fn expand(env: TestEnv, mut item: ItemFn) -> TokenStream {
+    let keys = env
+        .vars
+        .iter()
+        .map(|(key, _)| key)
+        .chain(env.unset.iter())
+        .collect::<Vec<_>>();
+    let values = env
+        .vars
+        .iter()
+        .map(|(_, value)| quote! { Some(#value) })
+        .chain(env.unset.iter().map(|_| quote! { None }))
+        .collect::<Vec<_>>();
+    let body = &item.block;
+    item.block = syn::parse_quote! {
+        {
+            let __from_env_guard = ::derive_from_env::test_util::EnvGuard::acquire(&[
+                #( (#keys, #values) ),*
+            ]);
+            #body
+        }
+    };
+    quote! { #item }
+}
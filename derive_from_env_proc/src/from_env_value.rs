@@ -0,0 +1,151 @@
+use darling::{ast, FromDeriveInput, FromVariant};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+#[derive(FromDeriveInput)]
+#[darling(attributes(from_env), supports(enum_unit))]
+pub struct EnvValue {
+    ident: syn::Ident,
+    #[darling(default, rename = "crate")]
+    crate_path: Option<syn::Path>,
+    /// Variable read without prefix, instead of the upper-snake-cased enum name.
+    #[darling(default)]
+    var: Option<String>,
+    data: ast::Data<EnvValueVariant, ()>,
+}
+
+#[derive(FromVariant)]
+#[darling(attributes(from_env))]
+struct EnvValueVariant {
+    ident: syn::Ident,
+    #[darling(default)]
+    rename: Option<String>,
+    #[darling(multiple)]
+    alias: Vec<String>,
+}
+
+pub fn expand(env_value: EnvValue) -> TokenStream {
+    let enum_identifier = &env_value.ident;
+    let variants = env_value.data.take_enum().unwrap();
+    let variant_identifiers = variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let names = variants
+        .iter()
+        .map(|v| v.rename.clone().unwrap_or_else(|| v.ident.to_string()))
+        .collect::<Vec<_>>();
+    let accepted_names = variants
+        .iter()
+        .zip(&names)
+        .map(|(v, name)| std::iter::once(name).chain(&v.alias).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let expected = format!("one of: {}", names.join(", "));
+    let enum_name = enum_identifier.to_string();
+    let default_var_name = env_value
+        .var
+        .clone()
+        .unwrap_or_else(|| upper_snake_case(&enum_name));
+
+    let expanded = quote! {
+        impl ::std::str::FromStr for #enum_identifier {
+            type Err = ::derive_from_env::ParseValueError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                #(
+                    if [#(#accepted_names),*].iter().any(|name| name.eq_ignore_ascii_case(s)) {
+                        return Ok(#enum_identifier::#variant_identifiers);
+                    }
+                )*
+                Err(::derive_from_env::ParseValueError {
+                    value: s.to_string(),
+                    expected: &[#(#names),*],
+                })
+            }
+        }
+        impl ::std::fmt::Display for #enum_identifier {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(match self {
+                    #( #enum_identifier::#variant_identifiers => #names ),*
+                })
+            }
+        }
+        impl ::derive_from_env::_inner_trait::FromEnv for #enum_identifier {
+            fn from_source(source: &dyn ::derive_from_env::EnvSource) -> Result<Self, ::derive_from_env::FromEnvError> {
+                Self::from_source_with_prefix(source, #default_var_name)
+            }
+            fn from_source_with_prefix(
+                source: &dyn ::derive_from_env::EnvSource,
+                prefix: &str,
+            ) -> Result<Self, ::derive_from_env::FromEnvError> {
+                let (str_value, origin) = source.lookup(prefix)?.ok_or_else(|| {
+                    ::derive_from_env::FromEnvError::MissingEnvVar {
+                        var_name: prefix.to_string(),
+                        origin: source.origin(prefix),
+                        fallbacks: Vec::new(),
+                    }
+                })?;
+                str_value.parse().map_err(|_| ::derive_from_env::FromEnvError::ParsingFailure {
+                    var_name: prefix.to_string(),
+                    expected_type: #expected.to_string(),
+                    str_value,
+                    origin,
+                })
+            }
+            fn env_vars() -> Vec<::derive_from_env::EnvVarSpec> {
+                Self::env_vars_with_prefix(#default_var_name)
+            }
+            fn env_vars_with_prefix(prefix: &str) -> Vec<::derive_from_env::EnvVarSpec> {
+                vec![::derive_from_env::EnvVarSpec {
+                    name: prefix.to_string(),
+                    field: String::new(),
+                    type_name: #enum_name.to_string(),
+                    default: None,
+                    required: true,
+                    optional: false,
+                    hidden: false,
+                    secret: false,
+                }]
+            }
+        }
+        impl ::derive_from_env::_inner_trait::EnvMerge for #enum_identifier {
+            /// Enums have no default: `other` is kept unless the policy prefers `self`.
+            fn env_merge(self, other: Self, policy: ::derive_from_env::MergePolicy) -> Self {
+                match policy {
+                    ::derive_from_env::MergePolicy::PreferSelf => self,
+                    _ => other,
+                }
+            }
+        }
+        impl ::derive_from_env::_inner_trait::CliArgs for #enum_identifier {
+            fn cli_args(&self, path: &str, style: ::derive_from_env::ArgStyle, redact: bool, args: &mut Vec<String>) {
+                ::derive_from_env::_inner_helpers::push_arg(args, path, &self.to_string(), style, redact);
+            }
+        }
+        impl ::derive_from_env::_inner_trait::EnvDiff for #enum_identifier {
+            fn env_diff(&self, other: &Self) -> Vec<::derive_from_env::FieldDiff> {
+                self.env_diff_with_prefix(other, #default_var_name)
+            }
+            fn env_diff_with_prefix(&self, other: &Self, prefix: &str) -> Vec<::derive_from_env::FieldDiff> {
+                let (old, new) = (self.to_string(), other.to_string());
+                if old == new {
+                    return Vec::new();
+                }
+                vec![::derive_from_env::FieldDiff {
+                    field: String::new(),
+                    var_name: prefix.to_string(),
+                    old,
+                    new,
+                }]
+            }
+        }
+    };
+    crate::crate_path::replace(expanded, env_value.crate_path.as_ref())
+}
+
+pub(crate) fn upper_snake_case(name: &str) -> String {
+    let mut upper = String::new();
+    for (i, c) in name.char_indices() {
+        if c.is_uppercase() && i > 0 {
+            upper.push('_');
+        }
+        upper.extend(c.to_uppercase());
+    }
+    upper
+}
@@ -1,8 +1,17 @@
 extern crate proc_macro;
 
-use darling::FromField;
+mod crate_path;
+mod from_env_test;
+mod from_env_value;
+mod manifest;
+mod tagged_enum;
+
+use darling::util::Override;
+use darling::{FromDeriveInput, FromField, FromMeta};
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 use syn::{Data, DeriveInput, GenericArgument, PathArguments, Type};
 
 #[derive(FromField)]
@@ -10,43 +19,569 @@ use syn::{Data, DeriveInput, GenericArgument, PathArguments, Type};
 struct EnvField {
     ident: Option<syn::Ident>,
     ty: syn::Type,
-    #[darling(default)]
-    default: Option<syn::Lit>,
+    /// `default = "..."`, or a bare `default` on a flattened section.
+    #[darling(default, rename = "default")]
+    default_attr: Option<Override<syn::Lit>>,
     #[darling(default)]
     no_prefix: bool,
+    /// Segment between the prefixes and the name of the field.
     #[darling(default)]
-    var: Option<syn::Lit>,
+    prefix: Option<String>,
+    #[darling(default)]
+    var: Option<VarNames>,
     #[darling(default)]
     from_str: bool,
+    #[darling(default)]
+    flatten: bool,
+    #[darling(default)]
+    deserialize: bool,
+    #[darling(default)]
+    path_list: bool,
+    #[darling(default)]
+    decimal_comma: bool,
+    #[darling(default)]
+    lenient_bool: bool,
+    #[darling(default)]
+    lenient: bool,
+    /// Function checking the loaded value.
+    #[darling(default)]
+    validate: Option<syn::Path>,
+    /// Range of the accepted numbers, e.g. `1..=65535`.
+    #[darling(default)]
+    range: Option<String>,
+    /// Regular expression the value must match.
+    #[darling(default)]
+    matches: Option<String>,
+    /// Minimum number of characters of a string, or of elements of a collection.
+    #[darling(default)]
+    min_len: Option<usize>,
+    /// Maximum number of characters of a string, or of elements of a collection.
+    #[darling(default)]
+    max_len: Option<usize>,
+    #[darling(default)]
+    flag: bool,
+    #[darling(default)]
+    percent: Option<Override<PercentMode>>,
+    #[darling(default)]
+    base64: Option<Override<Base64Alphabet>>,
+    #[darling(default)]
+    hex: bool,
+    #[darling(default)]
+    zeroize: bool,
+    #[darling(default)]
+    credential: Option<String>,
+    /// Reads the value from the file named by `<NAME>_FILE` when the variable is not set.
+    #[darling(default)]
+    file: bool,
+    /// Opts out of the `file_suffix` of the struct.
+    #[darling(default)]
+    no_file: bool,
+    /// Strips the whitespace surrounding the value before parsing it.
+    #[darling(default)]
+    trim: bool,
+    /// Opts out of the `trim` of the struct, the value being read verbatim.
+    #[darling(default)]
+    raw: bool,
+    #[darling(default)]
+    json_pointer: Option<String>,
+    #[darling(default)]
+    separator: Option<String>,
+    #[darling(default, multiple)]
+    append_from: Vec<String>,
+    #[darling(default)]
+    secret: bool,
+    #[darling(default)]
+    doc_hidden: bool,
+    #[darling(default)]
+    from: Option<syn::Type>,
+    #[darling(default)]
+    try_from: Option<syn::Type>,
+    #[darling(default, multiple)]
+    alias: Vec<String>,
+    #[darling(default, multiple)]
+    alias_var: Vec<String>,
+    #[darling(default, multiple)]
+    deprecated_alias: Vec<String>,
+    #[darling(default)]
+    default_env: Option<String>,
+    /// Function called for the value when the variable is not set.
+    #[darling(default)]
+    default_fn: Option<syn::Path>,
+    #[darling(default)]
+    type_name: Option<String>,
+    /// Function parsing the value, or each item of a collection, in place of `FromStr`.
+    #[darling(default)]
+    parse_with: Option<syn::Path>,
+    /// Module providing `parse` and `to_string` functions for the value.
+    #[darling(default)]
+    with: Option<syn::Path>,
+    #[darling(default)]
+    lazy: bool,
+    #[darling(default)]
+    deny_duplicates: bool,
+    #[darling(default)]
+    indexed: bool,
+    #[darling(default)]
+    deny_gaps: bool,
+    #[darling(default)]
+    count_var: Option<String>,
+    #[darling(default)]
+    tuple_separator: Option<String>,
+    #[darling(default)]
+    quoted: bool,
+    #[darling(default)]
+    json: bool,
+    #[darling(default)]
+    format: Option<String>,
+    #[darling(default)]
+    json_var: Option<String>,
+    #[darling(default)]
+    url_var: Option<String>,
+    #[darling(default)]
+    deny_conflicts: bool,
+    #[darling(default)]
+    kv_separator: Option<String>,
+    #[darling(default)]
+    split: Option<SplitMode>,
+    #[darling(default)]
+    non_empty: Option<Override<NonEmptyMode>>,
+    /// Position of the field in a tuple struct, naming its variable.
+    #[darling(skip)]
+    position: Option<usize>,
+    /// Whether the type of the field is a type parameter of the struct, parsed with `FromStr`.
+    #[darling(skip)]
+    generic: bool,
+}
+
+/// Names of `#[from_env(var = "...")]`, or of `var = ["...", ...]` tried in order.
+struct VarNames(Vec<syn::Lit>);
+
+impl FromMeta for VarNames {
+    fn from_value(value: &syn::Lit) -> darling::Result<Self> {
+        Ok(VarNames(vec![value.clone()]))
+    }
+
+    fn from_expr(expr: &syn::Expr) -> darling::Result<Self> {
+        match expr {
+            syn::Expr::Lit(lit) => Self::from_value(&lit.lit),
+            syn::Expr::Array(array) if array.elems.is_empty() => {
+                Err(darling::Error::custom("var needs at least one name").with_span(array))
+            }
+            syn::Expr::Array(array) => array
+                .elems
+                .iter()
+                .map(|name| match name {
+                    syn::Expr::Lit(lit) => Ok(lit.lit.clone()),
+                    name => Err(darling::Error::unexpected_expr_type(name)),
+                })
+                .collect::<darling::Result<_>>()
+                .map(VarNames),
+            expr => Err(darling::Error::unexpected_expr_type(expr)),
+        }
+    }
+}
+
+/// Policies of `#[from_env(file_conflict = "...")]`, when both the variable and its file are set.
+#[derive(FromMeta, Clone, Copy, PartialEq)]
+enum FileConflict {
+    PreferVar,
+    PreferFile,
+    Error,
+}
+
+/// Explicit modes of `#[from_env(percent = "...")]`.
+#[derive(FromMeta, Clone, Copy, PartialEq)]
+enum PercentMode {
+    RequireSign,
+}
+
+/// Explicit modes of `#[from_env(non_empty = "...")]`.
+#[derive(FromMeta, Clone, Copy, PartialEq)]
+enum NonEmptyMode {
+    Trim,
+}
+
+/// Modes of `#[from_env(split = "...")]`.
+#[derive(FromMeta, Clone, Copy, PartialEq)]
+enum SplitMode {
+    Whitespace,
+}
+
+/// Explicit alphabets of `#[from_env(base64 = "...")]`.
+#[derive(FromMeta, Clone, Copy, PartialEq)]
+enum Base64Alphabet {
+    Url,
+}
+
+impl EnvField {
+    /// First name of the `var` attribute, the variable of the field.
+    fn var_name(&self) -> Option<&syn::Lit> {
+        self.var.as_ref().map(|names| &names.0[0])
+    }
+
+    /// Value of the `default` attribute.
+    fn default_value(&self) -> Option<&syn::Lit> {
+        match &self.default_attr {
+            Some(Override::Explicit(default)) => Some(default),
+            _ => None,
+        }
+    }
+
+    /// Whether the field has a bare `default`, loading `Default::default()` when its variable is
+    /// not set, or none of the variables of its section for nested structs.
+    fn bare_default(&self) -> bool {
+        matches!(self.default_attr, Some(Override::Inherit))
+    }
+
+    /// Function parsing the value: `parse_with`, or the `parse` function of the `with` module.
+    fn parse_fn(&self) -> Option<syn::Path> {
+        match (&self.parse_with, &self.with) {
+            (Some(_), Some(_)) => panic!("parse_with and with can't be used together"),
+            (Some(parse_with), None) => Some(parse_with.clone()),
+            (None, Some(module)) => Some(syn::parse_quote!(#module::parse)),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(FromDeriveInput)]
+#[darling(attributes(from_env))]
+struct EnvStruct {
+    ident: syn::Ident,
+    #[darling(default)]
+    compat: Option<Compat>,
+    #[darling(default)]
+    prefix: Option<String>,
+    #[darling(default)]
+    collapse_prefix: bool,
+    #[darling(default)]
+    all_defaultable: bool,
+    #[darling(default, rename = "crate")]
+    crate_path: Option<syn::Path>,
+    #[darling(default)]
+    transparent: bool,
+    #[darling(default)]
+    diff: bool,
+    #[darling(default)]
+    merge: bool,
+    #[darling(default)]
+    check_defaults: bool,
+    #[darling(default)]
+    cli_args: bool,
+    #[darling(default)]
+    defaults_from: Option<syn::Type>,
+    #[darling(default)]
+    separator: Option<String>,
+    #[darling(default)]
+    kv_separator: Option<String>,
+    #[darling(default)]
+    lenient_bool: bool,
+    /// Variable selecting the variant of an enum with struct variants.
+    #[darling(default)]
+    tag: Option<String>,
+    #[darling(default)]
+    non_empty: Option<Override<NonEmptyMode>>,
+    /// Reads every field from the file named by `<NAME>{file_suffix}` when its variable is not set.
+    #[darling(default)]
+    file_suffix: Option<String>,
+    #[darling(default)]
+    file_conflict: Option<FileConflict>,
+    #[darling(default)]
+    trim: bool,
+    generics: syn::Generics,
+}
+
+/// Naming conventions of other crates that can be reproduced with `#[from_env(compat = "...")]`.
+#[derive(FromMeta, Clone, Copy, PartialEq)]
+enum Compat {
+    Envy,
 }
 
 #[proc_macro_derive(FromEnv, attributes(from_env))]
 pub fn from_env_proc_macro(item: TokenStream) -> TokenStream {
-    let DeriveInput { ident, data, .. } = syn::parse_macro_input!(item as syn::DeriveInput);
+    let input = syn::parse_macro_input!(item as syn::DeriveInput);
+    // Enums with unit variants are read from a single variable, as with `FromEnvValue`.
+    if let Data::Enum(data) = &input.data {
+        if data
+            .variants
+            .iter()
+            .all(|variant| matches!(variant.fields, syn::Fields::Unit))
+        {
+            let env_value = from_env_value::EnvValue::from_derive_input(&input).unwrap();
+            return from_env_value::expand(env_value).into();
+        }
+    }
+    let mut env_struct = EnvStruct::from_derive_input(&input).unwrap();
+    // Newtype tuple structs are transparent, read like the field they wrap.
+    if matches!(&input.data, Data::Struct(syn::DataStruct { fields: syn::Fields::Unnamed(fields), .. }) if fields.unnamed.len() == 1)
+    {
+        env_struct.transparent = true;
+    }
+    if env_struct.collapse_prefix && env_struct.prefix.is_none() {
+        panic!("collapse_prefix requires a struct prefix");
+    }
+    if env_struct.collapse_prefix && env_struct.compat.is_some() {
+        panic!("collapse_prefix is not supported with compat");
+    }
+    if env_struct.transparent && (env_struct.prefix.is_some() || env_struct.compat.is_some()) {
+        panic!("transparent is not supported with prefix or compat");
+    }
+    if env_struct.defaults_from.is_some() && env_struct.transparent {
+        panic!("defaults_from is not supported with transparent");
+    }
+    let DeriveInput {
+        ident, data, vis, ..
+    } = input;
     let struct_identifier = &ident;
 
     match &data {
         Data::Struct(syn::DataStruct { fields, .. }) => {
+            if env_struct.transparent && fields.len() != 1 {
+                panic!("transparent is only supported on structs with a single field");
+            }
+            // Fields of tuple structs are named after their position.
+            let positional = !env_struct.transparent && matches!(fields, syn::Fields::Unnamed(_));
             let env_fields = fields
                 .iter()
-                .map(|field| EnvField::from_field(field).unwrap())
+                .enumerate()
+                .map(|(index, field)| {
+                    check_default_attributes(field);
+                    let env_field = EnvField::from_field(field).unwrap();
+                    // Flattened type parameters are loaded as nested structs.
+                    let generic = type_parameter(&env_field.ty, &env_struct.generics).is_some()
+                        && !env_field.flatten
+                        && !env_field.no_prefix;
+                    EnvField {
+                        position: Some(index).filter(|_| positional),
+                        from_str: env_field.from_str || generic,
+                        generic,
+                        ..env_field
+                    }
+                })
                 .collect::<Vec<_>>();
+            if env_struct.generics.type_params().next().is_some()
+                && (env_struct.check_defaults || env_struct.defaults_from.is_some())
+            {
+                panic!("check_defaults and defaults_from are not supported on generic structs");
+            }
+            let generics = impl_generics(&env_fields, &env_struct);
+            let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+            // Tuple structs are built as `Self { 0: ... }`.
             let field_identifiers = env_fields
                 .iter()
-                .map(|f| f.ident.as_ref().unwrap())
+                .enumerate()
+                .map(|(index, f)| match &f.ident {
+                    Some(ident) => quote! { #ident },
+                    None => {
+                        let index = syn::Index::from(index);
+                        quote! { #index }
+                    }
+                })
                 .collect::<Vec<_>>();
             let field_loaders = env_fields
                 .iter()
-                .map(|field| generate_field_loader(field, false))
+                .map(|field| generate_field_loader(field, false, &env_struct))
                 .collect::<Vec<_>>();
             let field_loaders_with_prefix = env_fields
                 .iter()
-                .map(|field| generate_field_loader(field, true))
+                .map(|field| generate_field_loader(field, true, &env_struct))
+                .collect::<Vec<_>>();
+            let field_explains = env_fields
+                .iter()
+                .map(|field| generate_field_explain(field, false, &env_struct))
+                .collect::<Vec<_>>();
+            let field_explains_with_prefix = env_fields
+                .iter()
+                .map(|field| generate_field_explain(field, true, &env_struct))
+                .collect::<Vec<_>>();
+            let field_specs = env_fields
+                .iter()
+                .map(|field| generate_field_spec(field, false, &env_struct))
+                .collect::<Vec<_>>();
+            let field_specs_with_prefix = env_fields
+                .iter()
+                .map(|field| generate_field_spec(field, true, &env_struct))
+                .collect::<Vec<_>>();
+            if let Err(error) =
+                manifest::emit(&struct_identifier.to_string(), &env_fields, &env_struct)
+            {
+                panic!("{}", error);
+            }
+            let diff = if env_struct.diff {
+                let field_diffs = env_fields
+                    .iter()
+                    .zip(&field_identifiers)
+                    .map(|(field, identifier)| {
+                        generate_field_diff(field, identifier, false, &env_struct)
+                    })
+                    .collect::<Vec<_>>();
+                let field_diffs_with_prefix = env_fields
+                    .iter()
+                    .zip(&field_identifiers)
+                    .map(|(field, identifier)| {
+                        generate_field_diff(field, identifier, true, &env_struct)
+                    })
+                    .collect::<Vec<_>>();
+                quote! {
+                    impl #impl_generics ::derive_from_env::_inner_trait::EnvDiff for #struct_identifier #type_generics #where_clause {
+                        fn env_diff(&self, other: &Self) -> Vec<::derive_from_env::FieldDiff> {
+                            let mut diffs = Vec::new();
+                            #(#field_diffs)*
+                            diffs
+                        }
+                        fn env_diff_with_prefix(&self, other: &Self, prefix: &str) -> Vec<::derive_from_env::FieldDiff> {
+                            let mut diffs = Vec::new();
+                            #(#field_diffs_with_prefix)*
+                            diffs
+                        }
+                    }
+                    impl #impl_generics #struct_identifier #type_generics #where_clause {
+                        /// Fields differing between `self` and `other`, named as in `env_vars()`.
+                        pub fn diff(&self, other: &Self) -> Vec<::derive_from_env::FieldDiff> {
+                            ::derive_from_env::_inner_trait::EnvDiff::env_diff(self, other)
+                        }
+                        /// Fields differing between `self` and `other`, named as in `env_vars_with_prefix(prefix)`.
+                        pub fn diff_with_prefix(&self, other: &Self, prefix: &str) -> Vec<::derive_from_env::FieldDiff> {
+                            ::derive_from_env::_inner_trait::EnvDiff::env_diff_with_prefix(self, other, prefix)
+                        }
+                    }
+                }
+            } else {
+                quote! {}
+            };
+            let default_checks = env_fields
+                .iter()
+                .map(|field| generate_default_check(field, &env_struct))
                 .collect::<Vec<_>>();
+            let default_check_tests = if env_struct.check_defaults {
+                let module = format_ident!(
+                    "__from_env_default_checks_{}",
+                    from_env_value::upper_snake_case(&struct_identifier.to_string()).to_lowercase()
+                );
+                let tests = env_fields
+                    .iter()
+                    .zip(&default_checks)
+                    .enumerate()
+                    .filter(|(_, (field, _))| field.default_value().is_some())
+                    .map(|(index, (field, check))| {
+                        let test_name = field.ident.clone().unwrap_or_else(|| format_ident!("field_{}", index));
+                        let field_name = field_name(field, &env_struct);
+                        quote! {
+                            #[test]
+                            fn #test_name() {
+                                use std::str::FromStr;
+                                let check = || -> Result<(), ::derive_from_env::FromEnvError> {
+                                    #check
+                                    Ok(())
+                                };
+                                if let Err(error) = check() {
+                                    panic!("invalid default of {}.{}: {}", stringify!(#struct_identifier), #field_name, error);
+                                }
+                            }
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                quote! {
+                    #[cfg(test)]
+                    #[allow(non_snake_case)]
+                    mod #module {
+                        use super::*;
+                        #(#tests)*
+                    }
+                }
+            } else {
+                quote! {}
+            };
+            let cli_args = if env_struct.cli_args {
+                let field_args = env_fields
+                    .iter()
+                    .zip(&field_identifiers)
+                    .map(|(field, identifier)| {
+                        generate_field_cli_args(field, identifier, &env_struct)
+                    })
+                    .collect::<Vec<_>>();
+                quote! {
+                    impl #impl_generics ::derive_from_env::_inner_trait::CliArgs for #struct_identifier #type_generics #where_clause {
+                        fn cli_args(&self, path: &str, style: ::derive_from_env::ArgStyle, redact: bool, args: &mut Vec<String>) {
+                            #(#field_args)*
+                        }
+                    }
+                    impl #impl_generics #struct_identifier #type_generics #where_clause {
+                        /// Renders the fields as command-line flags named after their kebab-cased paths.
+                        pub fn to_cli_args(&self, style: ::derive_from_env::ArgStyle) -> Vec<String> {
+                            let mut args = Vec::new();
+                            ::derive_from_env::_inner_trait::CliArgs::cli_args(self, "", style, false, &mut args);
+                            args
+                        }
+                    }
+                }
+            } else {
+                quote! {}
+            };
+            let merge = if env_struct.merge {
+                let field_merges = env_fields
+                    .iter()
+                    .zip(&field_identifiers)
+                    .map(|(field, identifier)| generate_field_merge(field, identifier, &env_struct))
+                    .collect::<Vec<_>>();
+                quote! {
+                    impl #impl_generics ::derive_from_env::_inner_trait::EnvMerge for #struct_identifier #type_generics #where_clause {
+                        fn env_merge(self, other: Self, policy: ::derive_from_env::MergePolicy) -> Self {
+                            use std::str::FromStr;
+                            Self {
+                                #(
+                                    #field_identifiers: #field_merges
+                                ),*
+                            }
+                        }
+                    }
+                    impl #impl_generics #struct_identifier #type_generics #where_clause {
+                        /// Combines `self` and `other` field by field according to `policy`.
+                        pub fn merge(self, other: Self, policy: ::derive_from_env::MergePolicy) -> Self {
+                            ::derive_from_env::_inner_trait::EnvMerge::env_merge(self, other, policy)
+                        }
+                    }
+                }
+            } else {
+                quote! {}
+            };
 
-            quote! {
-                impl ::derive_from_env::_inner_trait::FromEnv for #struct_identifier {
-                    fn from_env() -> Result<Self, ::derive_from_env::FromEnvError> {
+            let defaults_trait = if env_struct.defaults_from.is_some() {
+                let trait_identifier = defaults_trait(&env_struct);
+                let methods = env_fields.iter().filter(|field| has_trait_default(field)).map(|field| {
+                    let field_type = &field.ty;
+                    let method = format_ident!("default_{}", field_name(field, &env_struct));
+                    let doc = format!("Default of `{}` when its variable is not set, unless it has a `default` attribute.", field_name(field, &env_struct));
+                    quote! {
+                        #[doc = #doc]
+                        fn #method() -> Option<#field_type> {
+                            None
+                        }
+                    }
+                });
+                let doc = format!(
+                    "Defaults of the fields of [`{}`], see its `defaults_from` attribute.",
+                    struct_identifier
+                );
+                quote! {
+                    #[doc = #doc]
+                    #vis trait #trait_identifier {
+                        #(#methods)*
+                    }
+                }
+            } else {
+                quote! {}
+            };
+
+            let inherent_impl = generate_inherent_impl(
+                struct_identifier,
+                &generics,
+                env_struct.check_defaults.then_some(&default_checks),
+            );
+            let expanded = quote! {
+                #defaults_trait
+                impl #impl_generics ::derive_from_env::_inner_trait::FromEnv for #struct_identifier #type_generics #where_clause {
+                    fn from_source(source: &dyn ::derive_from_env::EnvSource) -> Result<Self, ::derive_from_env::FromEnvError> {
                         use std::str::FromStr;
                         Ok(Self {
                             #(
@@ -54,7 +589,7 @@ pub fn from_env_proc_macro(item: TokenStream) -> TokenStream {
                             ),*
                         })
                     }
-                    fn from_env_with_prefix(prefix: &str) -> Result<Self, ::derive_from_env::FromEnvError> {
+                    fn from_source_with_prefix(source: &dyn ::derive_from_env::EnvSource, prefix: &str) -> Result<Self, ::derive_from_env::FromEnvError> {
                         use std::str::FromStr;
                         Ok(Self {
                             #(
@@ -62,21 +597,126 @@ pub fn from_env_proc_macro(item: TokenStream) -> TokenStream {
                             ),*
                         })
                     }
-                }
-                impl #struct_identifier {
-                    pub fn from_env() -> Result<Self, ::derive_from_env::FromEnvError> {
-                        <Self as ::derive_from_env::_inner_trait::FromEnv>::from_env()
+                    fn env_vars() -> Vec<::derive_from_env::EnvVarSpec> {
+                        let mut specs = Vec::new();
+                        #(#field_specs)*
+                        specs
+                    }
+                    fn env_vars_with_prefix(prefix: &str) -> Vec<::derive_from_env::EnvVarSpec> {
+                        let mut specs = Vec::new();
+                        #(#field_specs_with_prefix)*
+                        specs
                     }
-                    pub fn from_env_with_prefix(prefix: &str) -> Result<Self, ::derive_from_env::FromEnvError> {
-                        <Self as ::derive_from_env::_inner_trait::FromEnv>::from_env_with_prefix(prefix)
+                    fn explain_from_source(source: &dyn ::derive_from_env::EnvSource, field_path: &str) -> Option<::derive_from_env::ResolutionPlan> {
+                        #(#field_explains)*
+                        None
+                    }
+                    fn explain_from_source_with_prefix(source: &dyn ::derive_from_env::EnvSource, field_path: &str, prefix: &str) -> Option<::derive_from_env::ResolutionPlan> {
+                        #(#field_explains_with_prefix)*
+                        None
                     }
                 }
-            }.into()
+                #inherent_impl
+                #diff
+                #merge
+                #cli_args
+                #default_check_tests
+            };
+            crate_path::replace(expanded, env_struct.crate_path.as_ref()).into()
         }
+        Data::Enum(_) => tagged_enum::expand(&data, &env_struct).into(),
         _ => unimplemented!(),
     }
 }
 
+/// Generates the inherent methods of a derived type, forwarding to its `FromEnv` implementation.
+/// `check_defaults()` is only generated for types with the `check_defaults` attribute.
+fn generate_inherent_impl(
+    identifier: &syn::Ident,
+    generics: &syn::Generics,
+    default_checks: Option<&Vec<proc_macro2::TokenStream>>,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let check_defaults = default_checks.map(|default_checks| {
+        quote! {
+            /// Parses the `default` attributes of the fields, failing on the first invalid one.
+            pub fn check_defaults() -> Result<(), ::derive_from_env::FromEnvError> {
+                use std::str::FromStr;
+                #(#default_checks)*
+                Ok(())
+            }
+        }
+    });
+    quote! {
+        impl #impl_generics #identifier #type_generics #where_clause {
+            pub fn from_env() -> Result<Self, ::derive_from_env::FromEnvError> {
+                <Self as ::derive_from_env::_inner_trait::FromEnv>::from_env()
+            }
+            pub fn from_env_with_prefix(prefix: &str) -> Result<Self, ::derive_from_env::FromEnvError> {
+                <Self as ::derive_from_env::_inner_trait::FromEnv>::from_env_with_prefix(prefix)
+            }
+            /// Loads the struct with the prefix given by the name of the executable, upper-snake-cased.
+            pub fn from_env_with_exe_prefix() -> Result<Self, ::derive_from_env::FromEnvError> {
+                <Self as ::derive_from_env::_inner_trait::FromEnv>::from_env_with_exe_prefix()
+            }
+            /// Loads the struct from `source` with the prefix given by the name of its executable.
+            pub fn from_source_with_exe_prefix(source: &dyn ::derive_from_env::EnvSource) -> Result<Self, ::derive_from_env::FromEnvError> {
+                <Self as ::derive_from_env::_inner_trait::FromEnv>::from_source_with_exe_prefix(source)
+            }
+            /// Loads the struct from the variables of `source` instead of the process environment.
+            pub fn from_source(source: &dyn ::derive_from_env::EnvSource) -> Result<Self, ::derive_from_env::FromEnvError> {
+                <Self as ::derive_from_env::_inner_trait::FromEnv>::from_source(source)
+            }
+            /// Loads the struct from the variables of `source` with the given prefix.
+            pub fn from_source_with_prefix(source: &dyn ::derive_from_env::EnvSource, prefix: &str) -> Result<Self, ::derive_from_env::FromEnvError> {
+                <Self as ::derive_from_env::_inner_trait::FromEnv>::from_source_with_prefix(source, prefix)
+            }
+            /// Variables read by `from_env`.
+            pub fn env_vars() -> Vec<::derive_from_env::EnvVarSpec> {
+                <Self as ::derive_from_env::_inner_trait::FromEnv>::env_vars()
+            }
+            /// Variables read by `from_env_with_prefix`.
+            pub fn env_vars_with_prefix(prefix: &str) -> Vec<::derive_from_env::EnvVarSpec> {
+                <Self as ::derive_from_env::_inner_trait::FromEnv>::env_vars_with_prefix(prefix)
+            }
+            /// Locations consulted for the field at `field_path` (e.g. `database.host`), in order,
+            /// with their current values, `None` for unknown fields.
+            pub fn explain(field_path: &str) -> Option<::derive_from_env::ResolutionPlan> {
+                <Self as ::derive_from_env::_inner_trait::FromEnv>::explain_from_source(&::derive_from_env::ProcessEnv, field_path)
+            }
+            /// Locations consulted by `from_source` for the field at `field_path`.
+            pub fn explain_from_source(source: &dyn ::derive_from_env::EnvSource, field_path: &str) -> Option<::derive_from_env::ResolutionPlan> {
+                <Self as ::derive_from_env::_inner_trait::FromEnv>::explain_from_source(source, field_path)
+            }
+            #check_defaults
+            /// Flags suspicious values among the variables read by `from_env`.
+            pub fn lint_env() -> Vec<::derive_from_env::EnvWarning> {
+                ::derive_from_env::lint_env(&Self::env_vars())
+            }
+            /// Flags suspicious values among the variables read by `from_env_with_prefix`.
+            pub fn lint_env_with_prefix(prefix: &str) -> Vec<::derive_from_env::EnvWarning> {
+                ::derive_from_env::lint_env(&Self::env_vars_with_prefix(prefix))
+            }
+        }
+    }
+}
+
+/// Implements `FromStr` and `Display` for enums with unit variants, see `derive_from_env::FromEnvValue`.
+#[proc_macro_derive(FromEnvValue, attributes(from_env))]
+pub fn from_env_value_proc_macro(item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as syn::DeriveInput);
+    let env_value = from_env_value::EnvValue::from_derive_input(&input).unwrap();
+    from_env_value::expand(env_value).into()
+}
+
+/// Runs a test with the given environment, see `derive_from_env::test_util`.
+#[proc_macro_attribute]
+pub fn from_env_test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let env = syn::parse_macro_input!(attr as from_env_test::TestEnv);
+    let item = syn::parse_macro_input!(item as syn::ItemFn);
+    from_env_test::expand(env, item).into()
+}
+
 fn impl_from_str(ty: &Type) -> bool {
     matches!(ty,
         Type::Path(type_path) if type_path.path.segments.iter().all(|seg|
@@ -91,117 +731,1986 @@ fn impl_from_str(ty: &Type) -> bool {
     )
 }
 
-fn extract_inner_type_if_option(ty: &Type) -> Option<&Type> {
-    if let Type::Path(type_path) = ty {
-        if type_path.qself.is_none() && type_path.path.segments.len() == 1 {
-            let segment = &type_path.path.segments[0];
-            if segment.ident == "Option" {
-                if let PathArguments::AngleBracketed(ref args) = segment.arguments {
-                    if args.args.len() == 1 {
-                        if let GenericArgument::Type(ref inner_type) = args.args[0] {
-                            return Some(inner_type);
-                        }
-                    }
-                }
-            }
+/// Whether the type is `PhantomData`, or an `Option` of it: such fields read no variable.
+fn is_phantom_data(ty: &Type) -> bool {
+    let ty = extract_inner_type_if_option(ty).unwrap_or(ty);
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|segment| segment.ident == "PhantomData"))
+}
+
+/// Whether the type is `std::time::Duration`, parsed from values like `1h30m` with the `duration`
+/// feature of the crate.
+fn is_duration(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let segments = type_path
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>();
+    matches!(
+        segments
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .as_slice(),
+        ["Duration"] | ["time", "Duration"] | ["std" | "core", "time", "Duration"]
+    )
+}
+
+/// Whether the type is `OsString`, read as-is without requiring UTF-8.
+fn is_os_string(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|segment| segment.ident == "OsString"))
+}
+
+/// Whether the type is a primitive integer or float.
+fn is_number(ty: &Type) -> bool {
+    const NUMBERS: [&str; 14] = [
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+        "f32", "f64",
+    ];
+    matches!(ty, Type::Path(type_path) if NUMBERS.iter().any(|number| type_path.path.is_ident(number)))
+}
+
+/// Parses the `range` attribute, whose bounds must be number literals.
+fn parse_range_attribute(range: &str) -> syn::ExprRange {
+    let Ok(parsed) = syn::parse_str::<syn::ExprRange>(range) else {
+        panic!(
+            "invalid range {:?}, expected e.g. \"1..=65535\", \"0..10\" or \"1024..\"",
+            range
+        );
+    };
+    let is_number = |bound: &syn::Expr| match bound {
+        syn::Expr::Lit(lit) => matches!(lit.lit, syn::Lit::Int(_) | syn::Lit::Float(_)),
+        syn::Expr::Unary(unary) => {
+            matches!(unary.op, syn::UnOp::Neg(_))
+                && matches!(&*unary.expr, syn::Expr::Lit(lit) if matches!(lit.lit, syn::Lit::Int(_) | syn::Lit::Float(_)))
         }
+        _ => false,
+    };
+    let bounds = parsed.start.iter().chain(&parsed.end).collect::<Vec<_>>();
+    if bounds.is_empty() || !bounds.into_iter().all(|bound| is_number(bound)) {
+        panic!("invalid range {:?}, the bounds must be numbers", range);
     }
-    None
+    parsed
 }
 
-fn generate_field_loader(field: &EnvField, prefix: bool) -> proc_macro2::TokenStream {
-    let field_name = field.ident.as_ref().unwrap().to_string();
-    let field_type = &field.ty;
-    let inner_field_type = extract_inner_type_if_option(field_type);
-    let default_value = &field.default;
-    let no_prefix = field.no_prefix;
-    let from_str = field.from_str;
-    let var_name = &field.var;
+/// Checks the pattern of the `matches` attribute, so that invalid ones fail to compile.
+#[cfg(feature = "regex")]
+fn check_pattern(pattern: &str) {
+    if let Err(error) = regex_syntax::Parser::new().parse(pattern) {
+        panic!("invalid matches pattern {:?}: {}", pattern, error);
+    }
+}
 
-    let env_var_name = if prefix {
-        quote! { format!("{}_{}", prefix, #field_name.to_uppercase()) }
-    } else {
-        quote! { #field_name.to_uppercase() }
+#[cfg(not(feature = "regex"))]
+fn check_pattern(_pattern: &str) {
+    panic!("matches requires the regex feature of derive_from_env");
+}
+
+fn is_float(ty: &Type) -> bool {
+    matches!(ty,
+        Type::Path(type_path) if type_path.path.segments.last().is_some_and(|seg|
+            seg.ident == "f32" || seg.ident == "f64"
+        )
+    )
+}
+
+/// The type of the bounds of a `Range` or `RangeInclusive`, and whether it is inclusive.
+fn range_bounds(ty: &Type) -> Option<(&Type, bool)> {
+    let Type::Path(type_path) = ty else {
+        return None;
     };
-    if let Some(field_type) = inner_field_type {
-        if !(impl_from_str(field_type) || from_str) {
-            panic!("Inner type of Option must implement FromStr");
-        }
-        if default_value.is_some() {
-            panic!("Default value is not supported for Option fields");
-        }
-        if let Some(var_name) = var_name {
-            quote! {
-                std::env::var(#var_name.to_string())
-                    .ok()
-                    .map(|s| #field_type::from_str(&s))
-                    .transpose()
-                    .map_err(|_| ::derive_from_env::FromEnvError::ParsingFailure{
-                        var_name: #var_name.to_string(),
-                        str_value: std::env::var(#var_name.to_string()).unwrap(),
-                        expected_type: stringify!(#field_type).to_string()
-                    })?
-            }
-        } else {
-            quote! {
-                std::env::var(#env_var_name)
-                    .ok()
-                    .map(|s| #field_type::from_str(&s))
-                    .transpose()
-                    .map_err(|_| ::derive_from_env::FromEnvError::ParsingFailure{
-                        var_name: #env_var_name.to_string(),
-                        str_value: std::env::var(#env_var_name).unwrap(),
-                        expected_type: stringify!(#field_type).to_string()
-                    })?
-            }
+    let segment = type_path.path.segments.last()?;
+    let inclusive = match segment.ident.to_string().as_str() {
+        "Range" => false,
+        "RangeInclusive" => true,
+        _ => return None,
+    };
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(bound_type) => Some((bound_type, inclusive)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The element type of a `Vec`, `HashSet`, `BTreeSet` or array, read from a list of separated
+/// values.
+fn list_element(ty: &Type) -> Option<&Type> {
+    if let Type::Array(array) = ty {
+        return Some(&array.elem);
+    }
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Vec" && !is_set(ty) {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(element_type) => Some(element_type),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The component types of a tuple of 2 or 3 elements, read from separated components.
+fn tuple_elements(ty: &Type) -> Option<Vec<&Type>> {
+    match ty {
+        Type::Tuple(tuple) if (2..=3).contains(&tuple.elems.len()) => {
+            Some(tuple.elems.iter().collect())
         }
-    } else if impl_from_str(field_type) || from_str || inner_field_type.is_some() {
-        match (default_value, var_name) {
-            (Some(default), Some(var_name)) => {
-                quote! {
-                    #field_type::from_str(
-                        &std::env::var(#var_name.to_string())
-                            .unwrap_or_else(|_| #default.to_string())
-                    ).map_err(|_| ::derive_from_env::FromEnvError::ParsingFailure{
-                        var_name: #var_name.to_string(),
-                        str_value: std::env::var(#var_name.to_string()).unwrap_or_else(|_| #default.to_string()),
-                        expected_type: stringify!(#field_type).to_string()
-                    })?
-                }
-            }
-            (Some(default), None) => {
-                quote! {
-                    #field_type::from_str(
-                        &std::env::var(#env_var_name)
-                            .unwrap_or_else(|_| #default.to_string())
-                    ).map_err(|_|::derive_from_env::FromEnvError::ParsingFailure {
-                        var_name: #env_var_name.to_string(),
-                        str_value: std::env::var(#env_var_name).unwrap_or_else(|_| #default.to_string()),
-                        expected_type: stringify!(#field_type).to_string()
-                    })?
+        _ => None,
+    }
+}
+
+/// Whether the type is a `Vec`, whose items can be extended by `append_from` or loaded one by one.
+fn is_vec(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|segment| segment.ident == "Vec"))
+}
+
+/// Whether the type is a `HashSet` or a `BTreeSet`.
+fn is_set(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|segment| segment.ident == "HashSet" || segment.ident == "BTreeSet"))
+}
+
+/// The key and value types of a `HashMap` or `BTreeMap`, read from separated `key=value` entries.
+fn map_types(ty: &Type) -> Option<(&Type, &Type)> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "HashMap" && segment.ident != "BTreeMap" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 2 => {
+            match (&args.args[0], &args.args[1]) {
+                (GenericArgument::Type(key_type), GenericArgument::Type(value_type)) => {
+                    Some((key_type, value_type))
                 }
+                _ => None,
             }
-            (None, Some(var_name)) => {
-                quote! {
-                    #field_type::from_str(&std::env::var(#var_name.to_string())
-                        .map_err(|_| ::derive_from_env::FromEnvError::MissingEnvVar{var_name: #var_name.to_string()})?)
-                        .map_err(|_| ::derive_from_env::FromEnvError::ParsingFailure{
-                            var_name: #var_name.to_string(),
-                            str_value: std::env::var(#var_name.to_string()).unwrap(),
-                            expected_type: stringify!(#field_type).to_string()
+        }
+        _ => None,
+    }
+}
+
+fn is_path_buf_vec(ty: &Type) -> bool {
+    matches!(ty,
+        Type::Path(type_path) if type_path.path.segments.last().is_some_and(|seg|
+            seg.ident == "Vec" && matches!(&seg.arguments,
+                PathArguments::AngleBracketed(args) if args.args.len() == 1 && matches!(&args.args[0],
+                    GenericArgument::Type(Type::Path(inner)) if inner.path.segments.last().is_some_and(|seg| seg.ident == "PathBuf")
+                )
+            )
+        )
+    )
+}
+
+/// Separator of the items of a `Vec` field: its `separator` attribute, or the struct's, or `,`.
+fn list_separator(field: &EnvField, env_struct: &EnvStruct) -> String {
+    // Items split on whitespace are joined with a space.
+    if field.split == Some(SplitMode::Whitespace) {
+        return " ".to_string();
+    }
+    let separator = field
+        .separator
+        .as_ref()
+        .or(env_struct.separator.as_ref())
+        .map_or(",", String::as_str);
+    if separator.is_empty() {
+        panic!("separator can't be empty");
+    }
+    separator.to_string()
+}
+
+/// Separator of the keys and values of a map field: its `kv_separator` attribute, or the struct's,
+/// or `=`.
+fn kv_separator(field: &EnvField, env_struct: &EnvStruct) -> String {
+    let separator = field
+        .kv_separator
+        .as_ref()
+        .or(env_struct.kv_separator.as_ref())
+        .map_or("=", String::as_str);
+    if separator.is_empty() {
+        panic!("kv_separator can't be empty");
+    }
+    separator.to_string()
+}
+
+/// Whether the type is `Lazy<T>`, for `#[from_env(lazy)]` fields.
+fn is_lazy(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|segment| segment.ident == "Lazy"))
+}
+
+/// The type wrapped in a `Box`, an `Rc` or an `Arc`.
+fn pointer_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if !matches!(segment.ident.to_string().as_str(), "Box" | "Rc" | "Arc") {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner_type) => Some(inner_type),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The type parsed for a `secrecy` type: `String` for `SecretString` and `T` for `SecretBox<T>`.
+fn secret_inner(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    match (segment.ident.to_string().as_str(), &segment.arguments) {
+        ("SecretString", PathArguments::None) => Some(syn::parse_quote!(String)),
+        ("SecretBox", PathArguments::AngleBracketed(args)) if args.args.len() == 1 => {
+            match &args.args[0] {
+                GenericArgument::Type(inner_type) => Some(inner_type.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The type wrapped in `Zeroizing`, for `#[from_env(zeroize)]` fields.
+fn zeroizing_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args)
+            if segment.ident == "Zeroizing" && args.args.len() == 1 =>
+        {
+            match &args.args[0] {
+                GenericArgument::Type(inner_type) => Some(inner_type),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Suffix and conflict policy of the file the value of the field is read from when its variable is
+/// not set, with the `file` attribute or the `file_suffix` of the struct.
+fn file_lookup(
+    field: &EnvField,
+    env_struct: &EnvStruct,
+) -> Option<(String, proc_macro2::TokenStream)> {
+    if field.file && field.no_file {
+        panic!("file and no_file can't be used together");
+    }
+    let single_variable = !(field.path_list
+        || is_nested(field)
+        || optional_section(field).is_some()
+        || field.flag
+        || field.indexed
+        || field.count_var.is_some());
+    if field.file && !single_variable {
+        panic!("file is only supported on fields read from a single variable, which are not flags");
+    }
+    // The struct setting leaves out the fields it can't apply to, such as `OsString` ones.
+    let value_type = extract_inner_type_if_option(&field.ty).unwrap_or(&field.ty);
+    let inherited = env_struct.file_suffix.is_some()
+        && !field.no_file
+        && single_variable
+        && !is_os_string(value_type)
+        && !is_phantom_data(&field.ty);
+    if !field.file && !inherited {
+        return None;
+    }
+    let suffix = env_struct
+        .file_suffix
+        .clone()
+        .unwrap_or("_FILE".to_string());
+    Some((suffix, file_conflict(env_struct)))
+}
+
+/// Whether the value of the field is trimmed before parsing, with the `trim` attribute of the field
+/// or of the struct.
+fn trims(field: &EnvField, env_struct: &EnvStruct) -> bool {
+    if field.trim && field.raw {
+        panic!("trim and raw can't be used together");
+    }
+    if field.trim
+        && (field.path_list
+            || is_nested(field)
+            || field.flag
+            || field.indexed
+            || field.count_var.is_some())
+    {
+        panic!("trim is only supported on fields read from a single variable, which are not flags");
+    }
+    // The struct setting leaves out the fields it can't apply to, such as `OsString` ones.
+    let value_type = extract_inner_type_if_option(&field.ty).unwrap_or(&field.ty);
+    let inherited = env_struct.trim
+        && !field.raw
+        && !(field.path_list
+            || is_nested(field)
+            || optional_section(field).is_some()
+            || field.flag
+            || field.indexed
+            || field.count_var.is_some()
+            || is_os_string(value_type)
+            || is_phantom_data(&field.ty));
+    field.trim || inherited
+}
+
+/// Policy of the struct when both a variable and its file are set.
+fn file_conflict(env_struct: &EnvStruct) -> proc_macro2::TokenStream {
+    match env_struct.file_conflict.unwrap_or(FileConflict::PreferVar) {
+        FileConflict::PreferVar => {
+            quote! { ::derive_from_env::_inner_helpers::FileConflict::PreferVar }
+        }
+        FileConflict::PreferFile => {
+            quote! { ::derive_from_env::_inner_helpers::FileConflict::PreferFile }
+        }
+        FileConflict::Error => quote! { ::derive_from_env::_inner_helpers::FileConflict::Error },
+    }
+}
+
+/// Whether the values of the field are hidden, by the `secret` attribute or a `secrecy` type.
+fn is_secret(field: &EnvField) -> bool {
+    let value_type = extract_inner_type_if_option(&field.ty).unwrap_or(&field.ty);
+    field.secret || secret_inner(value_type).is_some()
+}
+
+/// Whether the type is `str`, read as a `String` behind a pointer (`Arc<str>`).
+fn is_str(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.is_ident("str"))
+}
+
+/// Whether values of the type are parsed from a single variable without attributes, pointers to
+/// such types included (`Arc<String>`, `Box<str>`).
+fn is_value_type(ty: &Type) -> bool {
+    impl_from_str(ty)
+        || range_bounds(ty).is_some()
+        || list_element(ty).is_some()
+        || map_types(ty).is_some()
+        || tuple_elements(ty).is_some()
+        || is_duration(ty)
+        || is_os_string(ty)
+        || secret_inner(ty).is_some()
+        || zeroizing_inner(ty).is_some()
+        || pointer_inner(ty)
+            .is_some_and(|inner_type| is_str(inner_type) || is_value_type(inner_type))
+}
+
+/// Whether the field is loaded as a nested struct rather than parsed from a single variable.
+fn is_nested(field: &EnvField) -> bool {
+    let field_type = &field.ty;
+    extract_inner_type_if_option(field_type).is_none()
+        && !field.path_list
+        && !is_phantom_data(field_type)
+        && (field.flatten
+            || !(is_value_type(field_type)
+                || document_format(field).is_some()
+                || field.from.is_some()
+                || field.try_from.is_some()
+                || field.from_str
+                || field.parse_fn().is_some()
+                || field.default_value().is_some()
+                || field.default_fn.is_some()
+                || field.var.is_some()))
+}
+
+/// The content type of a flattened `Option` field, whose section is loaded only when set.
+fn optional_section(field: &EnvField) -> Option<&Type> {
+    extract_inner_type_if_option(&field.ty).filter(|_| field.flatten && !field.path_list)
+}
+
+/// Renders a type the way it is written, e.g. `Vec<PathBuf>` rather than `Vec < PathBuf >`.
+fn type_name(ty: &Type) -> String {
+    quote!(#ty)
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace(" < ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace(" ;", ";")
+}
+
+/// Type named in the `EnvVarSpec` and the manifest entry of a scalar field: its `type_name`
+/// attribute, or its type (the content type of `Option` fields).
+fn field_type_name(field: &EnvField) -> String {
+    match &field.type_name {
+        Some(name) => name.clone(),
+        None => type_name(extract_inner_type_if_option(&field.ty).unwrap_or(&field.ty)),
+    }
+}
+
+/// Expression of the type named in the parsing failures of `ty`, replaced by the `type_name`
+/// attribute of the field if any.
+fn expected_type(field: &EnvField, ty: &Type) -> proc_macro2::TokenStream {
+    match &field.type_name {
+        Some(name) => quote! { #name },
+        // Type parameters are named after the type they are instantiated with.
+        None if field.generic => quote! { ::std::any::type_name::<#ty>() },
+        None => quote! { stringify!(#ty) },
+    }
+}
+
+/// Fails on a field given both a bare `default` and `default = "..."`, which darling would only
+/// report as a duplicate field.
+fn check_default_attributes(field: &syn::Field) {
+    let defaults = field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("from_env"))
+        .filter_map(|attr| {
+            attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+            )
+            .ok()
+        })
+        .flatten()
+        .filter(|meta| meta.path().is_ident("default"))
+        .count();
+    if defaults > 1 {
+        panic!("default is given twice: use either a bare `default`, for `Default::default()`, or `default = \"...\"`, parsed like the variable");
+    }
+}
+
+/// The type parameter of `generics` which is `ty`, or the content of `ty` if an `Option`.
+fn type_parameter<'a>(ty: &Type, generics: &'a syn::Generics) -> Option<&'a syn::Ident> {
+    let ty = extract_inner_type_if_option(ty).unwrap_or(ty);
+    generics
+        .type_params()
+        .map(|param| &param.ident)
+        .find(|ident| matches!(ty, Type::Path(type_path) if type_path.qself.is_none() && type_path.path.is_ident(*ident)))
+}
+
+/// The generics of the struct, with a bound on the type parameters of its fields: `FromStr` for
+/// values, `FromEnv` (and the traits of the struct attributes) for flattened structs.
+fn impl_generics(fields: &[EnvField], env_struct: &EnvStruct) -> syn::Generics {
+    let mut generics = env_struct.generics.clone();
+    let bounds = fields
+        .iter()
+        .filter_map(|field| {
+            let ident = type_parameter(&field.ty, &env_struct.generics)?;
+            let bound = if field.generic {
+                quote! { ::std::str::FromStr }
+            } else {
+                let diff = env_struct
+                    .diff
+                    .then(|| quote! { + ::derive_from_env::_inner_trait::EnvDiff });
+                let merge = env_struct
+                    .merge
+                    .then(|| quote! { + ::derive_from_env::_inner_trait::EnvMerge });
+                let cli_args = env_struct
+                    .cli_args
+                    .then(|| quote! { + ::derive_from_env::_inner_trait::CliArgs });
+                quote! { ::derive_from_env::FromEnv #diff #merge #cli_args }
+            };
+            Some(syn::parse_quote! { #ident: #bound })
+        })
+        .collect::<Vec<syn::WherePredicate>>();
+    generics.make_where_clause().predicates.extend(bounds);
+    generics
+}
+
+fn extract_inner_type_if_option(ty: &Type) -> Option<&Type> {
+    if let Type::Path(type_path) = ty {
+        if type_path.qself.is_none() && type_path.path.segments.len() == 1 {
+            let segment = &type_path.path.segments[0];
+            if segment.ident == "Option" {
+                if let PathArguments::AngleBracketed(ref args) = segment.arguments {
+                    if args.args.len() == 1 {
+                        if let GenericArgument::Type(ref inner_type) = args.args[0] {
+                            return Some(inner_type);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Format of the documents parsed by the `json` and `format` attributes of the field.
+fn document_format(field: &EnvField) -> Option<&str> {
+    match (field.json, field.format.as_deref()) {
+        (true, Some(_)) => panic!("json and format can't be used together"),
+        (true, None) => Some("json"),
+        (false, Some(format @ ("json" | "yaml" | "toml"))) => Some(format),
+        (false, Some(format)) => panic!(
+            "unsupported format {:?}, expected \"json\", \"yaml\" or \"toml\"",
+            format
+        ),
+        (false, None) => None,
+    }
+}
+
+/// Generates an expression deserializing `parsed_value: &str` as a `format` document into `ty`,
+/// failures giving the message of the deserializer, whatever the format.
+fn generate_document_parser(format: &str, ty: &Type) -> proc_macro2::TokenStream {
+    let parse = format_ident!("parse_{}", format);
+    let format_name = format.to_uppercase();
+    let field_type_name = type_name(ty);
+    quote! {
+        ::derive_from_env::_inner_helpers::#parse::<#ty>(parsed_value).map_err(|error| {
+            ::derive_from_env::FromEnvError::ParsingFailure {
+                var_name: var_name.clone(),
+                str_value: str_value.clone(),
+                origin: origin.clone(),
+                expected_type: format!("{} {} ({})", #format_name, #field_type_name, error),
+            }
+        })?
+    }
+}
+
+/// Generates the parser of the value of the field, failures of `secret` fields hiding the value.
+fn generate_field_parser(
+    field: &EnvField,
+    field_type: &Type,
+    env_struct: &EnvStruct,
+) -> proc_macro2::TokenStream {
+    let parser = generate_parser(field, field_type, env_struct);
+    // `secrecy` types already hide the value.
+    if !field.secret || secret_inner(field_type).is_some() {
+        return parser;
+    }
+    quote! {
+        #parser.map_err(|error| ::derive_from_env::_inner_helpers::redact_value(error, &str_value))
+    }
+}
+
+/// Generates an expression parsing the `str_value: String` read from `var_name: String` (coming
+/// from `origin: Origin`) into `field_type`, evaluating to `Result<field_type, FromEnvError>`.
+fn generate_parser(
+    field: &EnvField,
+    field_type: &Type,
+    env_struct: &EnvStruct,
+) -> proc_macro2::TokenStream {
+    // `Zeroizing` values are parsed as the type they wrap.
+    if let Some(inner_type) = zeroizing_inner(field_type) {
+        let is_string =
+            matches!(inner_type, Type::Path(type_path) if type_path.path.is_ident("String"));
+        let is_bytes = is_vec(inner_type)
+            && list_element(inner_type).is_some_and(|element_type| matches!(element_type, Type::Path(type_path) if type_path.path.is_ident("u8")));
+        if !is_string && !is_bytes {
+            panic!("zeroize is only supported on Zeroizing<String> and Zeroizing<Vec<u8>> fields");
+        }
+        let parser = generate_parser(field, inner_type, env_struct);
+        return quote! { #parser.map(::derive_from_env::zeroize::Zeroizing::new) };
+    }
+    // `secrecy` types are parsed as the type they wrap, failures hiding the value.
+    if let Some(inner_type) = secret_inner(field_type) {
+        if field.default_value().is_some() {
+            panic!("default is not supported on secrecy types, the secret would be in the code");
+        }
+        let parser = generate_parser(field, &inner_type, env_struct);
+        return quote! {
+            #parser
+                .map_err(|error| ::derive_from_env::_inner_helpers::redact_value(error, &str_value))
+                .map(<#field_type as ::derive_from_env::_inner_helpers::FromSecret<#inner_type>>::from_secret)
+        };
+    }
+    // Pointers are parsed as the type they wrap, `str` being read as a `String`.
+    if let Some(inner_type) = pointer_inner(field_type) {
+        if field.from.is_none() && field.try_from.is_none() {
+            let string_type: Type = syn::parse_quote!(String);
+            let inner_type = if is_str(inner_type) {
+                &string_type
+            } else {
+                inner_type
+            };
+            let parser = generate_parser(field, inner_type, env_struct);
+            return quote! {
+                #parser.map(<#field_type as ::core::convert::From<#inner_type>>::from)
+            };
+        }
+    }
+    let mut preprocess = Vec::new();
+    if let Some(pointer) = &field.json_pointer {
+        if !pointer.is_empty() && !pointer.starts_with('/') {
+            panic!("json_pointer must be empty or start with '/'");
+        }
+        // Shadows `str_value` so that parsing failures show the value found in the document.
+        preprocess.push(quote! {
+            let str_value = ::derive_from_env::_inner_helpers::json_pointer(&var_name, parsed_value, #pointer)?;
+            let parsed_value: &str = &str_value;
+        });
+    }
+    if field.decimal_comma {
+        if !is_float(field_type) {
+            panic!("decimal_comma is only supported on f32 and f64 fields");
+        }
+        preprocess.push(quote! {
+            let parsed_value = ::derive_from_env::_inner_helpers::decimal_comma(&var_name, parsed_value)?;
+        });
+    }
+    let is_bool = matches!(field_type, Type::Path(type_path) if type_path.path.is_ident("bool"));
+    if field.lenient_bool && !is_bool {
+        panic!("lenient_bool is only supported on bool fields");
+    }
+    let lenient_bool = is_bool && (field.lenient_bool || env_struct.lenient_bool);
+    let mut postprocess = Vec::new();
+    if let Some(percent) = &field.percent {
+        if !is_float(field_type) {
+            panic!("percent is only supported on f32 and f64 fields");
+        }
+        let require_sign = matches!(percent, Override::Explicit(PercentMode::RequireSign));
+        preprocess.push(quote! {
+            let (parsed_value, is_percentage) =
+                ::derive_from_env::_inner_helpers::percent(&var_name, &parsed_value, #require_sign)?;
+        });
+        postprocess.push(quote! {
+            let value = if is_percentage { value / 100.0 } else { value };
+        });
+    }
+    let list_element = list_element(field_type);
+    let map_types = map_types(field_type);
+    let tuple_elements = tuple_elements(field_type);
+    if field.tuple_separator.is_some() && tuple_elements.is_none() {
+        panic!("tuple_separator is only supported on tuple fields of 2 or 3 elements");
+    }
+    if field.deny_duplicates && !is_set(field_type) {
+        panic!("deny_duplicates is only supported on HashSet and BTreeSet fields");
+    }
+    if field.quoted && (list_element.is_none() || field.hex || field.base64.is_some()) {
+        panic!("quoted is only supported on list fields");
+    }
+    if field.split.is_some() {
+        if list_element.is_none() || field.hex || field.base64.is_some() {
+            panic!("split is only supported on list fields");
+        }
+        if field.separator.is_some() || field.quoted {
+            panic!("split can't be used together with separator or quoted");
+        }
+    }
+    if field.kv_separator.is_some() && map_types.is_none() {
+        panic!("kv_separator is only supported on map fields");
+    }
+    if field.separator.is_some() && list_element.is_none() && map_types.is_none() {
+        panic!("separator is only supported on Vec and map fields");
+    }
+    let conversion = match (&field.from, &field.try_from) {
+        (Some(_), Some(_)) => panic!("from and try_from can't be used together"),
+        (Some(intermediate_type), None) => Some((intermediate_type, false)),
+        (None, Some(intermediate_type)) => Some((intermediate_type, true)),
+        (None, None) => None,
+    };
+    // Messages quoting the value, or a part of it, leave it out for `secret` fields.
+    let shown = |value: proc_macro2::TokenStream| match is_secret(field) {
+        true => quote! { "<redacted>" },
+        false => quote! { format!("{:?}", #value) },
+    };
+    if field.parse_fn().is_some()
+        && (conversion.is_some()
+            || document_format(field).is_some()
+            || field.base64.is_some()
+            || field.hex
+            || lenient_bool)
+    {
+        panic!("parse_with and with can't be used together with from, try_from, json, format, base64, hex or lenient_bool");
+    }
+    let parse = match range_bounds(field_type) {
+        _ if conversion.is_some() => {
+            let (intermediate_type, fallible) = conversion.unwrap();
+            let intermediate_type_name = expected_type(field, intermediate_type);
+            let intermediate = quote! {
+                <#intermediate_type as FromStr>::from_str(&parsed_value).map_err(|_| {
+                    ::derive_from_env::FromEnvError::ParsingFailure {
+                        var_name: var_name.clone(),
+                        str_value: str_value.clone(),
+                        origin: origin.clone(),
+                        expected_type: #intermediate_type_name.to_string(),
+                    }
+                })?
+            };
+            if fallible {
+                let shown_value = shown(quote! { str_value });
+                quote! {
+                    <#field_type as ::core::convert::TryFrom<#intermediate_type>>::try_from(#intermediate).map_err(|error| {
+                        ::derive_from_env::FromEnvError::Custom {
+                            var_name: Some(var_name.clone()),
+                            message: format!("invalid value {}: {}", #shown_value, error),
+                        }
+                    })?
+                }
+            } else {
+                quote! { <#field_type as ::core::convert::From<#intermediate_type>>::from(#intermediate) }
+            }
+        }
+        // Collections are parsed item by item below.
+        _ if field.parse_fn().is_some() && list_element.is_none() && map_types.is_none() => {
+            let parse_with = field.parse_fn().unwrap();
+            let field_type_name = expected_type(field, field_type);
+            quote! {
+                #parse_with(&parsed_value).map_err(|error| {
+                    ::derive_from_env::FromEnvError::ParsingFailure {
+                        var_name: var_name.clone(),
+                        str_value: str_value.clone(),
+                        origin: origin.clone(),
+                        expected_type: format!("{} ({})", #field_type_name, error),
+                    }
+                })?
+            }
+        }
+        _ if document_format(field).is_some() => {
+            generate_document_parser(document_format(field).unwrap(), field_type)
+        }
+        _ if field.base64.is_some() => {
+            let url_safe = matches!(field.base64, Some(Override::Explicit(Base64Alphabet::Url)));
+            let is_string =
+                matches!(field_type, Type::Path(type_path) if type_path.path.is_ident("String"));
+            let is_bytes = list_element.is_some_and(|element_type| {
+                is_vec(field_type)
+                    && matches!(element_type, Type::Path(type_path) if type_path.path.is_ident("u8"))
+            });
+            if !is_string && !is_bytes {
+                panic!("base64 is only supported on Vec<u8> and String fields");
+            }
+            // The value is a secret more often than not, so failures don't show it.
+            let failure = quote! {
+                |expected_type: String| ::derive_from_env::FromEnvError::ParsingFailure {
+                    var_name: var_name.clone(),
+                    str_value: "<redacted>".to_string(),
+                    origin: origin.clone(),
+                    expected_type,
+                }
+            };
+            let decoded = quote! {
+                ::derive_from_env::_inner_helpers::decode_base64(&parsed_value, #url_safe).map_err(#failure)?
+            };
+            if is_string {
+                let expected_type = if url_safe {
+                    "base64 (URL-safe alphabet) of UTF-8 text"
+                } else {
+                    "base64 (standard alphabet) of UTF-8 text"
+                };
+                quote! {
+                    String::from_utf8(#decoded).map_err(|error| {
+                        ::derive_from_env::_inner_helpers::wipe(&mut error.into_bytes());
+                        (#failure)(#expected_type.to_string())
+                    })?
+                }
+            } else {
+                decoded
+            }
+        }
+        // Only defaults get there, values being read as `OsString` by the loader.
+        _ if is_os_string(field_type) => quote! { ::std::ffi::OsString::from(parsed_value) },
+        _ if field.hex => {
+            let is_bytes = list_element.is_some_and(
+                |element_type| matches!(element_type, Type::Path(type_path) if type_path.path.is_ident("u8")),
+            );
+            if !is_bytes || is_set(field_type) {
+                panic!("hex is only supported on Vec<u8> and [u8; N] fields");
+            }
+            // Key material more often than not, so failures don't show the value.
+            let failure = quote! {
+                |expected_type: String| ::derive_from_env::FromEnvError::ParsingFailure {
+                    var_name: var_name.clone(),
+                    str_value: "<redacted>".to_string(),
+                    origin: origin.clone(),
+                    expected_type,
+                }
+            };
+            let decoded = quote! {
+                ::derive_from_env::_inner_helpers::decode_hex(&parsed_value).map_err(#failure)?
+            };
+            if let Type::Array(array) = field_type {
+                let len = &array.len;
+                quote! {
+                    <#field_type as ::core::convert::TryFrom<Vec<u8>>>::try_from(#decoded).map_err(|bytes| {
+                        (#failure)(format!("hex of {} bytes, found {} bytes", #len, bytes.len()))
+                    })?
+                }
+            } else {
+                decoded
+            }
+        }
+        _ if lenient_bool => {
+            quote! {
+                ::derive_from_env::_inner_helpers::lenient_bool(&parsed_value).ok_or_else(|| {
+                    ::derive_from_env::FromEnvError::ParsingFailure {
+                        var_name: var_name.clone(),
+                        str_value: str_value.clone(),
+                        origin: origin.clone(),
+                        expected_type: ::derive_from_env::_inner_helpers::LENIENT_BOOL_SPELLINGS.to_string(),
+                    }
+                })?
+            }
+        }
+        _ if is_duration(field_type) => {
+            quote! {
+                ::derive_from_env::_inner_helpers::parse_duration(&parsed_value).ok_or_else(|| {
+                    ::derive_from_env::FromEnvError::ParsingFailure {
+                        var_name: var_name.clone(),
+                        str_value: str_value.clone(),
+                        origin: origin.clone(),
+                        expected_type: ::derive_from_env::_inner_helpers::DURATION_SYNTAX.to_string(),
+                    }
+                })?
+            }
+        }
+        _ if tuple_elements.is_some() => {
+            let tuple_elements = tuple_elements.unwrap();
+            let separator = field.tuple_separator.as_deref().unwrap_or(",");
+            if separator.is_empty() {
+                panic!("tuple_separator can't be empty");
+            }
+            let count = tuple_elements.len();
+            let components = tuple_elements
+                .iter()
+                .enumerate()
+                .map(|(index, component_type)| {
+                    let expected_type = format!(
+                        "{} (component {} of a tuple separated by {:?})",
+                        type_name(component_type),
+                        index + 1,
+                        separator
+                    );
+                    quote! {
+                        <#component_type as FromStr>::from_str(components[#index]).map_err(|_| {
+                            ::derive_from_env::FromEnvError::ParsingFailure {
+                                var_name: var_name.clone(),
+                                str_value: components[#index].to_string(),
+                                origin: origin.clone(),
+                                expected_type: #expected_type.to_string(),
+                            }
                         })?
+                    }
+                });
+            quote! {
+                {
+                    let components = ::derive_from_env::_inner_helpers::split_tuple(&var_name, &parsed_value, #separator, #count)?;
+                    (#(#components),*)
+                }
+            }
+        }
+        _ if map_types.is_some() => {
+            let (key_type, value_type) = map_types.unwrap();
+            let separator = list_separator(field, env_struct);
+            let kv_separator = kv_separator(field, env_struct);
+            let key_type_name = type_name(key_type);
+            let value_type_name = field
+                .type_name
+                .clone()
+                .unwrap_or_else(|| type_name(value_type));
+            // Failures show the raw entry and its position, naming the separator so that operators
+            // know how the value was split. Labels are format arguments, as they may hold braces.
+            let failure = |label: String| {
+                quote! {
+                    ::derive_from_env::FromEnvError::ParsingFailure {
+                        var_name: var_name.clone(),
+                        str_value: entry.to_string(),
+                        origin: origin.clone(),
+                        expected_type: format!("{} (entry {} of a map separated by {:?})", #label, index, #separator),
+                    }
+                }
+            };
+            let entry_failure = failure(format!("key{}value", kv_separator));
+            let key_failure = failure(format!("{} key", key_type_name));
+            let value_label = format!("{} value", value_type_name);
+            let value_failure = failure(value_label.clone());
+            let parse_value = match field.parse_fn() {
+                // The message of the function follows the position.
+                Some(parse_with) => {
+                    quote! {
+                        #parse_with(value).map_err(|error| {
+                            ::derive_from_env::FromEnvError::ParsingFailure {
+                                var_name: var_name.clone(),
+                                str_value: entry.to_string(),
+                                origin: origin.clone(),
+                                expected_type: format!("{} (entry {} of a map separated by {:?}) ({})", #value_label, index, #separator, error),
+                            }
+                        })?
+                    }
+                }
+                None => {
+                    quote! { <#value_type as FromStr>::from_str(value).map_err(|_| #value_failure)? }
+                }
+            };
+            let shown_entry = shown(quote! { entry });
+            let shown_key = shown(quote! { key });
+            quote! {
+                {
+                    let mut map: #field_type = ::core::default::Default::default();
+                    for (index, entry) in ::derive_from_env::_inner_helpers::map_entries(&parsed_value, #separator) {
+                        let (key, value) = ::derive_from_env::_inner_helpers::split_map_entry(entry, #kv_separator)
+                            .ok_or_else(|| #entry_failure)?;
+                        if key.is_empty() {
+                            return Err(::derive_from_env::FromEnvError::Custom {
+                                var_name: Some(var_name.clone()),
+                                message: format!("empty key in entry {} {}", index, #shown_entry),
+                            });
+                        }
+                        let parsed_key = <#key_type as FromStr>::from_str(key).map_err(|_| #key_failure)?;
+                        let parsed_entry_value: #value_type = #parse_value;
+                        if map.insert(parsed_key, parsed_entry_value).is_some() {
+                            return Err(::derive_from_env::FromEnvError::Custom {
+                                var_name: Some(var_name.clone()),
+                                message: format!("duplicate key {} in entry {} {}", #shown_key, index, #shown_entry),
+                            });
+                        }
+                    }
+                    map
+                }
+            }
+        }
+        _ if list_element.is_some() => {
+            let element_type = list_element.unwrap();
+            let separator = list_separator(field, env_struct);
+            let whitespace = field.split == Some(SplitMode::Whitespace);
+            // Names the separator so that operators know how the value was split.
+            let element_type_name = format!(
+                "{} item of a list separated by {}",
+                field
+                    .type_name
+                    .clone()
+                    .unwrap_or_else(|| type_name(element_type)),
+                if whitespace {
+                    "whitespace".to_string()
+                } else {
+                    format!("{:?}", separator)
+                }
+            );
+            let parse_item = match field.parse_fn() {
+                Some(parse_with) => quote! {
+                    |item: &str| -> Result<#element_type, ::derive_from_env::FromEnvError> {
+                        #parse_with(item).map_err(|error| {
+                            ::derive_from_env::FromEnvError::ParsingFailure {
+                                var_name: var_name.clone(),
+                                str_value: item.to_string(),
+                                origin: origin.clone(),
+                                expected_type: format!("{} ({})", #element_type_name, error),
+                            }
+                        })
+                    }
+                },
+                None => quote! {
+                |item: &str| {
+                    <#element_type as FromStr>::from_str(item).map_err(|_| {
+                        ::derive_from_env::FromEnvError::ParsingFailure {
+                            var_name: var_name.clone(),
+                            str_value: item.to_string(),
+                            origin: origin.clone(),
+                            expected_type: #element_type_name.to_string(),
+                        }
+                    })
+                }
+                },
+            };
+            // Quoted items are unescaped into a `Vec<String>` first.
+            let (split, items) = if field.quoted {
+                (
+                    quote! {
+                        let quoted_items = ::derive_from_env::_inner_helpers::split_quoted(&parsed_value, #separator)
+                            .map_err(|(index, item, expected)| ::derive_from_env::FromEnvError::ParsingFailure {
+                                var_name: var_name.clone(),
+                                str_value: item.to_string(),
+                                origin: origin.clone(),
+                                expected_type: format!("{} (item {} of a list separated by {:?})", expected, index, #separator),
+                            })?;
+                    },
+                    quote! { quoted_items.iter().map(String::as_str) },
+                )
+            } else if whitespace {
+                (quote! {}, quote! { parsed_value.split_whitespace() })
+            } else {
+                (
+                    quote! {},
+                    quote! { ::derive_from_env::_inner_helpers::split_list(&parsed_value, #separator) },
+                )
+            };
+            if field.deny_duplicates {
+                let shown_item = shown(quote! { item });
+                quote! {
+                    {
+                        #split
+                        let mut set: #field_type = ::core::default::Default::default();
+                        for item in #items {
+                            if !set.insert((#parse_item)(item)?) {
+                                return Err(::derive_from_env::FromEnvError::Custom {
+                                    var_name: Some(var_name.clone()),
+                                    message: format!("duplicate item {}", #shown_item),
+                                });
+                            }
+                        }
+                        set
+                    }
+                }
+            } else if let Type::Array(array) = field_type {
+                let len = &array.len;
+                let shown_value = shown(quote! { str_value });
+                quote! {
+                    {
+                        #split
+                        let items = #items
+                            .map(#parse_item)
+                            .collect::<Result<Vec<#element_type>, _>>()?;
+                        <#field_type as ::core::convert::TryFrom<Vec<#element_type>>>::try_from(items).map_err(|items| {
+                            ::derive_from_env::FromEnvError::Custom {
+                                var_name: Some(var_name.clone()),
+                                message: format!("expected {} items, found {} in {}", #len, items.len(), #shown_value),
+                            }
+                        })?
+                    }
+                }
+            } else {
+                quote! {
+                    {
+                        #split
+                        #items
+                            .map(#parse_item)
+                            .collect::<Result<#field_type, _>>()?
+                    }
+                }
+            }
+        }
+        Some((bound_type, inclusive)) => {
+            let range = if inclusive {
+                quote! { start..=end }
+            } else {
+                quote! { start..end }
+            };
+            let secret = is_secret(field);
+            quote! {
+                ::derive_from_env::_inner_helpers::parse_range::<#bound_type>(&var_name, &parsed_value, #inclusive, #secret)
+                    .map(|(start, end)| #range)?
+            }
+        }
+        None => {
+            let field_type_name = expected_type(field, field_type);
+            quote! {
+                <#field_type as FromStr>::from_str(&parsed_value).map_err(|_| {
+                    ::derive_from_env::FromEnvError::ParsingFailure {
+                        var_name: var_name.clone(),
+                        str_value: str_value.clone(),
+                        origin: origin.clone(),
+                        expected_type: #field_type_name.to_string(),
+                    }
+                })?
+            }
+        }
+    };
+    quote! {
+        (|| -> Result<#field_type, ::derive_from_env::FromEnvError> {
+            let parsed_value: &str = &str_value;
+            #(#preprocess)*
+            let value = #parse;
+            #(#postprocess)*
+            Ok(value)
+        })()
+    }
+}
+
+/// Expression of the prefix of the variables of the struct under `#[from_env(compat = "envy")]`.
+fn generate_envy_prefix(prefix: bool, env_struct: &EnvStruct) -> proc_macro2::TokenStream {
+    let struct_prefix = env_struct.prefix.clone().unwrap_or_default();
+    // Under envy compatibility, prefixes are concatenated as-is.
+    if prefix {
+        quote! { &format!("{}{}", prefix, #struct_prefix) }
+    } else {
+        quote! { #struct_prefix }
+    }
+}
+
+/// Expression of the variable name derived from the field name, evaluating to a `String`. For
+/// nested structs, this is the prefix given to their `from_env_with_prefix`.
+fn generate_env_var_name(
+    field: &EnvField,
+    prefix: bool,
+    env_struct: &EnvStruct,
+) -> proc_macro2::TokenStream {
+    // The field of a transparent struct is read from the prefix itself.
+    if env_struct.transparent && prefix {
+        return quote! { prefix.to_string() };
+    }
+    if env_struct.transparent || is_unprefixed_value(field) {
+        let upper_name = static_env_var_name(field, env_struct);
+        return quote! { #upper_name.to_string() };
+    }
+    generate_prefixed_var_name(
+        &segmented_name(field, &field_name(field, env_struct)),
+        prefix,
+        env_struct,
+    )
+}
+
+/// `name` under the `prefix` segment of the field, e.g. `OBSERVABILITY_SENTRY_DSN`.
+fn segmented_name(field: &EnvField, name: &str) -> String {
+    match &field.prefix {
+        Some(segment) => format!("{}_{}", segment, name),
+        None => name.to_string(),
+    }
+}
+
+/// Expression of the name of the variable `name` of the struct, under the prefixes of the struct
+/// and of the fields holding it.
+fn generate_prefixed_var_name(
+    name: &str,
+    prefix: bool,
+    env_struct: &EnvStruct,
+) -> proc_macro2::TokenStream {
+    let struct_prefix = env_struct.prefix.clone().unwrap_or_default();
+    let upper_name = name.to_uppercase();
+    match (env_struct.compat, prefix) {
+        (Some(Compat::Envy), _) => {
+            let envy_prefix = generate_envy_prefix(prefix, env_struct);
+            quote! { format!("{}{}", #envy_prefix, #upper_name) }
+        }
+        (None, true) if env_struct.collapse_prefix => {
+            quote! {
+                format!(
+                    "{}{}",
+                    ::derive_from_env::_inner_helpers::collapse_prefix(prefix, #struct_prefix),
+                    #upper_name
+                )
+            }
+        }
+        (None, true) => {
+            let upper_name = format!("{}{}", struct_prefix, upper_name);
+            quote! { format!("{}_{}", prefix, #upper_name) }
+        }
+        (None, false) => {
+            let upper_name = format!("{}{}", struct_prefix, upper_name);
+            quote! { #upper_name.to_string() }
+        }
+    }
+}
+
+/// Expressions of the names of the variables looked up, in order, when the variable of the field
+/// is not set: the other names of `var`, `alias` under the same prefixes as the field, `alias_var`,
+/// `deprecated_alias` under the same prefixes as `alias`, and `default_env`.
+fn generate_fallback_names(
+    field: &EnvField,
+    prefix: bool,
+    env_struct: &EnvStruct,
+) -> Vec<proc_macro2::TokenStream> {
+    // Aliases of fields read from a fixed name are fixed names as well.
+    let fixed_aliases = field.var.is_some() || env_struct.transparent || is_unprefixed_value(field);
+    let alias_name = |alias: &String| {
+        if fixed_aliases {
+            quote! { #alias.to_string() }
+        } else {
+            generate_prefixed_var_name(&segmented_name(field, alias), prefix, env_struct)
+        }
+    };
+    let aliases = field.alias.iter().map(alias_name);
+    let var_names = field
+        .var
+        .iter()
+        .flat_map(|names| &names.0[1..])
+        .map(|name| quote! { #name.to_string() });
+    let alias_vars = field
+        .alias_var
+        .iter()
+        .map(|name| quote! { #name.to_string() });
+    let deprecated_aliases = field.deprecated_alias.iter().map(alias_name);
+    let default_env = field
+        .default_env
+        .iter()
+        .map(|name| quote! { #name.to_string() });
+    var_names
+        .chain(aliases)
+        .chain(alias_vars)
+        .chain(deprecated_aliases)
+        .chain(default_env)
+        .collect()
+}
+
+/// Name of the field in the `EnvVarSpec`s, empty for the field of a transparent struct which is
+/// named after the field holding the struct, and the position for fields of tuple structs.
+fn field_name(field: &EnvField, env_struct: &EnvStruct) -> String {
+    if env_struct.transparent {
+        return String::new();
+    }
+    // Raw identifiers are named without their `r#`.
+    match &field.ident {
+        Some(ident) => ident.unraw().to_string(),
+        None => field.position.unwrap().to_string(),
+    }
+}
+
+/// Variable name derived from the field name when no prefix is given.
+fn static_env_var_name(field: &EnvField, env_struct: &EnvStruct) -> String {
+    if env_struct.transparent {
+        return from_env_value::upper_snake_case(&env_struct.ident.to_string());
+    }
+    let field_name = field_name(field, env_struct);
+    if is_unprefixed_value(field) {
+        return field_name.to_uppercase();
+    }
+    let struct_prefix = env_struct.prefix.clone().unwrap_or_default();
+    format!(
+        "{}{}",
+        struct_prefix,
+        segmented_name(field, &field_name).to_uppercase()
+    )
+}
+
+/// Whether the field is a value under `no_prefix`, read from its name alone whatever the prefixes.
+fn is_unprefixed_value(field: &EnvField) -> bool {
+    field.no_prefix && !is_nested(field) && optional_section(field).is_none()
+}
+
+/// Name of the trait generated for `#[from_env(defaults_from = "...")]`, e.g. `ConfigDefaults`.
+fn defaults_trait(env_struct: &EnvStruct) -> syn::Ident {
+    format_ident!("{}Defaults", env_struct.ident)
+}
+
+/// Whether the field gets a method in the trait generated for `defaults_from`: the fields parsed
+/// from a single variable which are neither `Option`, `OsString`, `path_list`, `flag` nor
+/// `count_var`.
+fn has_trait_default(field: &EnvField) -> bool {
+    extract_inner_type_if_option(&field.ty).is_none()
+        && !is_os_string(&field.ty)
+        && !field.path_list
+        && !field.flag
+        && field.count_var.is_none()
+        && field.default_fn.is_none()
+        && !field.bare_default()
+        && !is_nested(field)
+}
+
+/// Expression of the default of the field given by the `defaults_from` trait, evaluating to an
+/// `Option`, when the field has no `default` attribute.
+fn generate_trait_default(
+    field: &EnvField,
+    env_struct: &EnvStruct,
+) -> Option<proc_macro2::TokenStream> {
+    let defaults_from = env_struct.defaults_from.as_ref()?;
+    if field.default_value().is_some() || !has_trait_default(field) {
+        return None;
+    }
+    let trait_identifier = defaults_trait(env_struct);
+    let method = format_ident!("default_{}", field_name(field, env_struct));
+    Some(quote! { <#defaults_from as #trait_identifier>::#method() })
+}
+
+fn generate_field_loader(
+    field: &EnvField,
+    prefix: bool,
+    env_struct: &EnvStruct,
+) -> proc_macro2::TokenStream {
+    let value = generate_field_value(field, prefix, env_struct);
+    // Nested structs read their variables through the `file_suffix` of the struct as well.
+    let value = match &env_struct.file_suffix {
+        Some(suffix)
+            if !field.no_file && (is_nested(field) || optional_section(field).is_some()) =>
+        {
+            let conflict = file_conflict(env_struct);
+            quote! {
+                {
+                    let file_source = ::derive_from_env::_inner_helpers::FileSuffix {
+                        source,
+                        suffix: #suffix,
+                        conflict: #conflict,
+                    };
+                    let source: &dyn ::derive_from_env::EnvSource = &file_source;
+                    #value
+                }
+            }
+        }
+        _ => value,
+    };
+    if field.validate.is_none()
+        && field.range.is_none()
+        && field.matches.is_none()
+        && field.min_len.is_none()
+        && field.max_len.is_none()
+    {
+        return value;
+    }
+    if is_phantom_data(&field.ty) {
+        panic!(
+            "validate, range, matches, min_len and max_len are not supported on PhantomData fields"
+        );
+    }
+    let var_name = match field.var_name() {
+        Some(var_name) => quote! { #var_name.to_string() },
+        None => generate_env_var_name(field, prefix, env_struct),
+    };
+    let failure = quote! {
+        |message| ::derive_from_env::FromEnvError::ValidationFailure {
+            var_name: #var_name,
+            message,
+        }
+    };
+    let inner_field_type = extract_inner_type_if_option(&field.ty);
+    let value_type = inner_field_type.unwrap_or(&field.ty);
+    // The values of `secret` fields are left out of the messages.
+    let secret = is_secret(field);
+    // Collections are measured by their elements, strings by their characters.
+    let length_check = (field.min_len.is_some() || field.max_len.is_some()).then(|| {
+        if let (Some(min_len), Some(max_len)) = (field.min_len, field.max_len) {
+            if min_len > max_len {
+                panic!("min_len can't be greater than max_len");
+            }
+        }
+        let (len, unit) =
+            match list_element(value_type).or(map_types(value_type).map(|(key, _)| key)) {
+                Some(_) => (quote! { value.len() }, "elements"),
+                None => (
+                    quote! { ::std::convert::AsRef::<str>::as_ref(value).chars().count() },
+                    "characters",
+                ),
+            };
+        let min_check = field.min_len.map(|min_len| {
+            quote! {
+                if len < #min_len {
+                    return Err(format!("{} {}, expected at least {}", len, #unit, #min_len));
+                }
+            }
+        });
+        let max_check = field.max_len.map(|max_len| {
+            quote! {
+                if len > #max_len {
+                    return Err(format!("{} {}, expected at most {}", len, #unit, #max_len));
+                }
+            }
+        });
+        quote! {
+            (|value: &#value_type| -> Result<(), String> {
+                let len = #len;
+                #min_check
+                #max_check
+                Ok(())
+            })
+        }
+    });
+    // Numbers are checked against the range, lists item by item.
+    let range_check = field.range.as_ref().map(|range| {
+        let parsed = parse_range_attribute(range);
+        let shown_item = match secret {
+            true => quote! { "<redacted>" },
+            false => quote! { item },
+        };
+        let element_type = list_element(value_type).filter(|_| map_types(value_type).is_none());
+        if !is_number(element_type.unwrap_or(value_type)) {
+            panic!("range is only supported on integer and float fields, and lists of them");
+        }
+        let check = quote! {
+            if !(#parsed).contains(item) {
+                return Err(format!("{} is out of the range {}", #shown_item, #range));
+            }
+        };
+        let check = match element_type {
+            Some(_) => quote! { for item in value { #check } },
+            None => quote! { let item = value; #check },
+        };
+        quote! {
+            (|value: &#value_type| -> Result<(), String> {
+                #check
+                Ok(())
+            })
+        }
+    });
+    // Strings are matched against the pattern, compiled on first use, lists item by item.
+    let matches_check = field.matches.as_ref().map(|pattern| {
+        check_pattern(pattern);
+        let element_type = list_element(value_type).filter(|_| map_types(value_type).is_none());
+        let check = quote! {
+            ::derive_from_env::_inner_helpers::check_matches(&REGEX, #pattern, ::std::convert::AsRef::<str>::as_ref(item), #secret)?;
+        };
+        let check = match element_type {
+            Some(_) => quote! { for item in value { #check } },
+            None => quote! { let item = value; #check },
+        };
+        quote! {
+            (|value: &#value_type| -> Result<(), String> {
+                static REGEX: ::std::sync::OnceLock<::derive_from_env::_inner_helpers::Regex> =
+                    ::std::sync::OnceLock::new();
+                #check
+                Ok(())
+            })
+        }
+    });
+    let checks = length_check
+        .into_iter()
+        .chain(range_check)
+        .chain(matches_check)
+        .chain(
+            field
+                .validate
+                .as_ref()
+                .map(|validate| quote! { (|value| #validate(value)) }),
+        )
+        .map(|check| match inner_field_type {
+            // `Option` fields are checked when they hold a value.
+            Some(_) => quote! {
+                value.as_ref().map_or(Ok(()), #check).map_err(#failure)?;
+            },
+            None => quote! { (#check)(&value).map_err(#failure)?; },
+        });
+    quote! {
+        {
+            let value = #value;
+            #(#checks)*
+            value
+        }
+    }
+}
+
+/// Generates an expression loading the value of the field, before its `validate` function.
+fn generate_field_value(
+    field: &EnvField,
+    prefix: bool,
+    env_struct: &EnvStruct,
+) -> proc_macro2::TokenStream {
+    let field_name = field_name(field, env_struct);
+    let field_type = &field.ty;
+    let inner_field_type = extract_inner_type_if_option(field_type);
+    let default_value = field.default_value();
+    let no_prefix = field.no_prefix;
+    let from_str = field.from_str;
+    let var_name = field.var_name();
+    let flatten = field.flatten;
+    let compat = env_struct.compat;
+
+    if field.default_fn.is_some()
+        && (default_value.is_some()
+            || field.bare_default()
+            || field.path_list
+            || field.flag
+            || flatten)
+    {
+        panic!("default_fn is mutually exclusive with default, and not supported with path_list, flag or flatten");
+    }
+    // Docker and Kubernetes secrets: the variable, then the file named by `<NAME>_FILE`.
+    let file = file_lookup(field, env_struct);
+    let trim = trims(field, env_struct);
+    if is_phantom_data(field_type) {
+        return match inner_field_type {
+            Some(_) => quote! { Some(::core::marker::PhantomData) },
+            None => quote! { ::core::marker::PhantomData },
+        };
+    }
+    if field.deserialize && !flatten {
+        panic!("deserialize is only supported on flatten fields");
+    }
+    if field.lenient {
+        let has_default = inner_field_type.is_some()
+            || default_value.is_some()
+            || field.default_fn.is_some()
+            || field.bare_default()
+            || env_struct.all_defaultable;
+        if !has_default {
+            panic!("lenient requires a default: a default or default_fn attribute, a bare default, or an Option field");
+        }
+        if flatten
+            || is_nested(field)
+            || field.path_list
+            || field.flag
+            || is_os_string(inner_field_type.unwrap_or(field_type))
+        {
+            panic!("lenient is only supported on fields parsed from a single variable");
+        }
+    }
+    // Parse failures of `lenient` fields are recorded, the field taking its default instead.
+    let lenient = |parsed: proc_macro2::TokenStream, fallback: &proc_macro2::TokenStream| {
+        if field.lenient {
+            quote! {
+                match #parsed {
+                    Ok(value) => value,
+                    Err(error) => {
+                        ::derive_from_env::_inner_helpers::lenient_fallback(error);
+                        #fallback
+                    }
+                }
+            }
+        } else {
+            quote! { #parsed? }
+        }
+    };
+    if field.lazy != is_lazy(field_type) {
+        panic!("lazy must be used on Lazy<T> fields, and Lazy<T> fields need the lazy attribute");
+    }
+    if field.zeroize != zeroizing_inner(inner_field_type.unwrap_or(field_type)).is_some() {
+        panic!("zeroize must be used on Zeroizing<T> fields, and Zeroizing<T> fields need the zeroize attribute");
+    }
+    if (field.json_var.is_some() || field.url_var.is_some()) && !is_nested(field) {
+        panic!("json_var and url_var are only supported on nested structs");
+    }
+    if field.deny_conflicts && field.url_var.is_none() {
+        panic!("deny_conflicts requires url_var");
+    }
+    if field.type_name.is_some() && is_nested(field) {
+        panic!("type_name is not supported on nested structs");
+    }
+    if is_unprefixed_value(field) && (var_name.is_some() || compat.is_some()) {
+        panic!(
+            "no_prefix can't be used together with var, which bypasses prefixes already, or compat"
+        );
+    }
+    if field.prefix.is_some() {
+        if is_nested(field) || optional_section(field).is_some() || env_struct.transparent {
+            panic!("prefix is only supported on fields read from a single variable, nested structs have their own prefix");
+        }
+        if var_name.is_some() || field.no_prefix {
+            panic!("prefix can't be used together with var or no_prefix, which bypass prefixes");
+        }
+    }
+    let envy_prefix = generate_envy_prefix(prefix, env_struct);
+    let env_var_name = generate_env_var_name(field, prefix, env_struct);
+    let lookup = if let Some(var_name) = var_name {
+        quote! { (#var_name.to_string(), source.lookup(#var_name)?) }
+    } else if compat == Some(Compat::Envy) {
+        quote! { ::derive_from_env::_inner_helpers::envy_lookup(source, #envy_prefix, #field_name)? }
+    } else {
+        quote! {
+            {
+                let var_name = #env_var_name;
+                let value = source.lookup(&var_name)?;
+                (var_name, value)
+            }
+        }
+    };
+    if field.deny_gaps && !field.indexed {
+        panic!("deny_gaps requires indexed");
+    }
+    if field.indexed {
+        let Some(element_type) = list_element(field_type).filter(|_| is_vec(field_type)) else {
+            panic!("indexed is only supported on Vec fields");
+        };
+        if default_value.is_some() || var_name.is_some() || no_prefix || compat.is_some() {
+            panic!("indexed is not supported with default, var, no_prefix or compat");
+        }
+        let deny_gaps = field.deny_gaps;
+        return quote! {
+            ::derive_from_env::_inner_helpers::load_indexed::<#element_type>(source, &#env_var_name, #deny_gaps)?
+        };
+    }
+    if let Some(count_var) = &field.count_var {
+        let list_type = inner_field_type.unwrap_or(field_type);
+        let Some(element_type) = list_element(list_type).filter(|_| is_vec(list_type)) else {
+            panic!("count_var is only supported on Vec fields");
+        };
+        if default_value.is_some()
+            || field.separator.is_some()
+            || !field.append_from.is_empty()
+            || field.path_list
+            || flatten
+        {
+            panic!("count_var is not supported with default, separator, append_from, path_list or flatten");
+        }
+        let name = match var_name {
+            Some(var_name) => quote! { #var_name.to_string() },
+            None => env_var_name,
+        };
+        let element_type_name = expected_type(field, element_type);
+        let secret = is_secret(field);
+        let load = quote! {
+            ::derive_from_env::_inner_helpers::load_counted::<#element_type>(source, #count_var, &#name, #element_type_name, #secret)?
+        };
+        return if inner_field_type.is_some() {
+            load
+        } else {
+            quote! {
+                #load.ok_or_else(|| ::derive_from_env::FromEnvError::MissingEnvVar {
+                    var_name: #count_var.to_string(),
+                    origin: source.origin(#count_var),
+                    fallbacks: Vec::new(),
+                })?
+            }
+        };
+    }
+    if field.credential.is_some() && (field.path_list || is_nested(field)) {
+        panic!("credential is only supported on fields read from a single variable");
+    }
+    // systemd credentials take precedence over the variable when the directory is set.
+    let lookup = match &field.credential {
+        Some(credential) => {
+            let required = inner_field_type.is_none();
+            let var_name = match var_name {
+                Some(var_name) => quote! { #var_name.to_string() },
+                None => env_var_name.clone(),
+            };
+            quote! {
+                match ::derive_from_env::_inner_helpers::credential(source, #credential, #required)? {
+                    Some(value) => (#var_name, Some(value)),
+                    None => #lookup,
+                }
+            }
+        }
+        None => lookup,
+    };
+    // Empty values are treated as unset, whitespace-only ones as well with `non_empty = "trim"` or
+    // when the value is trimmed.
+    let non_empty = field
+        .non_empty
+        .as_ref()
+        .or(env_struct.non_empty.as_ref())
+        .filter(|_| !field.path_list && !is_nested(field))
+        .map(|non_empty| {
+            let trim = trim || matches!(non_empty, Override::Explicit(NonEmptyMode::Trim));
+            move |lookup: proc_macro2::TokenStream| {
+                quote! { ::derive_from_env::_inner_helpers::non_empty(#lookup, #trim) }
+            }
+        });
+    if field.non_empty.is_some() && (field.path_list || is_nested(field)) {
+        panic!("non_empty is only supported on fields read from a single variable");
+    }
+    let lookup = match non_empty {
+        Some(non_empty) => non_empty(lookup),
+        None => lookup,
+    };
+    let lookup = match (&file, non_empty) {
+        (None, _) => lookup,
+        (Some((suffix, conflict)), non_empty) => {
+            let lookup = quote! {
+                ::derive_from_env::_inner_helpers::lookup_file(source, #lookup, #suffix, #conflict)?
+            };
+            match non_empty {
+                Some(non_empty) => non_empty(lookup),
+                None => lookup,
+            }
+        }
+    };
+    let fallbacks = generate_fallback_names(field, prefix, env_struct);
+    // Told apart from the other names as it depends on the name of the variable.
+    let file_fallback = file
+        .as_ref()
+        .map(|(suffix, _)| quote! { format!("{}{}", var_name, #suffix), });
+    if !fallbacks.is_empty() && (field.path_list || is_nested(field)) {
+        panic!("alias, alias_var, deprecated_alias, default_env and several var names are only supported on fields read from a single variable");
+    }
+    // Deprecated aliases are read after the other names of the field, notifying the hook, and
+    // before `default_env`.
+    let (names, rest) = fallbacks.split_at(
+        fallbacks.len() - field.deprecated_alias.len() - usize::from(field.default_env.is_some()),
+    );
+    let (deprecated_aliases, default_env) = rest.split_at(field.deprecated_alias.len());
+    let field_path = [env_struct.ident.to_string(), field_name.clone()]
+        .into_iter()
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>()
+        .join(".");
+    let mut lookup = lookup;
+    for (names, deprecated) in [
+        (names, false),
+        (deprecated_aliases, true),
+        (default_env, false),
+    ] {
+        if names.is_empty() {
+            continue;
+        }
+        let fallback_lookup = if deprecated {
+            quote! {
+                ::derive_from_env::_inner_helpers::lookup_deprecated(source, #lookup, &[#(#names),*], #field_path)?
+            }
+        } else {
+            quote! {
+                ::derive_from_env::_inner_helpers::lookup_fallbacks(source, #lookup, &[#(#names),*])?
+            }
+        };
+        lookup = match non_empty {
+            Some(non_empty) => non_empty(fallback_lookup),
+            None => fallback_lookup,
+        };
+    }
+    if trim {
+        lookup = quote! { ::derive_from_env::_inner_helpers::trim_value(#lookup) };
+    }
+    if field.flag {
+        if !matches!(field_type, Type::Path(type_path) if type_path.path.is_ident("bool")) {
+            panic!("flag is only supported on bool fields");
+        }
+        if default_value.is_some() || field.lenient_bool || field.json_pointer.is_some() {
+            panic!("flag is not supported with default, lenient_bool or json_pointer");
+        }
+        // Set to anything, even empty, turns the flag on.
+        return quote! {
+            {
+                let (_, value) = #lookup;
+                value.is_some()
+            }
+        };
+    }
+    if !field.append_from.is_empty() && (inner_field_type.is_some() || field.path_list) {
+        panic!("append_from is only supported on Vec fields which are not Option or path_list");
+    }
+    if field.path_list {
+        let list_type = inner_field_type.unwrap_or(field_type);
+        if !is_path_buf_vec(list_type) {
+            panic!("path_list is only supported on Vec<PathBuf> fields");
+        }
+        let var_name = match var_name {
+            Some(var_name) => quote! { #var_name.to_string() },
+            None => env_var_name,
+        };
+        let value = match (inner_field_type, default_value) {
+            (Some(_), None) => {
+                return quote! {
+                    source.get_os(&#var_name)?
+                        .map(|value| ::derive_from_env::_inner_helpers::split_path_list(&value))
+                }
+            }
+            (_, Some(default)) => quote! {
+                source.get_os(&var_name)?.unwrap_or_else(|| #default.to_string().into())
+            },
+            (None, None) if env_struct.all_defaultable || field.bare_default() => quote! {
+                source.get_os(&var_name)?.unwrap_or_default()
+            },
+            (None, None) => quote! {
+                source.get_os(&var_name)?
+                    .ok_or_else(|| ::derive_from_env::FromEnvError::MissingEnvVar {
+                        origin: source.origin(&var_name),
+                        var_name: var_name.clone(),
+                        fallbacks: Vec::new(),
+                    })?
+            },
+        };
+        // `Option` fields with a default always hold a list.
+        let list = quote! { ::derive_from_env::_inner_helpers::split_path_list(&#value) };
+        let list = match inner_field_type {
+            Some(_) => quote! { Some(#list) },
+            None => list,
+        };
+        return quote! {
+            {
+                let var_name = #var_name;
+                #list
+            }
+        };
+    }
+    let value_type = inner_field_type.unwrap_or(field_type);
+    let reads_string = field.credential.is_some()
+        || file.is_some()
+        || trim
+        || !fallbacks.is_empty()
+        || field.json_pointer.is_some()
+        || field.default_fn.is_some()
+        || field.non_empty.is_some()
+        || compat.is_some();
+    if is_os_string(value_type) && reads_string {
+        panic!("OsString fields are not supported with credential, file, trim, several var names, alias, alias_var, default_env, json_pointer, default_fn, non_empty or compat");
+    }
+    // `PathBuf` fields are read the same way unless an attribute needs the value as a `String`.
+    let is_plain_path_buf = matches!(value_type, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|segment| segment.ident == "PathBuf"))
+        && !reads_string
+        && non_empty.is_none()
+        && !from_str
+        && field.from.is_none()
+        && field.try_from.is_none()
+        && field.parse_fn().is_none()
+        && env_struct.defaults_from.is_none();
+    if is_os_string(value_type) || is_plain_path_buf {
+        let var_name = match var_name {
+            Some(var_name) => quote! { #var_name.to_string() },
+            None => env_var_name,
+        };
+        // Read with `get_os` so that values which are not UTF-8 are kept.
+        let value = match (inner_field_type, default_value) {
+            (Some(_), Some(default)) => quote! {
+                Some(<#value_type>::from(source.get_os(&var_name)?.unwrap_or_else(|| #default.into())))
+            },
+            (Some(_), None) => quote! { source.get_os(&var_name)?.map(<#value_type>::from) },
+            (None, Some(default)) => quote! {
+                <#value_type>::from(source.get_os(&var_name)?.unwrap_or_else(|| #default.into()))
+            },
+            (None, None) if env_struct.all_defaultable || field.bare_default() => quote! {
+                <#value_type>::from(source.get_os(&var_name)?.unwrap_or_default())
+            },
+            (None, None) => quote! {
+                <#value_type>::from(source.get_os(&var_name)?
+                    .ok_or_else(|| ::derive_from_env::FromEnvError::MissingEnvVar {
+                        origin: source.origin(&var_name),
+                        var_name: var_name.clone(),
+                        fallbacks: Vec::new(),
+                    })?)
+            },
+        };
+        return quote! {
+            {
+                let var_name = #var_name;
+                #value
+            }
+        };
+    }
+    if field.json_pointer.is_some() {
+        if field.path_list || is_nested(field) {
+            panic!("json_pointer is only supported on fields read from a single variable");
+        }
+        if default_value.is_some() {
+            panic!("json_pointer is not supported with a default value");
+        }
+    }
+    if let Some(section_type) = optional_section(field) {
+        if field.deserialize
+            || field.lazy
+            || field.json_var.is_some()
+            || field.url_var.is_some()
+            || var_name.is_some()
+            || default_value.is_some()
+        {
+            panic!("flatten on Option fields is not supported with deserialize, lazy, json_var, url_var, var or default");
+        }
+        let prefix = match (no_prefix, compat) {
+            (true, _) => quote! { None },
+            (false, Some(Compat::Envy)) => quote! {
+                Some(#envy_prefix).filter(|prefix: &&str| !prefix.is_empty())
+            },
+            (false, None) => quote! { Some(&#env_var_name) },
+        };
+        // The section is loaded only when one of its required variables is set.
+        return quote! {
+            {
+                let prefix_name: Option<&str> = #prefix;
+                let specs = match prefix_name {
+                    Some(prefix) => <#section_type as ::derive_from_env::_inner_trait::FromEnv>::env_vars_with_prefix(prefix),
+                    None => <#section_type as ::derive_from_env::_inner_trait::FromEnv>::env_vars(),
+                };
+                if ::derive_from_env::_inner_helpers::section_is_set(source, &specs)? {
+                    Some(match prefix_name {
+                        Some(prefix) => <#section_type as ::derive_from_env::_inner_trait::FromEnv>::from_source_with_prefix(source, prefix)?,
+                        None => <#section_type as ::derive_from_env::_inner_trait::FromEnv>::from_source(source)?,
+                    })
+                } else {
+                    None
+                }
+            }
+        };
+    }
+    // The raw values of `zeroize` fields are wiped once parsed.
+    let parser = |field_type: &Type| {
+        let parser = generate_field_parser(field, field_type, env_struct);
+        if field.zeroize {
+            quote! {
+                {
+                    let value = #parser;
+                    ::derive_from_env::_inner_helpers::wipe_string(str_value);
+                    value
+                }
+            }
+        } else {
+            parser
+        }
+    };
+    if let Some(field_type) = inner_field_type {
+        let parser = parser(field_type);
+        let fallback = match (&field.default_fn, default_value) {
+            // The function may return the content of the `Option` as well.
+            (Some(default_fn), _) => {
+                quote! { ::core::convert::Into::<Option<#field_type>>::into(#default_fn()) }
+            }
+            (None, Some(default)) => quote! {
+                {
+                    let str_value = #default.to_string();
+                    let origin = ::derive_from_env::Origin::Default;
+                    Some(#parser?)
                 }
+            },
+            (None, None) => quote! { None },
+        };
+        let parsed = lenient(quote! { #parser.map(Some) }, &fallback);
+        quote! {
+            {
+                let (var_name, value) = #lookup;
+                match value {
+                    Some((str_value, origin)) => #parsed,
+                    None => #fallback,
+                }
+            }
+        }
+    } else if !flatten
+        && (is_value_type(field_type)
+            || field.default_fn.is_some()
+            || document_format(field).is_some()
+            || field.from.is_some()
+            || field.try_from.is_some()
+            || from_str
+            || field.parse_fn().is_some()
+            || default_value.is_some()
+            || var_name.is_some())
+    {
+        let parser = parser(field_type);
+        let fallback = match default_value {
+            // Spanned on the field type so that a missing `Default` implementation is reported there.
+            None if field.bare_default() => quote_spanned! {field_type.span()=>
+                <#field_type as ::core::default::Default>::default()
+            },
+            // Only called when the variable is not set.
+            None if field.default_fn.is_some() => {
+                let default_fn = field.default_fn.as_ref().unwrap();
+                quote! { ::core::convert::Into::<#field_type>::into(#default_fn()) }
             }
-            (None, None) => {
+            Some(default) => quote! {
+                {
+                    let str_value = #default.to_string();
+                    let origin = ::derive_from_env::Origin::Default;
+                    #parser?
+                }
+            },
+            None if env_struct.defaults_from.is_some() => {
+                let trait_default = generate_trait_default(field, env_struct).unwrap();
+                let otherwise = if env_struct.all_defaultable {
+                    quote_spanned! {field_type.span()=>
+                        <#field_type as ::core::default::Default>::default()
+                    }
+                } else {
+                    quote! {
+                        return Err(::derive_from_env::FromEnvError::MissingEnvVar {
+                            origin: source.origin(&var_name),
+                            fallbacks: vec![#file_fallback #(#fallbacks),*],
+                            var_name,
+                        })
+                    }
+                };
                 quote! {
-                    #field_type::from_str(&std::env::var(#env_var_name)
-                        .map_err(|_| ::derive_from_env::FromEnvError::MissingEnvVar{var_name: #env_var_name.to_string()})?)
-                        .map_err(|_| ::derive_from_env::FromEnvError::ParsingFailure{
-                            var_name: #env_var_name.to_string(),
-                            str_value: std::env::var(#env_var_name).unwrap(),
-                            expected_type: stringify!(#field_type).to_string()
-                        })?
+                    match #trait_default {
+                        Some(value) => value,
+                        None => #otherwise,
+                    }
+                }
+            }
+            // Spanned on the field type so that a missing `Default` implementation is reported there.
+            None if env_struct.all_defaultable => quote_spanned! {field_type.span()=>
+                <#field_type as ::core::default::Default>::default()
+            },
+            None => quote! {
+                return Err(::derive_from_env::FromEnvError::MissingEnvVar {
+                    origin: source.origin(&var_name),
+                    fallbacks: vec![#file_fallback #(#fallbacks),*],
+                    var_name,
+                })
+            },
+        };
+        let parsed = lenient(parser.clone(), &fallback);
+        let value = quote! {
+            {
+                let (var_name, value) = #lookup;
+                match value {
+                    Some((str_value, origin)) => #parsed,
+                    None => #fallback,
+                }
+            }
+        };
+        if field.append_from.is_empty() {
+            return value;
+        }
+        if !is_vec(field_type) {
+            panic!("append_from is only supported on Vec fields");
+        }
+        let append_from = &field.append_from;
+        quote! {
+            {
+                let mut values = #value;
+                for var_name in [#(#append_from),*] {
+                    let var_name = var_name.to_string();
+                    if let Some((str_value, origin)) = source.lookup(&var_name)? {
+                        values.extend(#parser?);
+                    }
                 }
+                values
             }
         }
     } else {
@@ -211,14 +2720,726 @@ fn generate_field_loader(field: &EnvField, prefix: bool) -> proc_macro2::TokenSt
         if var_name.is_some() {
             panic!("Variable name specification is not suited for structured fields")
         }
-        if no_prefix {
+        if (field.json_var.is_some() || field.url_var.is_some())
+            && (field.deserialize || field.lazy)
+        {
+            panic!("json_var and url_var are not supported with deserialize or lazy");
+        }
+        let load = if field.deserialize {
+            quote! { ::derive_from_env::_inner_helpers::deserialize::<#field_type> }
+        } else {
+            quote! {
+                |source: &dyn ::derive_from_env::EnvSource, prefix: Option<&str>| match prefix {
+                    Some(prefix) => <#field_type as ::derive_from_env::_inner_trait::FromEnv>::from_source_with_prefix(source, prefix),
+                    None => <#field_type as ::derive_from_env::_inner_trait::FromEnv>::from_source(source),
+                }
+            }
+        };
+        let nested = match (no_prefix, compat) {
+            (true, _) => quote! { (#load)(source, None)? },
+            (false, Some(Compat::Envy)) => quote! {
+                {
+                    let prefix: &str = #envy_prefix;
+                    (#load)(source, Some(prefix).filter(|prefix| !prefix.is_empty()))?
+                }
+            },
+            (false, None) => quote! { (#load)(source, Some(&#env_var_name))? },
+        };
+        // Variables of the section, as given by `env_vars()`.
+        let section_specs = {
+            let prefix = match (no_prefix, compat) {
+                (true, _) => quote! { None },
+                (false, Some(Compat::Envy)) => quote! {
+                    Some(#envy_prefix).filter(|prefix: &&str| !prefix.is_empty())
+                },
+                (false, None) => quote! { Some(&#env_var_name) },
+            };
+            quote! {
+                match #prefix {
+                    Some(prefix) => <#field_type as ::derive_from_env::_inner_trait::FromEnv>::env_vars_with_prefix(prefix),
+                    None => <#field_type as ::derive_from_env::_inner_trait::FromEnv>::env_vars(),
+                }
+            }
+        };
+        let nested = if field.bare_default() {
+            if field.deserialize || field.lazy {
+                panic!("a bare default is not supported with deserialize or lazy");
+            }
+            // Spanned on the field type so that a missing `Default` implementation is reported there.
+            let default = quote_spanned! {field_type.span()=>
+                <#field_type as ::core::default::Default>::default()
+            };
+            quote! {
+                if ::derive_from_env::_inner_helpers::any_var_set(source, &#section_specs)? {
+                    #nested
+                } else {
+                    #default
+                }
+            }
+        } else {
+            nested
+        };
+        let nested = match &field.url_var {
+            // The whole section is parsed from the URL variable when it is set.
+            Some(url_var) => {
+                let field_type_name = type_name(field_type);
+                let conflicts = if field.deny_conflicts {
+                    quote! {
+                        ::derive_from_env::_inner_helpers::deny_conflicts(source, #url_var, &#section_specs)?;
+                    }
+                } else {
+                    quote! {}
+                };
+                quote! {
+                    match source.lookup(#url_var)? {
+                        Some((str_value, origin)) => {
+                            #conflicts
+                            <#field_type as FromStr>::from_str(&str_value).map_err(|_| {
+                                ::derive_from_env::FromEnvError::ParsingFailure {
+                                    var_name: #url_var.to_string(),
+                                    str_value: str_value.clone(),
+                                    origin,
+                                    expected_type: #field_type_name.to_string(),
+                                }
+                            })?
+                        }
+                        None => #nested,
+                    }
+                }
+            }
+            None => nested,
+        };
+        let Some(json_var) = &field.json_var else {
+            return nested;
+        };
+        // The whole section is read from the JSON variable when it is set.
+        let parser = generate_document_parser("json", field_type);
+        quote! {
+            match source.lookup(#json_var)? {
+                Some((str_value, origin)) => {
+                    let var_name = #json_var.to_string();
+                    let parsed_value: &str = &str_value;
+                    #parser
+                }
+                None => #nested,
+            }
+        }
+    }
+}
+
+/// Generates a statement appending the command-line arguments of the field to
+/// `args: Vec<String>`.
+fn generate_field_cli_args(
+    field: &EnvField,
+    identifier: &proc_macro2::TokenStream,
+    env_struct: &EnvStruct,
+) -> proc_macro2::TokenStream {
+    let field_name = field_name(field, env_struct);
+    let field_type = &field.ty;
+    if is_phantom_data(field_type) {
+        return quote! {};
+    }
+    let secret = is_secret(field);
+    let push = if (is_nested(field) || optional_section(field).is_some()) && !field.deserialize {
+        quote! {
+            ::derive_from_env::_inner_trait::CliArgs::cli_args(value, &path, style, redact, args);
+        }
+    } else {
+        let value_type = extract_inner_type_if_option(field_type).unwrap_or(field_type);
+        let is_bool =
+            matches!(value_type, Type::Path(type_path) if type_path.path.is_ident("bool"));
+        let rendered = if let Some(module) = &field.with {
+            // Collections are rendered item by item, the way `parse` reads them.
+            if map_types(value_type).is_some() {
+                let separator = list_separator(field, env_struct);
+                let kv_separator = kv_separator(field, env_struct);
+                quote! {
+                    value.iter().map(|(key, value)| format!("{}{}{}", key, #kv_separator, #module::to_string(value))).collect::<Vec<_>>().join(#separator)
+                }
+            } else if list_element(value_type).is_some() {
+                let separator = list_separator(field, env_struct);
+                quote! { value.iter().map(#module::to_string).collect::<Vec<_>>().join(#separator) }
+            } else {
+                quote! { #module::to_string(value) }
+            }
+        } else if let Some(format) = document_format(field) {
+            let render = format_ident!("to_{}", format);
+            quote! { ::derive_from_env::_inner_helpers::#render(value) }
+        } else if field.path_list {
+            quote! { ::derive_from_env::_inner_helpers::join_path_list(value) }
+        } else if let Some(tuple_elements) = tuple_elements(value_type) {
+            let separator = field.tuple_separator.as_deref().unwrap_or(",");
+            let indexes = (0..tuple_elements.len()).map(syn::Index::from);
+            quote! { [#(value.#indexes.to_string()),*].join(#separator) }
+        } else if map_types(value_type).is_some() {
+            let separator = list_separator(field, env_struct);
+            let kv_separator = kv_separator(field, env_struct);
             quote! {
-                <#field_type as ::derive_from_env::_inner_trait::FromEnv>::from_env()?
+                value.iter().map(|(key, value)| format!("{}{}{}", key, #kv_separator, value)).collect::<Vec<_>>().join(#separator)
             }
+        } else if list_element(value_type).is_some() {
+            let separator = list_separator(field, env_struct);
+            quote! { value.iter().map(ToString::to_string).collect::<Vec<_>>().join(#separator) }
+        } else if range_bounds(value_type).is_some() {
+            quote! { format!("{:?}", value) }
+        } else if secret_inner(value_type).is_some() {
+            quote! { "<redacted>" }
+        } else if is_os_string(value_type) {
+            quote! { value.to_string_lossy() }
+        } else if is_duration(value_type) {
+            quote! { ::derive_from_env::_inner_helpers::format_duration(value) }
+        } else {
+            quote_spanned! {value_type.span()=> value.to_string() }
+        };
+        if is_bool {
+            quote! { ::derive_from_env::_inner_helpers::push_flag(args, &path, *value, style, redact); }
         } else {
+            quote! { ::derive_from_env::_inner_helpers::push_arg(args, &path, &#rendered, style, redact); }
+        }
+    };
+    let value = if extract_inner_type_if_option(field_type).is_some() {
+        quote! { let Some(value) = &self.#identifier else { return; }; }
+    } else {
+        quote! { let value = &self.#identifier; }
+    };
+    quote! {
+        (|| {
+            let redact = redact || #secret;
+            if #secret && style.skip_secrets {
+                return;
+            }
+            #value
+            let path = ::derive_from_env::_inner_helpers::arg_path(path, #field_name);
+            #push
+        })();
+    }
+}
+
+/// Generates a statement adding the differences of the field between `self` and `other` to
+/// `diffs: Vec<FieldDiff>`.
+fn generate_field_diff(
+    field: &EnvField,
+    identifier: &proc_macro2::TokenStream,
+    prefix: bool,
+    env_struct: &EnvStruct,
+) -> proc_macro2::TokenStream {
+    let field_name = field_name(field, env_struct);
+    let field_type = &field.ty;
+    let env_var_name = generate_env_var_name(field, prefix, env_struct);
+    let redact = if is_secret(field) {
+        quote! { ::derive_from_env::_inner_helpers::redact_diffs }
+    } else {
+        quote! { ::std::convert::identity }
+    };
+    if is_nested(field) && !field.deserialize {
+        let diff_of = quote! {
+            |prefix: Option<&str>| match prefix {
+                Some(prefix) => <#field_type as ::derive_from_env::_inner_trait::EnvDiff>::env_diff_with_prefix(&self.#identifier, &other.#identifier, prefix),
+                None => <#field_type as ::derive_from_env::_inner_trait::EnvDiff>::env_diff(&self.#identifier, &other.#identifier),
+            }
+        };
+        let nested_diffs = match (field.no_prefix, env_struct.compat) {
+            (true, _) => quote! { (#diff_of)(None) },
+            (false, Some(Compat::Envy)) => {
+                let envy_prefix = generate_envy_prefix(prefix, env_struct);
+                quote! {
+                    {
+                        let prefix: &str = #envy_prefix;
+                        (#diff_of)(Some(prefix).filter(|prefix| !prefix.is_empty()))
+                    }
+                }
+            }
+            (false, None) => quote! { (#diff_of)(Some(&#env_var_name)) },
+        };
+        return quote! {
+            diffs.extend((#redact)(::derive_from_env::_inner_helpers::nest_diffs(#field_name, #nested_diffs)));
+        };
+    }
+    let var_name = match field.var_name() {
+        Some(var_name) => quote! { #var_name.to_string() },
+        None => env_var_name,
+    };
+    // Spanned on the field type so that missing `PartialEq` or `Debug` implementations are
+    // reported there.
+    quote_spanned! {field_type.span()=>
+        if self.#identifier != other.#identifier {
+            diffs.extend((#redact)(vec![::derive_from_env::FieldDiff {
+                field: #field_name.to_string(),
+                var_name: #var_name,
+                old: format!("{:?}", self.#identifier),
+                new: format!("{:?}", other.#identifier),
+            }]));
+        }
+    }
+}
+
+/// Generates a statement parsing the `default` attribute of the field, returning the error of an
+/// invalid default, or nothing when the field has no default.
+fn generate_default_check(field: &EnvField, env_struct: &EnvStruct) -> proc_macro2::TokenStream {
+    // `path_list` defaults are split rather than parsed.
+    let Some(default) = field.default_value().filter(|_| !field.path_list) else {
+        return quote! {};
+    };
+    let var_name = match field.var_name() {
+        Some(var_name) => quote! { #var_name.to_string() },
+        None => {
+            let var_name = static_env_var_name(field, env_struct);
+            quote! { #var_name.to_string() }
+        }
+    };
+    let value_type = extract_inner_type_if_option(&field.ty).unwrap_or(&field.ty);
+    let parser = generate_field_parser(field, value_type, env_struct);
+    quote! {
+        {
+            let var_name = #var_name;
+            let str_value = #default.to_string();
+            let origin = ::derive_from_env::Origin::Default;
+            #parser?;
+        }
+    }
+}
+
+/// Generates an expression combining the field of `self` and `other` according to `policy`.
+fn generate_field_merge(
+    field: &EnvField,
+    identifier: &proc_macro2::TokenStream,
+    env_struct: &EnvStruct,
+) -> proc_macro2::TokenStream {
+    let field_type = &field.ty;
+    if is_nested(field) && !field.deserialize {
+        return quote! {
+            <#field_type as ::derive_from_env::_inner_trait::EnvMerge>::env_merge(self.#identifier, other.#identifier, policy)
+        };
+    }
+    // Whether the field of `other` holds its default.
+    let is_default = match (
+        field.default_value(),
+        extract_inner_type_if_option(field_type),
+    ) {
+        // Unset, or set to the default.
+        (Some(default), Some(_)) if field.path_list => quote! {
+            other.#identifier.is_none()
+                || other.#identifier == Some(::derive_from_env::_inner_helpers::split_path_list(::std::ffi::OsStr::new(#default)))
+        },
+        (Some(default), Some(inner_type)) => {
+            let parser = generate_field_parser(field, inner_type, env_struct);
+            quote! {
+                other.#identifier.is_none() || {
+                    let var_name = String::new();
+                    let str_value = #default.to_string();
+                    let origin = ::derive_from_env::Origin::Default;
+                    #parser.is_ok_and(|default| Some(default) == other.#identifier)
+                }
+            }
+        }
+        (_, Some(_)) => quote! { other.#identifier.is_none() },
+        (Some(default), None) if field.path_list => quote! {
+            other.#identifier == ::derive_from_env::_inner_helpers::split_path_list(::std::ffi::OsStr::new(#default))
+        },
+        (Some(default), None) => {
+            let parser = generate_field_parser(field, field_type, env_struct);
+            quote! {
+                {
+                    let var_name = String::new();
+                    let str_value = #default.to_string();
+                    let origin = ::derive_from_env::Origin::Default;
+                    #parser.is_ok_and(|default| default == other.#identifier)
+                }
+            }
+        }
+        (None, None) if field.default_fn.is_some() => {
+            let default_fn = field.default_fn.as_ref().unwrap();
+            quote_spanned! {field_type.span()=>
+                other.#identifier == ::core::convert::Into::<#field_type>::into(#default_fn())
+            }
+        }
+        (None, None)
+            if (env_struct.all_defaultable || field.bare_default()) && !field.deserialize =>
+        {
+            quote_spanned! {field_type.span()=>
+                other.#identifier == <#field_type as ::core::default::Default>::default()
+            }
+        }
+        (None, None) => quote! { false },
+    };
+    quote! {
+        match policy {
+            ::derive_from_env::MergePolicy::PreferSelf => self.#identifier,
+            ::derive_from_env::MergePolicy::PreferOther => other.#identifier,
+            ::derive_from_env::MergePolicy::PreferNonDefault => {
+                if #is_default {
+                    self.#identifier
+                } else {
+                    other.#identifier
+                }
+            }
+        }
+    }
+}
+
+/// Generates a statement returning the resolution plan of the field when `field_path` designates
+/// it (or one of its fields for nested structs).
+fn generate_field_explain(
+    field: &EnvField,
+    prefix: bool,
+    env_struct: &EnvStruct,
+) -> proc_macro2::TokenStream {
+    let field_name = field_name(field, env_struct);
+    let field_type = &field.ty;
+    let env_var_name = generate_env_var_name(field, prefix, env_struct);
+    // Optional sections are left out, their variables being required only once one is set.
+    if field.indexed
+        || field.count_var.is_some()
+        || optional_section(field).is_some()
+        || is_phantom_data(field_type)
+    {
+        return quote! {};
+    }
+    if is_nested(field) {
+        if field.deserialize {
+            return quote! {};
+        }
+        let explain_of = quote! {
+            |prefix: Option<&str>| match prefix {
+                Some(prefix) => <#field_type as ::derive_from_env::_inner_trait::FromEnv>::explain_from_source_with_prefix(source, rest, prefix),
+                None => <#field_type as ::derive_from_env::_inner_trait::FromEnv>::explain_from_source(source, rest),
+            }
+        };
+        let nested_plan = match (field.no_prefix, env_struct.compat) {
+            (true, _) => quote! { (#explain_of)(None) },
+            (false, Some(Compat::Envy)) => {
+                let envy_prefix = generate_envy_prefix(prefix, env_struct);
+                quote! {
+                    {
+                        let prefix: &str = #envy_prefix;
+                        (#explain_of)(Some(prefix).filter(|prefix| !prefix.is_empty()))
+                    }
+                }
+            }
+            (false, None) => quote! { (#explain_of)(Some(&#env_var_name)) },
+        };
+        return quote! {
+            if let Some(rest) = ::derive_from_env::_inner_helpers::field_rest(field_path, #field_name) {
+                if let Some(plan) = #nested_plan {
+                    return Some(::derive_from_env::_inner_helpers::nest_plan(#field_name, plan));
+                }
+            }
+        };
+    }
+    let var_name = match (field.var_name(), env_struct.compat) {
+        (Some(var_name), _) => quote! { #var_name.to_string() },
+        (None, Some(Compat::Envy)) => {
+            let envy_prefix = generate_envy_prefix(prefix, env_struct);
+            let upper_name = field_name.to_uppercase();
+            quote! { format!("{}{}", #envy_prefix, #upper_name) }
+        }
+        (None, None) => env_var_name,
+    };
+    let secret = is_secret(field);
+    let credential = field.credential.iter();
+    let file = file_lookup(field, env_struct)
+        .map(|(suffix, _)| quote! { format!("{}{}", #var_name, #suffix) })
+        .into_iter();
+    let var_names = field.var.iter().flat_map(|names| &names.0[1..]);
+    let fallback_names = generate_fallback_names(field, prefix, env_struct);
+    let alias = fallback_names
+        .iter()
+        .skip(var_names.clone().count())
+        .take(field.alias.len() + field.alias_var.len());
+    let deprecated_alias = fallback_names
+        .iter()
+        .skip(var_names.clone().count() + field.alias.len() + field.alias_var.len())
+        .take(field.deprecated_alias.len());
+    let default_env = field.default_env.iter();
+    let fallback = match field.default_value() {
+        Some(default) => quote! {
+            steps.push(::derive_from_env::_inner_helpers::explain_default(#default, #secret));
+        },
+        // The function is not called, its value being unknown until the field is loaded.
+        None if field.default_fn.is_some() => {
+            let default_fn = field.default_fn.as_ref().unwrap();
+            let default_fn = quote!(#default_fn).to_string().replace(' ', "");
+            quote! {
+                steps.push(::derive_from_env::ResolutionStep {
+                    location: ::derive_from_env::Location::DefaultFn(#default_fn.to_string()),
+                    origin: None,
+                    value: None,
+                });
+            }
+        }
+        None if field.flag
+            || field.bare_default()
+            || (env_struct.all_defaultable
+                && extract_inner_type_if_option(field_type).is_none()) =>
+        {
             quote! {
-                <#field_type as ::derive_from_env::_inner_trait::FromEnv>::from_env_with_prefix(&#env_var_name)?
+                steps.push(::derive_from_env::ResolutionStep {
+                    location: ::derive_from_env::Location::TypeDefault,
+                    origin: None,
+                    value: None,
+                });
             }
         }
+        None => quote! {},
+    };
+    let append_from = &field.append_from;
+    quote! {
+        if field_path == #field_name {
+            let mut steps = Vec::new();
+            #(
+                steps.push(::derive_from_env::_inner_helpers::explain_credential(source, #credential, #secret));
+            )*
+            steps.push(::derive_from_env::_inner_helpers::explain_lookup(
+                source,
+                ::derive_from_env::Location::Variable(#var_name),
+                #secret,
+            ));
+            #(
+                steps.push(::derive_from_env::_inner_helpers::explain_file(source, #file, #secret));
+            )*
+            #(
+                steps.push(::derive_from_env::_inner_helpers::explain_lookup(
+                    source,
+                    ::derive_from_env::Location::Variable(#var_names.to_string()),
+                    #secret,
+                ));
+            )*
+            #(
+                steps.push(::derive_from_env::_inner_helpers::explain_lookup(
+                    source,
+                    ::derive_from_env::Location::Alias(#alias),
+                    #secret,
+                ));
+            )*
+            #(
+                steps.push(::derive_from_env::_inner_helpers::explain_lookup(
+                    source,
+                    ::derive_from_env::Location::DeprecatedAlias(#deprecated_alias),
+                    #secret,
+                ));
+            )*
+            #(
+                steps.push(::derive_from_env::_inner_helpers::explain_lookup(
+                    source,
+                    ::derive_from_env::Location::DefaultEnv(#default_env.to_string()),
+                    #secret,
+                ));
+            )*
+            #fallback
+            #(
+                steps.push(::derive_from_env::_inner_helpers::explain_lookup(
+                    source,
+                    ::derive_from_env::Location::AppendFrom(#append_from.to_string()),
+                    #secret,
+                ));
+            )*
+            return Some(::derive_from_env::ResolutionPlan {
+                field: #field_name.to_string(),
+                steps,
+            });
+        }
+    }
+}
+
+/// Generates a statement adding the variables read for the field to `specs: Vec<EnvVarSpec>`.
+fn generate_field_spec(
+    field: &EnvField,
+    prefix: bool,
+    env_struct: &EnvStruct,
+) -> proc_macro2::TokenStream {
+    let field_name = field_name(field, env_struct);
+    let field_type = &field.ty;
+    let env_var_name = generate_env_var_name(field, prefix, env_struct);
+    if is_phantom_data(field_type) {
+        return quote! {};
+    }
+    // Elements are described by the variables of the first one.
+    if field.indexed {
+        let element_type = list_element(field_type).unwrap();
+        let element_field = format!("{}.0", field_name);
+        return quote! {
+            specs.extend(::derive_from_env::_inner_helpers::nest_specs(
+                #element_field,
+                <#element_type as ::derive_from_env::_inner_trait::FromEnv>::env_vars_with_prefix(&format!("{}_0", #env_var_name)),
+            ));
+        };
+    }
+    if is_nested(field) || optional_section(field).is_some() {
+        // Deserialize-only types can't describe the variables they read.
+        if field.deserialize {
+            return quote! {};
+        }
+        let section_type = optional_section(field).unwrap_or(field_type);
+        let specs_of = quote! {
+            |prefix: Option<&str>| match prefix {
+                Some(prefix) => <#section_type as ::derive_from_env::_inner_trait::FromEnv>::env_vars_with_prefix(prefix),
+                None => <#section_type as ::derive_from_env::_inner_trait::FromEnv>::env_vars(),
+            }
+        };
+        let nested_specs = match (field.no_prefix, env_struct.compat) {
+            (true, _) => quote! { (#specs_of)(None) },
+            (false, Some(Compat::Envy)) => {
+                let envy_prefix = generate_envy_prefix(prefix, env_struct);
+                quote! {
+                    {
+                        let prefix: &str = #envy_prefix;
+                        (#specs_of)(Some(prefix).filter(|prefix| !prefix.is_empty()))
+                    }
+                }
+            }
+            (false, None) => quote! { (#specs_of)(Some(&#env_var_name)) },
+        };
+        let hide = if field.doc_hidden {
+            quote! { .into_iter().map(|spec| ::derive_from_env::EnvVarSpec { hidden: true, ..spec }) }
+        } else {
+            quote! {}
+        };
+        // None of the variables of an optional section is required.
+        let optional = if optional_section(field).is_some() {
+            quote! {
+                .into_iter().map(|spec| ::derive_from_env::EnvVarSpec {
+                    optional: spec.optional || spec.required,
+                    required: false,
+                    ..spec
+                })
+            }
+        } else if field.bare_default() {
+            // The section defaults as a whole when none of its variables is set.
+            quote! {
+                .into_iter().map(|spec| ::derive_from_env::EnvVarSpec { required: false, ..spec })
+            }
+        } else {
+            quote! {}
+        };
+        let hidden = field.doc_hidden;
+        // Variables giving the whole section, along with their type.
+        let (section_vars, section_type_names): (Vec<_>, Vec<_>) = field
+            .json_var
+            .iter()
+            .map(|json_var| (json_var, format!("JSON {}", type_name(field_type))))
+            .chain(
+                field
+                    .url_var
+                    .iter()
+                    .map(|url_var| (url_var, format!("{} URL", type_name(field_type)))),
+            )
+            .unzip();
+        let section_specs = quote! {
+            #(
+                specs.push(::derive_from_env::EnvVarSpec {
+                    name: #section_vars.to_string(),
+                    field: #field_name.to_string(),
+                    type_name: #section_type_names.to_string(),
+                    default: None,
+                    required: false,
+                    optional: true,
+                    hidden: #hidden,
+                    secret: false,
+                });
+            )*
+            specs.extend(::derive_from_env::_inner_helpers::nest_specs(#field_name, #nested_specs)#hide #optional);
+        };
+        // Nested structs read their variables through the `file_suffix` of the struct as well.
+        return match &env_struct.file_suffix {
+            Some(suffix) if !field.no_file => quote! {
+                {
+                    let start = specs.len();
+                    #section_specs
+                    let section_specs = specs.split_off(start);
+                    specs.extend(::derive_from_env::_inner_helpers::with_file_specs(section_specs, #suffix));
+                }
+            },
+            _ => section_specs,
+        };
+    }
+    let var_name = match field.var_name() {
+        Some(var_name) => quote! { #var_name.to_string() },
+        None => env_var_name,
+    };
+    let inner_field_type = extract_inner_type_if_option(field_type);
+    let optional = inner_field_type.is_some();
+    // The items are described by the count, their names depending on it.
+    let (var_name, type_name) = match &field.count_var {
+        Some(count_var) => (quote! { #count_var.to_string() }, "usize".to_string()),
+        None => (var_name, field_type_name(field)),
+    };
+    let required = !optional
+        && field.default_value().is_none()
+        && field.default_fn.is_none()
+        && !field.bare_default()
+        && !field.flag
+        && !env_struct.all_defaultable;
+    // Computed when the specs are requested, the trait method giving `None` for no default.
+    let required = match generate_trait_default(field, env_struct) {
+        Some(trait_default) if required => quote! { #trait_default.is_none() },
+        _ => quote! { #required },
+    };
+    let default_value = match field.default_value() {
+        Some(default) => quote! { Some(#default.to_string()) },
+        None => quote! { None },
+    };
+    let append_from = &field.append_from;
+    // The `<NAME>_FILE` variable, read when the variable is not set.
+    let (file_vars, file_type_name): (Vec<_>, _) = match file_lookup(field, env_struct) {
+        Some((suffix, _)) => (
+            vec![quote! { format!("{}{}", #var_name, #suffix) }],
+            format!("path of a file holding {}", type_name),
+        ),
+        None => (Vec::new(), String::new()),
+    };
+    // The other names of the variable, read when it is not set.
+    let fallbacks = match field.count_var {
+        Some(_) => Vec::new(),
+        None => generate_fallback_names(field, prefix, env_struct),
+    };
+    let hidden = field.doc_hidden;
+    let secret = is_secret(field);
+    quote! {
+        specs.push(::derive_from_env::EnvVarSpec {
+            name: #var_name,
+            field: #field_name.to_string(),
+            type_name: #type_name.to_string(),
+            default: #default_value,
+            required: #required,
+            optional: #optional,
+            hidden: #hidden,
+            secret: #secret,
+        });
+        #(
+            specs.push(::derive_from_env::EnvVarSpec {
+                name: #file_vars,
+                field: #field_name.to_string(),
+                type_name: #file_type_name.to_string(),
+                default: None,
+                required: false,
+                optional: true,
+                hidden: #hidden,
+                secret: false,
+            });
+        )*
+        #(
+            specs.push(::derive_from_env::EnvVarSpec {
+                name: #fallbacks,
+                field: #field_name.to_string(),
+                type_name: #type_name.to_string(),
+                default: None,
+                required: false,
+                optional: true,
+                hidden: #hidden,
+                secret: #secret,
+            });
+        )*
+        #(
+            specs.push(::derive_from_env::EnvVarSpec {
+                name: #append_from.to_string(),
+                field: #field_name.to_string(),
+                type_name: #type_name.to_string(),
+                default: None,
+                required: false,
+                optional: true,
+                hidden: #hidden,
+                secret: #secret,
+            });
+        )*
     }
 }
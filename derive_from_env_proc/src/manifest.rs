@@ -0,0 +1,304 @@
+//! Manifest of the variables declared by derived structs, written at compile time when
+//! `DERIVE_FROM_ENV_EMIT_MANIFEST` is set.
+//!
+//! The variable names the directory to write to, or is set to `1` to write to
+//! `$OUT_DIR/derive_from_env_manifest`. Each struct gets its own `<crate>.<Struct>.<hash>.jsonl`
+//! file, the hash of its lines telling apart structs of the same name in different modules. Files
+//! are written to a temporary file then renamed, so that concurrent expansions never interleave.
+//! Each line describes a field: either the variable it is read from by `from_env()`, or for nested
+//! structs the prefix they are given.
+
+use std::io;
+use std::path::PathBuf;
+
+use crate::{
+    extract_inner_type_if_option, field_name, field_type_name, is_nested, is_phantom_data,
+    is_secret, static_env_var_name, type_name, Compat, EnvField, EnvStruct,
+};
+
+pub const EMIT_MANIFEST_VAR: &str = "DERIVE_FROM_ENV_EMIT_MANIFEST";
+
+/// Writes the manifest of the struct if requested. Write failures are ignored, the manifest must
+/// never break the build, but asking for `$OUT_DIR` in a crate without one is reported.
+pub fn emit(struct_name: &str, fields: &[EnvField], env_struct: &EnvStruct) -> Result<(), String> {
+    let Some(dir) = manifest_dir()? else {
+        return Ok(());
+    };
+    let _ = write(
+        dir,
+        struct_name,
+        &manifest_lines(struct_name, fields, env_struct),
+    );
+    Ok(())
+}
+
+fn manifest_dir() -> Result<Option<PathBuf>, String> {
+    let Some(value) = std::env::var_os(EMIT_MANIFEST_VAR) else {
+        return Ok(None);
+    };
+    if !value.is_empty() && value != "1" {
+        return Ok(Some(PathBuf::from(value)));
+    }
+    match std::env::var_os("OUT_DIR") {
+        Some(out_dir) => Ok(Some(
+            PathBuf::from(out_dir).join("derive_from_env_manifest"),
+        )),
+        None => Err(format!(
+            "{}=1 writes the manifest to $OUT_DIR, which is only set for crates with a build \
+             script; set it to a directory instead",
+            EMIT_MANIFEST_VAR
+        )),
+    }
+}
+
+fn write(dir: PathBuf, struct_name: &str, lines: &[String]) -> io::Result<()> {
+    std::fs::create_dir_all(&dir)?;
+    let crate_name = std::env::var("CARGO_CRATE_NAME").unwrap_or_else(|_| "unknown".into());
+    let content = lines.concat();
+    let file_name = format!(
+        "{}.{}.{:016x}.jsonl",
+        crate_name,
+        struct_name,
+        fnv1a(content.as_bytes())
+    );
+    let tmp_path = dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+    std::fs::write(&tmp_path, content)?;
+    std::fs::rename(&tmp_path, dir.join(file_name)).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp_path);
+    })
+}
+
+pub fn manifest_lines(
+    struct_name: &str,
+    fields: &[EnvField],
+    env_struct: &EnvStruct,
+) -> Vec<String> {
+    fields
+        .iter()
+        .filter(|field| !is_phantom_data(&field.ty))
+        .map(|field| {
+            let field_name = field_name(field, env_struct);
+            let mut entries = vec![
+                ("struct", json_string(struct_name)),
+                ("field", json_string(&field_name)),
+            ];
+            if is_nested(field) {
+                let prefix = if field.no_prefix {
+                    None
+                } else if env_struct.compat == Some(Compat::Envy) {
+                    env_struct
+                        .prefix
+                        .clone()
+                        .filter(|prefix| !prefix.is_empty())
+                } else {
+                    Some(static_env_var_name(field, env_struct))
+                };
+                entries.push(("nested", json_string(&type_name(&field.ty))));
+                entries.push((
+                    "prefix",
+                    prefix.as_deref().map_or("null".into(), json_string),
+                ));
+            } else {
+                let var_name = match field.var_name() {
+                    Some(var_name) => lit_value(var_name),
+                    None => static_env_var_name(field, env_struct),
+                };
+                let inner_field_type = extract_inner_type_if_option(&field.ty);
+                let optional = inner_field_type.is_some();
+                let required = !optional
+                    && field.default_value().is_none()
+                    && field.default_fn.is_none()
+                    && !field.bare_default()
+                    && !env_struct.all_defaultable;
+                let default = field.default_value().map(lit_value);
+                entries.push(("var", json_string(&var_name)));
+                entries.push(("type", json_string(&field_type_name(field))));
+                entries.push(("required", required.to_string()));
+                entries.push(("optional", optional.to_string()));
+                entries.push(("hidden", field.doc_hidden.to_string()));
+                entries.push(("secret", is_secret(field).to_string()));
+                entries.push((
+                    "default",
+                    default.as_deref().map_or("null".into(), json_string),
+                ));
+            }
+            let entries = entries
+                .iter()
+                .map(|(key, value)| format!("\"{}\":{}", key, value))
+                .collect::<Vec<_>>();
+            format!("{{{}}}\n", entries.join(","))
+        })
+        .collect()
+}
+
+/// 64-bit FNV-1a hash, stable across compilers unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// The value of a literal as written in an attribute, without quotes for strings.
+fn lit_value(lit: &syn::Lit) -> String {
+    match lit {
+        syn::Lit::Str(lit) => lit.value(),
+        lit => quote::quote!(#lit).to_string(),
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut json = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use darling::{FromDeriveInput, FromField};
+    use syn::{Data, DeriveInput};
+
+    use super::*;
+
+    /// Tests set the process environment read by `emit`.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn parse(input: DeriveInput) -> (EnvStruct, Vec<EnvField>) {
+        let env_struct = EnvStruct::from_derive_input(&input).unwrap();
+        let Data::Struct(data) = &input.data else {
+            unreachable!()
+        };
+        let fields = data
+            .fields
+            .iter()
+            .map(|field| EnvField::from_field(field).unwrap())
+            .collect::<Vec<_>>();
+        (env_struct, fields)
+    }
+
+    /// Emits the manifests of the structs, all named `Config`, and returns the files written.
+    fn emit_all(test: &str, inputs: Vec<DeriveInput>) -> Vec<(String, String)> {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let dir = std::env::temp_dir().join(format!(
+            "derive_from_env_manifest_{}_{}",
+            test,
+            std::process::id()
+        ));
+        std::env::set_var(EMIT_MANIFEST_VAR, &dir);
+        std::env::set_var("CARGO_CRATE_NAME", "fixture");
+        for input in inputs {
+            let (env_struct, fields) = parse(input);
+            emit("Config", &fields, &env_struct).unwrap();
+        }
+        std::env::remove_var(EMIT_MANIFEST_VAR);
+        let mut files = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                (name, std::fs::read_to_string(&path).unwrap())
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        std::fs::remove_dir_all(&dir).unwrap();
+        files
+    }
+
+    #[test]
+    fn test_emit_manifest() {
+        let files = emit_all(
+            "single",
+            vec![syn::parse_quote! {
+                #[from_env(prefix = "APP_")]
+                struct Config {
+                    #[from_env(default = "8080")]
+                    port: u16,
+                    name: String,
+                    #[from_env(var = "API_TOKEN", secret)]
+                    token: Option<String>,
+                    database: Database,
+                }
+            }],
+        );
+        assert_eq!(files.len(), 1);
+        let (file_name, manifest) = &files[0];
+        assert!(
+            file_name.starts_with("fixture.Config.") && file_name.ends_with(".jsonl"),
+            "{}",
+            file_name
+        );
+        let lines = manifest
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                serde_json::json!({"struct": "Config", "field": "port", "var": "APP_PORT", "type": "u16", "required": false, "optional": false, "hidden": false, "secret": false, "default": "8080"}),
+                serde_json::json!({"struct": "Config", "field": "name", "var": "APP_NAME", "type": "String", "required": true, "optional": false, "hidden": false, "secret": false, "default": null}),
+                serde_json::json!({"struct": "Config", "field": "token", "var": "API_TOKEN", "type": "String", "required": false, "optional": true, "hidden": false, "secret": true, "default": null}),
+                serde_json::json!({"struct": "Config", "field": "database", "nested": "Database", "prefix": "APP_DATABASE"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_emit_manifest_same_struct_name() {
+        // As for two `Config` structs in different modules of the crate.
+        let files = emit_all(
+            "same_name",
+            vec![
+                syn::parse_quote! {
+                    struct Config {
+                        port: u16,
+                    }
+                },
+                syn::parse_quote! {
+                    struct Config {
+                        host: String,
+                    }
+                },
+            ],
+        );
+        assert_eq!(files.len(), 2);
+        let vars = files
+            .iter()
+            .map(|(_, manifest)| {
+                serde_json::from_str::<serde_json::Value>(manifest.trim()).unwrap()["var"].clone()
+            })
+            .collect::<Vec<_>>();
+        assert!(vars.contains(&serde_json::json!("PORT")), "{:?}", vars);
+        assert!(vars.contains(&serde_json::json!("HOST")), "{:?}", vars);
+    }
+
+    #[test]
+    fn test_emit_manifest_without_out_dir() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let out_dir = std::env::var_os("OUT_DIR");
+        std::env::remove_var("OUT_DIR");
+        std::env::set_var(EMIT_MANIFEST_VAR, "1");
+        let (env_struct, fields) = parse(syn::parse_quote! {
+            struct Config {
+                port: u16,
+            }
+        });
+        let result = emit("Config", &fields, &env_struct);
+        std::env::remove_var(EMIT_MANIFEST_VAR);
+        if let Some(out_dir) = out_dir {
+            std::env::set_var("OUT_DIR", out_dir);
+        }
+        assert!(result.unwrap_err().contains("$OUT_DIR"));
+    }
+}
@@ -0,0 +1,202 @@
+//! `FromEnv` for enums with struct variants, the variant being selected by the variable named by
+//! `#[from_env(tag = "...")]`.
+
+use darling::{ast, FromVariant};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{
+    check_default_attributes, generate_default_check, generate_field_loader, generate_field_spec,
+    generate_inherent_impl, EnvField, EnvStruct,
+};
+
+#[derive(FromVariant)]
+#[darling(attributes(from_env))]
+struct TaggedVariant {
+    ident: syn::Ident,
+    fields: ast::Fields<EnvField>,
+    #[darling(default)]
+    rename: Option<String>,
+    #[darling(multiple)]
+    alias: Vec<String>,
+}
+
+pub fn expand(data: &syn::Data, env_struct: &EnvStruct) -> TokenStream {
+    let Some(tag) = &env_struct.tag else {
+        panic!("enums with fields require #[from_env(tag = \"...\")]");
+    };
+    if env_struct.compat.is_some()
+        || env_struct.collapse_prefix
+        || env_struct.defaults_from.is_some()
+    {
+        panic!("tagged enums are not supported with compat, collapse_prefix or defaults_from");
+    }
+    if env_struct.diff || env_struct.merge || env_struct.cli_args {
+        panic!("diff, merge and cli_args are not supported on tagged enums");
+    }
+    if !env_struct.generics.params.is_empty() {
+        panic!("generic tagged enums are not supported");
+    }
+    let enum_identifier = &env_struct.ident;
+    if let syn::Data::Enum(data) = data {
+        data.variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .for_each(check_default_attributes);
+    }
+    let variants = ast::Data::<TaggedVariant, ()>::try_from(data)
+        .unwrap()
+        .take_enum()
+        .unwrap();
+    if variants
+        .iter()
+        .any(|variant| variant.fields.style == ast::Style::Tuple)
+    {
+        panic!("tuple variants are not supported on tagged enums, use named fields");
+    }
+    let names = variants
+        .iter()
+        .map(|variant| {
+            variant
+                .rename
+                .clone()
+                .unwrap_or_else(|| variant.ident.to_string())
+        })
+        .collect::<Vec<_>>();
+    let accepted_names = variants
+        .iter()
+        .zip(&names)
+        .map(|(variant, name)| {
+            std::iter::once(name)
+                .chain(&variant.alias)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let expected = format!("one of: {}", names.join(", "));
+    let struct_prefix = env_struct.prefix.clone().unwrap_or_default();
+    let tag_name = format!("{}{}", struct_prefix, tag);
+
+    // Builds the variant, reading only the variables of its fields.
+    let constructors = |prefix: bool| {
+        variants
+            .iter()
+            .map(|variant| {
+                let variant_identifier = &variant.ident;
+                let field_identifiers = variant.fields.iter().map(|field| &field.ident);
+                let field_loaders = variant
+                    .fields
+                    .iter()
+                    .map(|field| generate_field_loader(field, prefix, env_struct));
+                quote! {
+                    #enum_identifier::#variant_identifier {
+                        #( #field_identifiers: #field_loaders ),*
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let constructors_without_prefix = constructors(false);
+    let constructors_with_prefix = constructors(true);
+    // Variables of the fields of each variant, under the name of the variant.
+    let variant_specs = |prefix: bool| {
+        variants
+            .iter()
+            .zip(&names)
+            .map(|(variant, name)| {
+                let field_specs = variant
+                    .fields
+                    .iter()
+                    .map(|field| generate_field_spec(field, prefix, env_struct));
+                quote! {
+                    {
+                        let mut specs = Vec::new();
+                        #(#field_specs)*
+                        // Required only when the variant is selected.
+                        variant_specs.extend(
+                            ::derive_from_env::_inner_helpers::nest_specs(#name, specs)
+                                .into_iter()
+                                .map(|spec| ::derive_from_env::EnvVarSpec { required: false, ..spec }),
+                        );
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let variant_specs_without_prefix = variant_specs(false);
+    let variant_specs_with_prefix = variant_specs(true);
+    let default_checks = variants
+        .iter()
+        .flat_map(|variant| variant.fields.iter())
+        .map(|field| generate_default_check(field, env_struct))
+        .collect::<Vec<_>>();
+    let inherent_impl = generate_inherent_impl(
+        enum_identifier,
+        &env_struct.generics,
+        env_struct.check_defaults.then_some(&default_checks),
+    );
+    let tag_spec = quote! {
+        ::derive_from_env::EnvVarSpec {
+            name: tag_var.clone(),
+            field: String::new(),
+            type_name: #expected.to_string(),
+            default: None,
+            required: true,
+            optional: false,
+            hidden: false,
+            secret: false,
+        }
+    };
+    let select = |constructors: &[TokenStream]| {
+        quote! {
+            let (tag, origin) = source.lookup(&tag_var)?.ok_or_else(|| {
+                ::derive_from_env::FromEnvError::MissingEnvVar {
+                    origin: source.origin(&tag_var),
+                    var_name: tag_var.clone(),
+                    fallbacks: Vec::new(),
+                }
+            })?;
+            #(
+                if [#(#accepted_names),*].iter().any(|name| name.eq_ignore_ascii_case(&tag)) {
+                    return Ok(#constructors);
+                }
+            )*
+            Err(::derive_from_env::FromEnvError::ParsingFailure {
+                var_name: tag_var,
+                str_value: tag,
+                origin,
+                expected_type: #expected.to_string(),
+            })
+        }
+    };
+    let select_without_prefix = select(&constructors_without_prefix);
+    let select_with_prefix = select(&constructors_with_prefix);
+
+    let expanded = quote! {
+        impl ::derive_from_env::_inner_trait::FromEnv for #enum_identifier {
+            fn from_source(source: &dyn ::derive_from_env::EnvSource) -> Result<Self, ::derive_from_env::FromEnvError> {
+                use std::str::FromStr;
+                let tag_var = #tag_name.to_string();
+                #select_without_prefix
+            }
+            fn from_source_with_prefix(source: &dyn ::derive_from_env::EnvSource, prefix: &str) -> Result<Self, ::derive_from_env::FromEnvError> {
+                use std::str::FromStr;
+                let tag_var = format!("{}_{}", prefix, #tag_name);
+                #select_with_prefix
+            }
+            fn env_vars() -> Vec<::derive_from_env::EnvVarSpec> {
+                let tag_var = #tag_name.to_string();
+                let mut variant_specs = vec![#tag_spec];
+                #(#variant_specs_without_prefix)*
+                variant_specs
+            }
+            fn env_vars_with_prefix(prefix: &str) -> Vec<::derive_from_env::EnvVarSpec> {
+                let tag_var = format!("{}_{}", prefix, #tag_name);
+                let mut variant_specs = vec![#tag_spec];
+                #(#variant_specs_with_prefix)*
+                variant_specs
+            }
+        }
+        #inherent_impl
+    };
+    crate::crate_path::replace(expanded, env_struct.crate_path.as_ref())
+}
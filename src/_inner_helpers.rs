@@ -0,0 +1,1244 @@
+use crate::{EnvSource, Origin};
+
+/// Looks a field up the way `envy::prefixed(prefix)` does: the variable name must start with
+/// `prefix` (case-sensitive, stripped as many times as it repeats) and the remainder must equal
+/// the field name ignoring case. When several variables match, the upper-case one is used, and
+/// the lookup fails if there is none rather than picking one at random.
+///
+/// Returns the name to report in errors along with the value and its origin, if any.
+pub fn envy_lookup(
+    source: &dyn EnvSource,
+    prefix: &str,
+    field_name: &str,
+) -> Result<(String, Option<(String, Origin)>), crate::FromEnvError> {
+    let exact = format!("{}{}", prefix, field_name.to_uppercase());
+    let mut keys = source
+        .keys()?
+        .into_iter()
+        .filter(|key| {
+            key.starts_with(prefix) && key.trim_start_matches(prefix).to_lowercase() == field_name
+        })
+        .collect::<Vec<_>>();
+    let key = match keys.len() {
+        0 => return Ok((exact, None)),
+        1 => keys.remove(0),
+        _ if keys.contains(&exact) => exact,
+        _ => {
+            keys.sort();
+            return Err(crate::FromEnvError::Custom {
+                var_name: Some(exact),
+                message: format!(
+                    "ambiguous variables {} for field {}",
+                    keys.join(", "),
+                    field_name
+                ),
+            });
+        }
+    };
+    let value = source.lookup(&key)?;
+    Ok((key, value))
+}
+
+/// Loads a `#[from_env(flatten, deserialize)]` field.
+#[cfg(feature = "serde")]
+pub fn deserialize<T: serde::de::DeserializeOwned>(
+    source: &dyn EnvSource,
+    prefix: Option<&str>,
+) -> Result<T, crate::FromEnvError> {
+    T::deserialize(crate::Deserializer::from_source(source, prefix))
+}
+
+/// Joins the prefix given to `from_env_with_prefix` with the struct's own prefix for
+/// `#[from_env(prefix = "...", collapse_prefix)]`: the longest run of `_`-separated segments that
+/// ends `incoming` and starts `own` is written only once, as in `incoming`.
+pub fn collapse_prefix(incoming: &str, own: &str) -> String {
+    let incoming_segments = incoming.split('_').collect::<Vec<_>>();
+    let own_segments = own.trim_end_matches('_').split('_').collect::<Vec<_>>();
+    let overlap = (1..=incoming_segments.len().min(own_segments.len()))
+        .rev()
+        .find(|&len| {
+            incoming_segments[incoming_segments.len() - len..]
+                .iter()
+                .zip(&own_segments[..len])
+                .all(|(incoming, own)| same_segment(incoming, own))
+        })
+        .unwrap_or(0);
+    // Drops the overlapping segments along with the separator following each of them.
+    let rest = if overlap == own_segments.len() {
+        ""
+    } else {
+        own.splitn(overlap + 1, '_').last().unwrap_or_default()
+    };
+    format!("{}_{}", incoming, rest)
+}
+
+/// Whether two prefix segments name the same thing for `collapse_prefix`: they are equal, or the
+/// shorter one abbreviates the other, either way round (`DB` and `DATABASE`). An abbreviation
+/// starts with the same letter and has its other letters in order in the longer segment.
+fn same_segment(first: &str, second: &str) -> bool {
+    let (short, long) = match first.len() <= second.len() {
+        true => (first, second),
+        false => (second, first),
+    };
+    if short == long {
+        return true;
+    }
+    let (mut short, mut long) = (short.chars(), long.chars());
+    match (short.next(), long.next()) {
+        (Some(short_first), Some(long_first)) if short_first == long_first => {
+            short.all(|c| long.any(|other| other == c))
+        }
+        _ => false,
+    }
+}
+
+/// Splits a `#[from_env(path_list)]` value with [`std::env::split_paths`] (on `:` on Unix and `;`
+/// on Windows), dropping empty entries.
+pub fn split_path_list(value: &std::ffi::OsStr) -> Vec<std::path::PathBuf> {
+    std::env::split_paths(value)
+        .filter(|path| !path.as_os_str().is_empty())
+        .collect()
+}
+
+/// Decodes a `#[from_env(base64)]` value, with the URL-safe alphabet when `url_safe` is set,
+/// padding being optional. Failures give the expected type to report, which describes the problem
+/// without the value.
+pub fn decode_base64(value: &str, url_safe: bool) -> Result<Vec<u8>, String> {
+    let alphabet = if url_safe { "URL-safe" } else { "standard" };
+    let invalid = |problem: String| format!("base64 ({} alphabet), {}", alphabet, problem);
+    let value = value.trim();
+    let data = value.trim_end_matches('=');
+    if value.len() - data.len() > 2 || data.len() % 4 == 1 {
+        return Err(invalid(format!("invalid length {}", value.len())));
+    }
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for (index, character) in data.bytes().enumerate() {
+        let sextet = match character {
+            b'A'..=b'Z' => character - b'A',
+            b'a'..=b'z' => character - b'a' + 26,
+            b'0'..=b'9' => character - b'0' + 52,
+            b'+' if !url_safe => 62,
+            b'/' if !url_safe => 63,
+            b'-' if url_safe => 62,
+            b'_' if url_safe => 63,
+            _ => {
+                wipe(&mut bytes);
+                return Err(invalid(format!(
+                    "invalid character at position {}",
+                    index + 1
+                )));
+            }
+        };
+        buffer = (buffer << 6) | u32::from(sextet);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+/// Decodes a `#[from_env(hex)]` value, in any case and with an optional `0x` prefix. Failures give
+/// the expected type to report, which describes the problem without the value.
+pub fn decode_hex(value: &str) -> Result<Vec<u8>, String> {
+    let value = value.trim();
+    let prefix = if value.starts_with("0x") || value.starts_with("0X") {
+        2
+    } else {
+        0
+    };
+    let digits = &value.as_bytes()[prefix..];
+    // Checked before decoding so that no partially decoded buffer is left behind.
+    if let Some(index) = digits.iter().position(|digit| !digit.is_ascii_hexdigit()) {
+        return Err(format!(
+            "hex, invalid character at position {}",
+            prefix + index + 1
+        ));
+    }
+    if digits.len() % 2 == 1 {
+        return Err(format!("hex, odd number of digits {}", digits.len()));
+    }
+    let nibble = |digit: u8| (digit as char).to_digit(16).unwrap() as u8;
+    Ok(digits
+        .chunks(2)
+        .map(|pair| (nibble(pair[0]) << 4) | nibble(pair[1]))
+        .collect())
+}
+
+/// Zeroes a buffer which may hold key material before it is dropped, with the `zeroize` feature.
+pub fn wipe(bytes: &mut [u8]) {
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(bytes);
+    #[cfg(not(feature = "zeroize"))]
+    let _ = bytes;
+}
+
+/// Zeroes the raw value of a `#[from_env(zeroize)]` field once parsed.
+#[cfg(feature = "zeroize")]
+pub fn wipe_string(mut value: String) {
+    zeroize::Zeroize::zeroize(&mut value);
+}
+
+/// Expected type reported when a `#[from_env(lenient_bool)]` field fails to parse.
+pub const LENIENT_BOOL_SPELLINGS: &str =
+    "bool (true, false, 1, 0, yes, no, on or off, ignoring case)";
+
+/// Parses a `#[from_env(lenient_bool)]` field, accepting the usual spellings of booleans in any
+/// case.
+pub fn lenient_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Expected type reported when a `Duration` field fails to parse.
+#[cfg(feature = "duration")]
+pub const DURATION_SYNTAX: &str =
+    "duration (numbers followed by d, h, m, s, ms, us or ns, e.g. 30s, 5m, 1h30m or 250ms)";
+
+#[cfg(feature = "duration")]
+const DURATION_UNITS: [(&str, std::time::Duration); 7] = [
+    ("d", std::time::Duration::from_secs(86400)),
+    ("h", std::time::Duration::from_secs(3600)),
+    ("m", std::time::Duration::from_secs(60)),
+    ("s", std::time::Duration::from_secs(1)),
+    ("ms", std::time::Duration::from_millis(1)),
+    ("us", std::time::Duration::from_micros(1)),
+    ("ns", std::time::Duration::from_nanos(1)),
+];
+
+/// Parses a `Duration` field written as a sequence of integers followed by their unit, e.g.
+/// `1h30m` or `250ms`, whitespace being allowed between the parts. `0` alone is accepted.
+#[cfg(feature = "duration")]
+pub fn parse_duration(value: &str) -> Option<std::time::Duration> {
+    let mut rest = value.trim();
+    if rest == "0" {
+        return Some(std::time::Duration::ZERO);
+    }
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total = std::time::Duration::ZERO;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let number = rest[..digits].parse::<u32>().ok()?;
+        rest = &rest[digits..];
+        let letters = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let (_, unit) = DURATION_UNITS
+            .iter()
+            .find(|(name, _)| *name == &rest[..letters])?;
+        total = total.checked_add(unit.checked_mul(number)?)?;
+        rest = rest[letters..].trim_start();
+    }
+    Some(total)
+}
+
+/// Renders a `Duration` the way [`parse_duration`] reads it, e.g. `1h30m`, for the command-line
+/// arguments of `Duration` fields.
+#[cfg(feature = "duration")]
+pub fn format_duration(value: &std::time::Duration) -> String {
+    if value.is_zero() {
+        return "0".to_string();
+    }
+    let mut rest = value.as_nanos();
+    let mut parts = String::new();
+    for (name, unit) in DURATION_UNITS {
+        let count = rest / unit.as_nanos();
+        if count > 0 {
+            parts.push_str(&format!("{}{}", count, name));
+            rest %= unit.as_nanos();
+        }
+    }
+    parts
+}
+
+/// Replaces the comma of a `#[from_env(decimal_comma)]` value written with a single comma as
+/// decimal separator, refusing values mixing commas and dots.
+pub fn decimal_comma<'a>(
+    var_name: &str,
+    value: &'a str,
+) -> Result<std::borrow::Cow<'a, str>, crate::FromEnvError> {
+    if value.contains(',') && value.contains('.') {
+        return Err(crate::FromEnvError::Custom {
+            var_name: Some(var_name.to_string()),
+            message: format!(
+                "ambiguous decimal separator in {:?}, use either a single ',' or '.'",
+                value
+            ),
+        });
+    }
+    if value.matches(',').count() == 1 {
+        Ok(value.replace(',', ".").into())
+    } else {
+        Ok(value.into())
+    }
+}
+
+/// Strips the `%` of a `#[from_env(percent)]` value, telling whether it was present.
+pub fn percent<'a>(
+    var_name: &str,
+    value: &'a str,
+    require_sign: bool,
+) -> Result<(&'a str, bool), crate::FromEnvError> {
+    match value.trim().strip_suffix('%') {
+        Some(number) => Ok((number.trim_end(), true)),
+        None if require_sign => Err(crate::FromEnvError::Custom {
+            var_name: Some(var_name.to_string()),
+            message: format!("expected a percentage such as \"95%\", got {:?}", value),
+        }),
+        None => Ok((value.trim(), false)),
+    }
+}
+
+/// Prepends the name of a nested field to the field paths of the variables it reads.
+pub fn nest_specs(field: &str, specs: Vec<crate::EnvVarSpec>) -> Vec<crate::EnvVarSpec> {
+    specs
+        .into_iter()
+        .map(|mut spec| {
+            spec.field = if spec.field.is_empty() {
+                field.to_string()
+            } else {
+                format!("{}.{}", field, spec.field)
+            };
+            spec
+        })
+        .collect()
+}
+
+/// Adds the `<NAME>{suffix}` variable of `lookup_file` after each variable of `specs`, for the
+/// nested structs of a struct with `#[from_env(file_suffix = "...")]`.
+pub fn with_file_specs(specs: Vec<crate::EnvVarSpec>, suffix: &str) -> Vec<crate::EnvVarSpec> {
+    specs
+        .into_iter()
+        .flat_map(|spec| {
+            let file_spec = crate::EnvVarSpec {
+                name: format!("{}{}", spec.name, suffix),
+                type_name: format!("path of a file holding {}", spec.type_name),
+                default: None,
+                required: false,
+                optional: true,
+                secret: false,
+                ..spec.clone()
+            };
+            [spec, file_spec]
+        })
+        .collect()
+}
+
+/// Removes a single trailing newline (`\n` or `\r\n`) from the content of a file.
+pub fn trim_trailing_newline(content: &str) -> &str {
+    match content.strip_suffix('\n') {
+        Some(content) => content.strip_suffix('\r').unwrap_or(content),
+        None => content,
+    }
+}
+
+/// Reads the `#[from_env(credential = "...")]` file `name` from the systemd credentials directory,
+/// if `$CREDENTIALS_DIRECTORY` is set in `source`, without its trailing newline.
+///
+/// Returns the content along with its origin. A missing file is an error when `required`, and
+/// `None` otherwise.
+pub fn credential(
+    source: &dyn EnvSource,
+    name: &str,
+    required: bool,
+) -> Result<Option<(String, Origin)>, crate::FromEnvError> {
+    let Some(dir) = source.get("CREDENTIALS_DIRECTORY")? else {
+        return Ok(None);
+    };
+    let path = std::path::Path::new(&dir).join(name);
+    match std::fs::read_to_string(&path) {
+        // Truncated in place rather than copied, so that no copy of the secret is left behind.
+        Ok(mut content) => {
+            content.truncate(trim_trailing_newline(&content).len());
+            Ok(Some((content, Origin::Credential(path))))
+        }
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound && !required => Ok(None),
+        Err(error) => Err(crate::FromEnvError::Custom {
+            var_name: Some(path.display().to_string()),
+            message: format!("failed to read credential file: {}", error),
+        }),
+    }
+}
+
+/// Parses the bounds of a `Range` or `RangeInclusive` field written `start..end` (exclusive),
+/// `start..=end` (inclusive) or `start-end` (exclusive or inclusive as the field). Failures of
+/// `secret` fields leave the value and its bounds out.
+pub fn parse_range<T: std::str::FromStr + PartialOrd>(
+    var_name: &str,
+    value: &str,
+    inclusive: bool,
+    secret: bool,
+) -> Result<(T, T), crate::FromEnvError> {
+    let error = |message: String| crate::FromEnvError::Custom {
+        var_name: Some(var_name.to_string()),
+        message,
+    };
+    let shown = |text: &str| redact(format!("{:?}", text), secret);
+    let (start, end) = if let Some((start, end)) = value.split_once("..=") {
+        if !inclusive {
+            return Err(error(format!(
+                "expected an exclusive range such as \"1..10\", got {}",
+                shown(value)
+            )));
+        }
+        (start, end)
+    } else if let Some((start, end)) = value.split_once("..") {
+        if inclusive {
+            return Err(error(format!(
+                "expected an inclusive range such as \"1..=10\", got {}",
+                shown(value)
+            )));
+        }
+        (start, end)
+    } else {
+        // The separator is the first `-` which is not the sign of the start bound.
+        match value.char_indices().skip(1).find(|&(_, c)| c == '-') {
+            Some((index, _)) => (&value[..index], &value[index + 1..]),
+            None => {
+                return Err(error(format!(
+                    "expected a range such as \"1-10\" or \"1..{}10\", got {}",
+                    if inclusive { "=" } else { "" },
+                    shown(value)
+                )))
+            }
+        }
+    };
+    let parse_bound = |name: &str, bound: &str| {
+        bound.trim().parse::<T>().map_err(|_| {
+            error(format!(
+                "invalid {} bound {}, expected {}",
+                name,
+                shown(bound.trim()),
+                std::any::type_name::<T>()
+            ))
+        })
+    };
+    let (start, end) = (parse_bound("start", start)?, parse_bound("end", end)?);
+    if start > end {
+        return Err(error(format!(
+            "the start of the range {} is greater than its end",
+            shown(value)
+        )));
+    }
+    Ok((start, end))
+}
+
+/// Fails when the `url_var` of a `#[from_env(deny_conflicts)]` section is set along with variables
+/// of its fields, naming them.
+pub fn deny_conflicts(
+    source: &dyn EnvSource,
+    url_var: &str,
+    specs: &[crate::EnvVarSpec],
+) -> Result<(), crate::FromEnvError> {
+    let mut conflicts = Vec::new();
+    for spec in specs {
+        if source.lookup(&spec.name)?.is_some() {
+            conflicts.push(spec.name.as_str());
+        }
+    }
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+    Err(crate::FromEnvError::Custom {
+        var_name: Some(url_var.to_string()),
+        message: format!(
+            "conflicts with {}, which should not be set along with it",
+            conflicts.join(", ")
+        ),
+    })
+}
+
+/// Whether any variable of a section is set, the section of a `#[from_env(flatten, default)]`
+/// field being loaded only then.
+pub fn any_var_set(
+    source: &dyn EnvSource,
+    specs: &[crate::EnvVarSpec],
+) -> Result<bool, crate::FromEnvError> {
+    for spec in specs {
+        if source.lookup(&spec.name)?.is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Whether the section of a flattened `Option` field is set, i.e. one of its required variables
+/// is. Fails when only some of them are, naming the missing ones.
+pub fn section_is_set(
+    source: &dyn EnvSource,
+    specs: &[crate::EnvVarSpec],
+) -> Result<bool, crate::FromEnvError> {
+    let mut set = Vec::new();
+    let mut missing = Vec::new();
+    for spec in specs.iter().filter(|spec| spec.required) {
+        if source.lookup(&spec.name)?.is_some() {
+            set.push(spec.name.as_str());
+        } else {
+            missing.push(spec.name.as_str());
+        }
+    }
+    if set.is_empty() {
+        return Ok(false);
+    }
+    if missing.is_empty() {
+        return Ok(true);
+    }
+    Err(crate::FromEnvError::Custom {
+        var_name: None,
+        message: format!(
+            "partially set section: {} set but {} missing",
+            set.join(", "),
+            missing.join(", ")
+        ),
+    })
+}
+
+#[cfg(feature = "regex")]
+pub use regex::Regex;
+
+/// Checks a `#[from_env(matches = "...")]` value, compiling the pattern on first use.
+#[cfg(feature = "regex")]
+pub fn check_matches(
+    regex: &std::sync::OnceLock<Regex>,
+    pattern: &str,
+    value: &str,
+    secret: bool,
+) -> Result<(), String> {
+    let regex = regex.get_or_init(|| Regex::new(pattern).expect("pattern checked by the derive"));
+    match (regex.is_match(value), secret) {
+        (true, _) => Ok(()),
+        (false, true) => Err(format!("<redacted> doesn't match the pattern {}", pattern)),
+        (false, false) => Err(format!("{:?} doesn't match the pattern {}", value, pattern)),
+    }
+}
+
+/// Parses a `#[from_env(json)]` field, failures giving the message of `serde_json`.
+#[cfg(feature = "json")]
+pub fn parse_json<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_str(value).map_err(|error| error.to_string())
+}
+
+/// Renders a `#[from_env(json)]` field for its command-line argument.
+#[cfg(feature = "json")]
+pub fn to_json<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// Parses a `#[from_env(format = "yaml")]` field, failures giving the message of `serde_yaml`.
+#[cfg(feature = "yaml")]
+pub fn parse_yaml<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_yaml::from_str(value).map_err(|error| error.to_string())
+}
+
+/// Renders a `#[from_env(format = "yaml")]` field for its command-line argument.
+#[cfg(feature = "yaml")]
+pub fn to_yaml<T: serde::Serialize>(value: &T) -> String {
+    serde_yaml::to_string(value).unwrap_or_default()
+}
+
+/// Parses a `#[from_env(format = "toml")]` field, failures giving the message of `toml` with the
+/// position of the error on a single line, like the other formats.
+#[cfg(feature = "toml")]
+pub fn parse_toml<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, String> {
+    toml::from_str(value).map_err(|error| match error.span() {
+        Some(span) => {
+            let before = &value[..span.start];
+            let line = before.matches('\n').count() + 1;
+            let column = before.len() - before.rfind('\n').map_or(0, |start| start + 1) + 1;
+            format!(
+                "{} at line {} column {}",
+                error.message().trim_end(),
+                line,
+                column
+            )
+        }
+        None => error.message().trim_end().to_string(),
+    })
+}
+
+/// Renders a `#[from_env(format = "toml")]` field for its command-line argument.
+#[cfg(feature = "toml")]
+pub fn to_toml<T: serde::Serialize>(value: &T) -> String {
+    toml::to_string(value).unwrap_or_default()
+}
+
+/// Extracts the scalar at the RFC 6901 `pointer` from the JSON document `value`, as a string.
+#[cfg(feature = "serde")]
+pub fn json_pointer(
+    var_name: &str,
+    value: &str,
+    pointer: &str,
+) -> Result<String, crate::FromEnvError> {
+    let error = |message: String| crate::FromEnvError::Custom {
+        var_name: Some(var_name.to_string()),
+        message,
+    };
+    let document: serde_json::Value =
+        serde_json::from_str(value).map_err(|e| error(format!("invalid JSON document: {}", e)))?;
+    match document.pointer(pointer) {
+        None => Err(error(format!("no value at JSON pointer {:?}", pointer))),
+        Some(serde_json::Value::String(value)) => Ok(value.clone()),
+        Some(serde_json::Value::Number(value)) => Ok(value.to_string()),
+        Some(serde_json::Value::Bool(value)) => Ok(value.to_string()),
+        Some(value) => {
+            let kind = match value {
+                serde_json::Value::Null => "null",
+                serde_json::Value::Array(_) => "an array",
+                _ => "an object",
+            };
+            Err(error(format!(
+                "expected a string, number or boolean at JSON pointer {:?}, found {}",
+                pointer, kind
+            )))
+        }
+    }
+}
+
+/// Items of a list separated by `separator`, trimmed, without empty items.
+pub fn split_list<'v>(value: &'v str, separator: &'v str) -> impl Iterator<Item = &'v str> {
+    value
+        .split(separator)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// Items of a `#[from_env(quoted)]` list separated by `separator`, trimmed, without empty items
+/// unless quoted. Items within double quotes may hold the separator, `""` standing for a quote.
+/// Fails with the 1-based position of the malformed item, its raw text and what was expected.
+pub fn split_quoted<'v>(
+    value: &'v str,
+    separator: &str,
+) -> Result<Vec<String>, (usize, &'v str, &'static str)> {
+    let mut items = Vec::new();
+    let mut rest = value;
+    for index in 1.. {
+        let trimmed = rest.trim_start();
+        let Some(quoted) = trimmed.strip_prefix('"') else {
+            let (item, next) = match rest.find(separator) {
+                Some(end) => (&rest[..end], Some(&rest[end + separator.len()..])),
+                None => (rest, None),
+            };
+            if !item.trim().is_empty() {
+                items.push(item.trim().to_string());
+            }
+            match next {
+                Some(next) => rest = next,
+                None => break,
+            }
+            continue;
+        };
+        let mut item = String::new();
+        let mut chars = quoted.char_indices();
+        let after = loop {
+            match chars.next() {
+                Some((position, '"')) => {
+                    if quoted[position + 1..].starts_with('"') {
+                        item.push('"');
+                        chars.next();
+                    } else {
+                        break &quoted[position + 1..];
+                    }
+                }
+                Some((_, character)) => item.push(character),
+                None => return Err((index, trimmed, "closing quote")),
+            }
+        };
+        items.push(item);
+        let after = after.trim_start();
+        if after.is_empty() {
+            break;
+        }
+        match after.strip_prefix(separator) {
+            Some(next) => rest = next,
+            None => {
+                let end = after
+                    .find(separator)
+                    .map_or(value.len(), |end| value.len() - after.len() + end);
+                let start = value.len() - trimmed.len();
+                return Err((index, &value[start..end], "separator after closing quote"));
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Components of a tuple field separated by `separator`, trimmed, failing unless there are
+/// exactly `count` of them.
+pub fn split_tuple<'v>(
+    var_name: &str,
+    value: &'v str,
+    separator: &str,
+    count: usize,
+) -> Result<Vec<&'v str>, crate::FromEnvError> {
+    let components = value.split(separator).map(str::trim).collect::<Vec<_>>();
+    if components.len() != count {
+        return Err(crate::FromEnvError::Custom {
+            var_name: Some(var_name.to_string()),
+            message: format!(
+                "expected {} components separated by {:?}, found {} in {:?}",
+                count,
+                separator,
+                components.len(),
+                value
+            ),
+        });
+    }
+    Ok(components)
+}
+
+/// Entries of a map separated by `separator`, trimmed, without empty entries, along with their
+/// 1-based position in the value (empty entries included).
+pub fn map_entries<'v>(
+    value: &'v str,
+    separator: &'v str,
+) -> impl Iterator<Item = (usize, &'v str)> {
+    value
+        .split(separator)
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, entry)| !entry.is_empty())
+        .map(|(index, entry)| (index + 1, entry))
+}
+
+/// Key and value of a `key=value` map entry, trimmed, split on the first `kv_separator`.
+pub fn split_map_entry<'e>(entry: &'e str, kv_separator: &str) -> Option<(&'e str, &'e str)> {
+    let (key, value) = entry.split_once(kv_separator)?;
+    Some((key.trim(), value.trim()))
+}
+
+/// Prepends `field` to the path of the diffs of a nested struct, see [`nest_specs`].
+pub fn nest_diffs(field: &str, diffs: Vec<crate::FieldDiff>) -> Vec<crate::FieldDiff> {
+    diffs
+        .into_iter()
+        .map(|mut diff| {
+            diff.field = if diff.field.is_empty() {
+                field.to_string()
+            } else {
+                format!("{}.{}", field, diff.field)
+            };
+            diff
+        })
+        .collect()
+}
+
+/// Hides the values of the diffs of a `secret` field.
+pub fn redact_diffs(diffs: Vec<crate::FieldDiff>) -> Vec<crate::FieldDiff> {
+    diffs
+        .into_iter()
+        .map(|diff| crate::FieldDiff {
+            old: "<redacted>".to_string(),
+            new: "<redacted>".to_string(),
+            ..diff
+        })
+        .collect()
+}
+
+/// Kebab-cased argument name of `field` under `path`, `path` itself for an empty field.
+pub fn arg_path(path: &str, field: &str) -> String {
+    let field = field.replace('_', "-");
+    match (path.is_empty(), field.is_empty()) {
+        (_, true) => path.to_string(),
+        (true, false) => field,
+        (false, false) => format!("{}-{}", path, field),
+    }
+}
+
+/// Appends the argument `--{path}` with `value`, in the given style.
+pub fn push_arg(
+    args: &mut Vec<String>,
+    path: &str,
+    value: &str,
+    style: crate::ArgStyle,
+    redact: bool,
+) {
+    let value = if redact { "<redacted>" } else { value };
+    if style.space_separated {
+        args.push(format!("--{}", path));
+        args.push(value.to_string());
+    } else {
+        args.push(format!("--{}={}", path, value));
+    }
+}
+
+/// Appends `--{path}` or `--no-{path}`, or the redacted value.
+pub fn push_flag(
+    args: &mut Vec<String>,
+    path: &str,
+    value: bool,
+    style: crate::ArgStyle,
+    redact: bool,
+) {
+    match (redact, value) {
+        (true, _) => push_arg(args, path, "", style, true),
+        (false, true) => args.push(format!("--{}", path)),
+        (false, false) => args.push(format!("--no-{}", path)),
+    }
+}
+
+/// Joins the paths of a `path_list` field like `PATH`.
+pub fn join_path_list(paths: &[std::path::PathBuf]) -> String {
+    std::env::join_paths(paths)
+        .map(|joined| joined.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Prefix of `from_env_with_exe_prefix`: the file stem of the executable, upper-snake-cased
+/// (`my-tool` gives `MY_TOOL`).
+pub fn exe_prefix(exe: &std::path::Path) -> Result<String, crate::FromEnvError> {
+    let stem = exe
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| !stem.is_empty())
+        .ok_or_else(|| crate::FromEnvError::SourceFailure {
+            var_name: None,
+            message: format!(
+                "failed to determine the name of the executable from {:?}",
+                exe
+            ),
+        })?;
+    Ok(stem
+        .chars()
+        .map(|c| match c {
+            '-' | '.' => '_',
+            c => c.to_ascii_uppercase(),
+        })
+        .collect())
+}
+
+/// Looks the `alias`, `alias_var` and `default_env` variables of a field up, in order, when its
+/// variable is not set.
+pub fn lookup_fallbacks(
+    source: &dyn EnvSource,
+    (var_name, value): (String, Option<(String, Origin)>),
+    fallbacks: &[String],
+) -> Result<(String, Option<(String, Origin)>), crate::FromEnvError> {
+    if value.is_some() {
+        return Ok((var_name, value));
+    }
+    for fallback in fallbacks {
+        if let Some(value) = source.lookup(fallback)? {
+            return Ok((fallback.clone(), Some(value)));
+        }
+    }
+    Ok((var_name, None))
+}
+
+/// What a `file` field does when both its variable and the `<NAME>_FILE` variable are set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileConflict {
+    PreferVar,
+    PreferFile,
+    Error,
+}
+
+/// Reads the value of a `#[from_env(file)]` field from the file named by `<NAME>{suffix}`, without
+/// its trailing newline, when its variable is not set, or when both are set as `conflict` says.
+pub fn lookup_file(
+    source: &dyn EnvSource,
+    (var_name, value): (String, Option<(String, Origin)>),
+    suffix: &str,
+    conflict: FileConflict,
+) -> Result<(String, Option<(String, Origin)>), crate::FromEnvError> {
+    if value.is_some() && conflict == FileConflict::PreferVar {
+        return Ok((var_name, value));
+    }
+    let file_var = format!("{}{}", var_name, suffix);
+    let Some(path) = source.get(&file_var)? else {
+        return Ok((var_name, value));
+    };
+    if value.is_some() && conflict == FileConflict::Error {
+        return Err(crate::FromEnvError::FileConflict { var_name, file_var });
+    }
+    let value = read_value_file(file_var, path.into())?;
+    Ok((var_name, Some(value)))
+}
+
+/// Reads the file named by `file_var`, without its trailing newline.
+fn read_value_file(
+    file_var: String,
+    path: std::path::PathBuf,
+) -> Result<(String, Origin), crate::FromEnvError> {
+    match std::fs::read_to_string(&path) {
+        // Truncated in place rather than copied, so that no copy of the secret is left behind.
+        Ok(mut content) => {
+            content.truncate(trim_trailing_newline(&content).len());
+            Ok((content, Origin::File(path)))
+        }
+        Err(error) => Err(crate::FromEnvError::FileReadFailure {
+            var_name: file_var,
+            path,
+            kind: error.kind(),
+        }),
+    }
+}
+
+/// Source giving every variable the `<NAME>{suffix}` file of `lookup_file`, through which the
+/// nested structs of a struct with `#[from_env(file_suffix = "...")]` are loaded.
+pub struct FileSuffix<'s> {
+    pub source: &'s dyn EnvSource,
+    pub suffix: &'static str,
+    pub conflict: FileConflict,
+}
+
+impl EnvSource for FileSuffix<'_> {
+    fn get(&self, key: &str) -> Result<Option<String>, crate::FromEnvError> {
+        Ok(self.lookup(key)?.map(|(value, _)| value))
+    }
+    fn origin(&self, key: &str) -> Origin {
+        self.source.origin(key)
+    }
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, crate::FromEnvError> {
+        let value = self.source.lookup(key)?;
+        let (_, value) = lookup_file(
+            self.source,
+            (key.to_string(), value),
+            self.suffix,
+            self.conflict,
+        )?;
+        Ok(value)
+    }
+    fn get_os(&self, key: &str) -> Result<Option<std::ffi::OsString>, crate::FromEnvError> {
+        match self.source.get_os(key)? {
+            Some(value) if self.conflict == FileConflict::PreferVar => Ok(Some(value)),
+            _ => Ok(self.get(key)?.map(std::ffi::OsString::from)),
+        }
+    }
+    fn keys(&self) -> Result<Vec<String>, crate::FromEnvError> {
+        self.source.keys()
+    }
+    fn current_exe(&self) -> Result<std::path::PathBuf, crate::FromEnvError> {
+        self.source.current_exe()
+    }
+}
+
+/// Records the failure to parse the value of a `lenient` field, which takes its default instead.
+pub fn lenient_fallback(error: crate::FromEnvError) {
+    let var_name = match &error {
+        crate::FromEnvError::MissingEnvVar { var_name, .. }
+        | crate::FromEnvError::ParsingFailure { var_name, .. }
+        | crate::FromEnvError::ValidationFailure { var_name, .. }
+        | crate::FromEnvError::FileReadFailure { var_name, .. }
+        | crate::FromEnvError::FileConflict { var_name, .. } => var_name.clone(),
+        crate::FromEnvError::Custom { var_name, .. }
+        | crate::FromEnvError::SourceFailure { var_name, .. } => {
+            var_name.clone().unwrap_or_default()
+        }
+    };
+    crate::lint::record_warning(crate::EnvWarning {
+        var_name,
+        kind: crate::EnvWarningKind::LenientFallback,
+        message: format!("{}, the default is used instead", error),
+    });
+}
+
+/// Looks the `deprecated_alias` variables of a field up, in order, when none of its other
+/// variables is set, notifying the deprecation hook of the one read.
+pub fn lookup_deprecated(
+    source: &dyn EnvSource,
+    (var_name, value): (String, Option<(String, Origin)>),
+    deprecated: &[String],
+    field: &str,
+) -> Result<(String, Option<(String, Origin)>), crate::FromEnvError> {
+    if value.is_some() {
+        return Ok((var_name, value));
+    }
+    for name in deprecated {
+        if let Some(value) = source.lookup(name)? {
+            crate::deprecation::notify(&crate::DeprecationNotice {
+                field: field.to_string(),
+                var_name,
+                deprecated_name: name.clone(),
+            });
+            return Ok((name.clone(), Some(value)));
+        }
+    }
+    Ok((var_name, None))
+}
+
+/// Drops the value of a `#[from_env(non_empty)]` field when it is empty, or only made of
+/// whitespace with `trim`, as if the variable was not set.
+pub fn non_empty(
+    (var_name, value): (String, Option<(String, Origin)>),
+    trim: bool,
+) -> (String, Option<(String, Origin)>) {
+    let value = value.filter(|(value, _)| {
+        let value = if trim { value.trim() } else { value };
+        !value.is_empty()
+    });
+    (var_name, value)
+}
+
+/// Strips the whitespace surrounding the value of a `#[from_env(trim)]` field, in place.
+pub fn trim_value(
+    (var_name, value): (String, Option<(String, Origin)>),
+) -> (String, Option<(String, Origin)>) {
+    let value = value.map(|(mut value, origin)| {
+        value.truncate(value.trim_end().len());
+        value.drain(..value.len() - value.trim_start().len());
+        (value, origin)
+    });
+    (var_name, value)
+}
+
+/// Rest of the `field_path` given to `explain()` below the field `name`, `""` for the field
+/// itself.
+pub fn field_rest<'p>(field_path: &'p str, name: &str) -> Option<&'p str> {
+    if field_path == name {
+        return Some("");
+    }
+    field_path.strip_prefix(name)?.strip_prefix('.')
+}
+
+/// Step of a resolution plan reading the variable of `location`.
+pub fn explain_lookup(
+    source: &dyn EnvSource,
+    location: crate::Location,
+    secret: bool,
+) -> crate::ResolutionStep {
+    let name = match &location {
+        crate::Location::Variable(name)
+        | crate::Location::Alias(name)
+        | crate::Location::DeprecatedAlias(name)
+        | crate::Location::DefaultEnv(name)
+        | crate::Location::AppendFrom(name) => name.clone(),
+        _ => unreachable!("not a variable location"),
+    };
+    let value = source.get(&name).ok().flatten();
+    crate::ResolutionStep {
+        location,
+        origin: Some(source.origin(&name)),
+        value: value.map(|value| redact(value, secret)),
+    }
+}
+
+/// Step of a resolution plan reading the `credential` file `name`.
+pub fn explain_credential(
+    source: &dyn EnvSource,
+    name: &str,
+    secret: bool,
+) -> crate::ResolutionStep {
+    let value = credential(source, name, false).ok().flatten();
+    crate::ResolutionStep {
+        location: crate::Location::Credential(name.to_string()),
+        origin: None,
+        value: value.map(|(value, _)| redact(value, secret)),
+    }
+}
+
+/// Step of a resolution plan reading the file named by the `file_var` variable of a
+/// `#[from_env(file)]` field.
+pub fn explain_file(
+    source: &dyn EnvSource,
+    file_var: String,
+    secret: bool,
+) -> crate::ResolutionStep {
+    let value = source
+        .get(&file_var)
+        .ok()
+        .flatten()
+        .and_then(|path| read_value_file(file_var.clone(), path.into()).ok());
+    crate::ResolutionStep {
+        location: crate::Location::File(file_var),
+        origin: value.as_ref().map(|(_, origin)| origin.clone()),
+        value: value.map(|(value, _)| redact(value, secret)),
+    }
+}
+
+/// Step of a resolution plan taking the `default` attribute.
+pub fn explain_default(default: &str, secret: bool) -> crate::ResolutionStep {
+    crate::ResolutionStep {
+        location: crate::Location::Default,
+        origin: None,
+        value: Some(redact(default.to_string(), secret)),
+    }
+}
+
+/// Hides the value of a parsing failure, and its occurrences in custom messages, for `secret`
+/// fields and the fields of `secrecy` types.
+pub fn redact_value(error: crate::FromEnvError, value: &str) -> crate::FromEnvError {
+    match error {
+        crate::FromEnvError::ParsingFailure {
+            var_name,
+            expected_type,
+            origin,
+            ..
+        } => crate::FromEnvError::ParsingFailure {
+            var_name,
+            // Messages of `parse_with` functions may quote the value.
+            expected_type: redact_occurrences(expected_type, value),
+            str_value: "<redacted>".to_string(),
+            origin,
+        },
+        crate::FromEnvError::Custom { var_name, message } => crate::FromEnvError::Custom {
+            var_name,
+            message: redact_occurrences(message, value),
+        },
+        error => error,
+    }
+}
+
+/// Replaces the occurrences of `value` in `message`, as is or escaped by `{:?}`.
+fn redact_occurrences(message: String, value: &str) -> String {
+    if value.is_empty() {
+        return message;
+    }
+    let escaped = format!("{:?}", value);
+    message
+        .replace(&escaped[1..escaped.len() - 1], "<redacted>")
+        .replace(value, "<redacted>")
+}
+
+/// Wraps the parsed value of a field of a `secrecy` type.
+#[cfg(feature = "secrecy")]
+pub trait FromSecret<T> {
+    fn from_secret(value: T) -> Self;
+}
+
+#[cfg(feature = "secrecy")]
+impl FromSecret<String> for secrecy::SecretString {
+    fn from_secret(value: String) -> Self {
+        value.into()
+    }
+}
+
+#[cfg(feature = "secrecy")]
+impl<T: secrecy::zeroize::Zeroize> FromSecret<T> for secrecy::SecretBox<T> {
+    fn from_secret(value: T) -> Self {
+        secrecy::SecretBox::new(Box::new(value))
+    }
+}
+
+fn redact(value: String, secret: bool) -> String {
+    if secret {
+        "<redacted>".to_string()
+    } else {
+        value
+    }
+}
+
+/// Prepends the field name to the path of a nested resolution plan.
+pub fn nest_plan(field: &str, plan: crate::ResolutionPlan) -> crate::ResolutionPlan {
+    crate::ResolutionPlan {
+        field: match (field.is_empty(), plan.field.is_empty()) {
+            (true, _) => plan.field,
+            (false, true) => field.to_string(),
+            (false, false) => format!("{}.{}", field, plan.field),
+        },
+        ..plan
+    }
+}
+
+/// Loads the elements of an `indexed` field from the prefixes `{name}_0`, `{name}_1`... until an
+/// element has none of its variables set. With `deny_gaps`, a variable of a later element is an
+/// error (detected among the keys listed by the source).
+pub fn load_indexed<T: crate::FromEnv>(
+    source: &dyn EnvSource,
+    name: &str,
+    deny_gaps: bool,
+) -> Result<Vec<T>, crate::FromEnvError> {
+    let is_set = |prefix: &str| -> Result<bool, crate::FromEnvError> {
+        for spec in T::env_vars_with_prefix(prefix) {
+            if source.get(&spec.name)?.is_some() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    };
+    let mut elements = Vec::new();
+    loop {
+        let prefix = format!("{}_{}", name, elements.len());
+        if !is_set(&prefix)? {
+            break;
+        }
+        elements.push(T::from_source_with_prefix(source, &prefix)?);
+    }
+    if deny_gaps {
+        for key in source.keys()? {
+            let Some(index) = key
+                .strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix('_'))
+                .and_then(|rest| rest.split('_').next())
+                .and_then(|index| index.parse::<usize>().ok())
+                .filter(|&index| index > elements.len())
+            else {
+                continue;
+            };
+            let prefix = format!("{}_{}", name, index);
+            if T::env_vars_with_prefix(&prefix)
+                .iter()
+                .any(|spec| spec.name == key)
+            {
+                return Err(crate::FromEnvError::Custom {
+                    var_name: Some(key),
+                    message: format!(
+                        "element {} is set but element {} is missing ({}_{} has no variable set)",
+                        index,
+                        elements.len(),
+                        name,
+                        elements.len()
+                    ),
+                });
+            }
+        }
+    }
+    Ok(elements)
+}
+
+/// Loads the items of a `count_var` field: the variable `count_var` gives their number, and they
+/// are read from `{name}_0`, `{name}_1`... `None` when the count is not set. Failures of `secret`
+/// fields hide the item.
+pub fn load_counted<T: std::str::FromStr>(
+    source: &dyn EnvSource,
+    count_var: &str,
+    name: &str,
+    type_name: &str,
+    secret: bool,
+) -> Result<Option<Vec<T>>, crate::FromEnvError> {
+    let Some((count, origin)) = source.lookup(count_var)? else {
+        return Ok(None);
+    };
+    let count = count
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| crate::FromEnvError::ParsingFailure {
+            var_name: count_var.to_string(),
+            expected_type: "usize".to_string(),
+            str_value: count.clone(),
+            origin,
+        })?;
+    (0..count)
+        .map(|index| {
+            let var_name = format!("{}_{}", name, index);
+            match source.lookup(&var_name)? {
+                Some((value, origin)) => value.parse().map_err(|_| {
+                    let error = crate::FromEnvError::ParsingFailure {
+                        var_name,
+                        expected_type: type_name.to_string(),
+                        str_value: value.clone(),
+                        origin,
+                    };
+                    match secret {
+                        true => redact_value(error, &value),
+                        false => error,
+                    }
+                }),
+                None => Err(crate::FromEnvError::MissingEnvVar {
+                    origin: source.origin(&var_name),
+                    var_name,
+                    fallbacks: Vec::new(),
+                }),
+            }
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
@@ -1,6 +1,158 @@
-use crate::FromEnvError;
+use crate::{
+    ArgStyle, EnvSource, EnvVarSpec, EnvWarning, FieldDiff, FromEnvError, MergePolicy, ProcessEnv,
+    ResolutionPlan,
+};
 
 pub trait FromEnv: Sized {
-    fn from_env() -> Result<Self, FromEnvError>;
-    fn from_env_with_prefix(prefix: &str) -> Result<Self, FromEnvError>;
+    fn from_source(source: &dyn EnvSource) -> Result<Self, FromEnvError>;
+    fn from_source_with_prefix(source: &dyn EnvSource, prefix: &str) -> Result<Self, FromEnvError>;
+    fn from_env() -> Result<Self, FromEnvError> {
+        Self::from_source(&ProcessEnv)
+    }
+    fn from_env_with_prefix(prefix: &str) -> Result<Self, FromEnvError> {
+        Self::from_source_with_prefix(&ProcessEnv, prefix)
+    }
+    /// Loads from the process environment, with the warnings recorded along the way such as the
+    /// invalid values of `lenient` fields.
+    fn from_env_with_warnings() -> Result<(Self, Vec<EnvWarning>), FromEnvError> {
+        Self::from_source_with_warnings(&ProcessEnv)
+    }
+    /// Loads from `source`, with the warnings recorded along the way.
+    fn from_source_with_warnings(
+        source: &dyn EnvSource,
+    ) -> Result<(Self, Vec<EnvWarning>), FromEnvError> {
+        let (result, warnings) = crate::lint::collect_warnings(|| Self::from_source(source));
+        result.map(|value| (value, warnings))
+    }
+    /// Loads from the process environment with the prefix given by the name of the executable.
+    fn from_env_with_exe_prefix() -> Result<Self, FromEnvError> {
+        Self::from_source_with_exe_prefix(&ProcessEnv)
+    }
+    /// Loads from `source` with the prefix given by the name of its executable.
+    fn from_source_with_exe_prefix(source: &dyn EnvSource) -> Result<Self, FromEnvError> {
+        let prefix = crate::_inner_helpers::exe_prefix(&source.current_exe()?)?;
+        Self::from_source_with_prefix(source, &prefix)
+    }
+    /// Variables read by `from_env`, empty unless implemented.
+    fn env_vars() -> Vec<EnvVarSpec> {
+        Vec::new()
+    }
+    /// Variables read by `from_env_with_prefix`, empty unless implemented.
+    fn env_vars_with_prefix(_prefix: &str) -> Vec<EnvVarSpec> {
+        Vec::new()
+    }
+    /// Locations consulted by `from_source` for the field at `field_path`, `None` for unknown
+    /// fields or unless implemented.
+    fn explain_from_source(_source: &dyn EnvSource, _field_path: &str) -> Option<ResolutionPlan> {
+        None
+    }
+    /// Locations consulted by `from_source_with_prefix` for the field at `field_path`, `None` for
+    /// unknown fields or unless implemented.
+    fn explain_from_source_with_prefix(
+        _source: &dyn EnvSource,
+        _field_path: &str,
+        _prefix: &str,
+    ) -> Option<ResolutionPlan> {
+        None
+    }
+}
+
+/// Implements `FromEnv` for a smart pointer by loading the pointed type and wrapping it.
+macro_rules! impl_from_env_for_pointer {
+    ($($pointer:ident)::+) => {
+        impl<T: FromEnv> FromEnv for $($pointer)::+<T> {
+            fn from_source(source: &dyn EnvSource) -> Result<Self, FromEnvError> {
+                T::from_source(source).map($($pointer)::+::new)
+            }
+            fn from_source_with_prefix(
+                source: &dyn EnvSource,
+                prefix: &str,
+            ) -> Result<Self, FromEnvError> {
+                T::from_source_with_prefix(source, prefix).map($($pointer)::+::new)
+            }
+            fn env_vars() -> Vec<EnvVarSpec> {
+                T::env_vars()
+            }
+            fn env_vars_with_prefix(prefix: &str) -> Vec<EnvVarSpec> {
+                T::env_vars_with_prefix(prefix)
+            }
+            fn explain_from_source(
+                source: &dyn EnvSource,
+                field_path: &str,
+            ) -> Option<ResolutionPlan> {
+                T::explain_from_source(source, field_path)
+            }
+            fn explain_from_source_with_prefix(
+                source: &dyn EnvSource,
+                field_path: &str,
+                prefix: &str,
+            ) -> Option<ResolutionPlan> {
+                T::explain_from_source_with_prefix(source, field_path, prefix)
+            }
+        }
+    };
+}
+
+impl_from_env_for_pointer!(Box);
+impl_from_env_for_pointer!(std::rc::Rc);
+impl_from_env_for_pointer!(std::sync::Arc);
+
+/// Comparison of two values of a type, implemented by `#[derive(FromEnv)]` with
+/// `#[from_env(diff)]` and by `#[derive(FromEnvValue)]`.
+pub trait EnvDiff {
+    /// Fields differing between `self` and `other`, named as in `env_vars()`.
+    fn env_diff(&self, other: &Self) -> Vec<FieldDiff>;
+    /// Fields differing between `self` and `other`, named as in `env_vars_with_prefix(prefix)`.
+    fn env_diff_with_prefix(&self, other: &Self, prefix: &str) -> Vec<FieldDiff>;
+}
+
+/// Implements `EnvDiff` for a smart pointer by comparing the pointed values.
+macro_rules! impl_env_diff_for_pointer {
+    ($($pointer:ident)::+) => {
+        impl<T: EnvDiff> EnvDiff for $($pointer)::+<T> {
+            fn env_diff(&self, other: &Self) -> Vec<FieldDiff> {
+                T::env_diff(self, other)
+            }
+            fn env_diff_with_prefix(&self, other: &Self, prefix: &str) -> Vec<FieldDiff> {
+                T::env_diff_with_prefix(self, other, prefix)
+            }
+        }
+    };
+}
+
+impl_env_diff_for_pointer!(Box);
+impl_env_diff_for_pointer!(std::rc::Rc);
+impl_env_diff_for_pointer!(std::sync::Arc);
+
+/// Combination of two values of a type, implemented by `#[derive(FromEnv)]` with
+/// `#[from_env(merge)]` and by `#[derive(FromEnvValue)]`.
+pub trait EnvMerge {
+    fn env_merge(self, other: Self, policy: MergePolicy) -> Self;
+}
+
+/// Rendering of a value as command-line arguments, implemented by `#[derive(FromEnv)]` with
+/// `#[from_env(cli_args)]` and by `#[derive(FromEnvValue)]`.
+pub trait CliArgs {
+    /// Appends the arguments of the value to `args`, their names starting with `path` (the
+    /// kebab-cased path of the field holding the value). Values are replaced by `<redacted>` when
+    /// `redact` is set.
+    fn cli_args(&self, path: &str, style: ArgStyle, redact: bool, args: &mut Vec<String>);
+}
+
+impl<T: CliArgs + ?Sized> CliArgs for Box<T> {
+    fn cli_args(&self, path: &str, style: ArgStyle, redact: bool, args: &mut Vec<String>) {
+        (**self).cli_args(path, style, redact, args)
+    }
+}
+
+impl<T: CliArgs + ?Sized> CliArgs for std::rc::Rc<T> {
+    fn cli_args(&self, path: &str, style: ArgStyle, redact: bool, args: &mut Vec<String>) {
+        (**self).cli_args(path, style, redact, args)
+    }
+}
+
+impl<T: CliArgs + ?Sized> CliArgs for std::sync::Arc<T> {
+    fn cli_args(&self, path: &str, style: ArgStyle, redact: bool, args: &mut Vec<String>) {
+        (**self).cli_args(path, style, redact, args)
+    }
 }
@@ -0,0 +1,89 @@
+//! Command-line flags printing the documentation of the configuration, without a CLI parser.
+
+use std::io::Write;
+
+use crate::_inner_trait::FromEnv;
+use crate::{generate, Report, EX_CONFIG};
+
+/// Handles the configuration flags found in [`std::env::args`], printing to stdout:
+/// - `--env-help`: the table of the variables (see [`generate::help_table`]);
+/// - `--env-template`: a `.env` template (see [`generate::dotenv_template`]);
+/// - `--env-check`: the required variables that are not set, then the error loading `T`, if
+///   any. The process exits with [`EX_CONFIG`] when the check fails.
+///
+/// The variables of `doc_hidden` fields are documented only if `--include-hidden` is given too.
+///
+/// Returns whether a flag was handled, in which case `main` should return:
+///
+/// ```rust,no_run
+/// use derive_from_env::FromEnv;
+///
+/// #[derive(FromEnv)]
+/// struct Config {
+///     port: u16,
+/// }
+///
+/// fn main() {
+///     if derive_from_env::cli::handle_env_flags::<Config>() {
+///         return;
+///     }
+///     let config = Config::from_env().unwrap();
+///     println!("listening on {}", config.port);
+/// }
+/// ```
+pub fn handle_env_flags<T: FromEnv>() -> bool {
+    let args = std::env::args().collect::<Vec<_>>();
+    let status = run_env_flags::<T>(&args, &mut std::io::stdout().lock())
+        .expect("failed to write to stdout");
+    match status {
+        None => false,
+        Some(0) => true,
+        Some(code) => std::process::exit(code),
+    }
+}
+
+/// Handles the flags of [`handle_env_flags`] found in `args` (program name first, as in
+/// [`std::env::args`]), writing to `out`.
+///
+/// Returns the exit code when a flag was handled: `0`, or [`EX_CONFIG`] when `--env-check` fails.
+pub fn run_env_flags<T: FromEnv>(
+    args: &[impl AsRef<str>],
+    out: &mut impl Write,
+) -> std::io::Result<Option<i32>> {
+    let has_flag = |flag: &str| args.iter().skip(1).any(|arg| arg.as_ref() == flag);
+    let include_hidden = has_flag("--include-hidden");
+    if has_flag("--env-help") {
+        out.write_all(generate::help_table(&T::env_vars(), include_hidden).as_bytes())?;
+        Ok(Some(0))
+    } else if has_flag("--env-template") {
+        out.write_all(generate::dotenv_template(&T::env_vars(), include_hidden).as_bytes())?;
+        Ok(Some(0))
+    } else if has_flag("--env-check") {
+        let missing = T::env_vars()
+            .into_iter()
+            .filter(|spec| spec.required && std::env::var_os(&spec.name).is_none())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            for spec in &missing {
+                writeln!(
+                    out,
+                    "missing environment variable {} ({}, {})",
+                    spec.name, spec.field, spec.type_name
+                )?;
+            }
+            return Ok(Some(EX_CONFIG.into()));
+        }
+        match T::from_env() {
+            Ok(_) => {
+                writeln!(out, "configuration loaded successfully")?;
+                Ok(Some(0))
+            }
+            Err(error) => {
+                writeln!(out, "{}", Report(error))?;
+                Ok(Some(EX_CONFIG.into()))
+            }
+        }
+    } else {
+        Ok(None)
+    }
+}
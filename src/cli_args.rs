@@ -0,0 +1,9 @@
+/// How the `to_cli_args()` method generated with `#[from_env(cli_args)]` renders the fields.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ArgStyle {
+    /// Renders values as a separate argument (`--port 8080`) rather than with `=`
+    /// (`--port=8080`).
+    pub space_separated: bool,
+    /// Leaves out `secret` fields rather than rendering their value as `<redacted>`.
+    pub skip_secrets: bool,
+}
@@ -0,0 +1,443 @@
+use serde::de::{self, value::StrDeserializer, IntoDeserializer, Visitor};
+
+use crate::{EnvSource, FromEnvError, Origin, ProcessEnv};
+
+/// A [`serde::Deserializer`] reading environment variables with the naming rules of the
+/// [`FromEnv`](crate::FromEnv) derive.
+///
+/// - struct fields are read from `{PREFIX}_{FIELD}` (upper-cased), nested structs from deeper
+///   prefixes (`{PREFIX}_{FIELD}_{NESTED_FIELD}`);
+/// - sequences are read from a single comma separated variable;
+/// - maps collect every variable starting with `{PREFIX}_`, keyed by the rest of the name;
+/// - primitives are parsed with [`FromStr`](std::str::FromStr);
+/// - `Option` fields are `None` when neither the variable nor any variable below it is set.
+///
+/// Variables are read from the process environment, or from any [`EnvSource`] with
+/// [`Deserializer::from_source`].
+///
+/// ```rust
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Database {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     database: Database,
+///     replicas: Vec<String>,
+/// }
+///
+/// std::env::set_var("APP_DATABASE_HOST", "localhost");
+/// std::env::set_var("APP_DATABASE_PORT", "5432");
+/// std::env::set_var("APP_REPLICAS", "db1,db2");
+/// let config = Config::deserialize(derive_from_env::Deserializer::from_prefix("APP")).unwrap();
+/// assert_eq!(config.database.port, 5432);
+/// assert_eq!(config.replicas, vec!["db1", "db2"]);
+/// ```
+pub struct Deserializer<'s> {
+    prefix: Option<String>,
+    source: &'s dyn EnvSource,
+}
+
+impl Deserializer<'static> {
+    /// Reads struct fields from un-prefixed variables.
+    pub fn from_env() -> Self {
+        Deserializer::from_source(&ProcessEnv, None)
+    }
+
+    /// Reads struct fields from variables prefixed with `{prefix}_`.
+    pub fn from_prefix(prefix: &str) -> Self {
+        Deserializer::from_source(&ProcessEnv, Some(prefix))
+    }
+}
+
+impl<'s> Deserializer<'s> {
+    /// Reads struct fields from the variables of `source`, prefixed with `{prefix}_` if given.
+    /// Maps, and structs nested below the prefix, need the source to list its
+    /// [`keys`](EnvSource::keys).
+    pub fn from_source(source: &'s dyn EnvSource, prefix: Option<&str>) -> Self {
+        Deserializer {
+            prefix: prefix.map(str::to_string),
+            source,
+        }
+    }
+
+    fn var_name(&self) -> String {
+        self.prefix.clone().unwrap_or_default()
+    }
+
+    fn child(&self, field: &str) -> Deserializer<'s> {
+        let field = field.to_uppercase();
+        Deserializer {
+            prefix: Some(match &self.prefix {
+                Some(prefix) => format!("{}_{}", prefix, field),
+                None => field,
+            }),
+            source: self.source,
+        }
+    }
+
+    fn is_present(&self) -> Result<bool, FromEnvError> {
+        let var_name = self.var_name();
+        if self.source.get(&var_name)?.is_some() {
+            return Ok(true);
+        }
+        let nested = format!("{}_", var_name);
+        Ok(self
+            .source
+            .keys()?
+            .iter()
+            .any(|key| key.starts_with(&nested)))
+    }
+
+    fn value(&self) -> Result<Value, FromEnvError> {
+        let var_name = self.var_name();
+        match self.source.lookup(&var_name)? {
+            Some((str_value, origin)) => Ok(Value {
+                var_name,
+                str_value,
+                origin,
+            }),
+            None => Err(FromEnvError::MissingEnvVar {
+                origin: self.source.origin(&var_name),
+                var_name,
+                fallbacks: Vec::new(),
+            }),
+        }
+    }
+}
+
+impl de::Error for FromEnvError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        FromEnvError::Custom {
+            var_name: None,
+            message: msg.to_string(),
+        }
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        // Prefixed by the struct being deserialized, and given its origin, see `StructAccess`.
+        FromEnvError::MissingEnvVar {
+            var_name: field.to_uppercase(),
+            origin: Origin::ProcessEnv,
+            fallbacks: Vec::new(),
+        }
+    }
+}
+
+fn with_var_name(error: FromEnvError, var_name: &str) -> FromEnvError {
+    match error {
+        FromEnvError::Custom {
+            var_name: None,
+            message,
+        } => FromEnvError::Custom {
+            var_name: Some(var_name.to_string()),
+            message,
+        },
+        error => error,
+    }
+}
+
+macro_rules! forward_to_value {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.value()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'_> {
+    type Error = FromEnvError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.value()?.deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.is_present()? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let mut access = StructAccess {
+            parent: &self,
+            fields: fields.iter(),
+            current: None,
+            nested_error: false,
+        };
+        match visitor.visit_map(&mut access) {
+            Err(FromEnvError::MissingEnvVar { var_name, .. }) if !access.nested_error => {
+                let var_name = self.child(&var_name).var_name();
+                Err(FromEnvError::MissingEnvVar {
+                    origin: self.source.origin(&var_name),
+                    var_name,
+                    fallbacks: Vec::new(),
+                })
+            }
+            result => result,
+        }
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let prefix = self.prefix.as_ref().map(|prefix| format!("{}_", prefix));
+        let mut vars = Vec::new();
+        for key in self.source.keys()? {
+            let entry = match &prefix {
+                Some(prefix) => match key.strip_prefix(prefix.as_str()) {
+                    Some(entry) => entry.to_string(),
+                    None => continue,
+                },
+                None => key.clone(),
+            };
+            if let Some((str_value, origin)) = self.source.lookup(&key)? {
+                let var_name = key;
+                vars.push((
+                    entry,
+                    Value {
+                        var_name,
+                        str_value,
+                        origin,
+                    },
+                ));
+            }
+        }
+        visitor.visit_map(de::value::MapDeserializer::new(vars.into_iter()))
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value()?.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value()?.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    forward_to_value! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_seq
+        deserialize_identifier deserialize_ignored_any
+    }
+}
+
+struct StructAccess<'a, 's> {
+    parent: &'a Deserializer<'s>,
+    fields: std::slice::Iter<'static, &'static str>,
+    current: Option<Deserializer<'s>>,
+    nested_error: bool,
+}
+
+impl<'de> de::MapAccess<'de> for StructAccess<'_, '_> {
+    type Error = FromEnvError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        // Fields without any variable are not yielded, so that serde applies
+        // `#[serde(default)]`, `None` for options or reports the missing field.
+        for field in self.fields.by_ref() {
+            let child = self.parent.child(field);
+            if child.is_present()? {
+                self.current = Some(child);
+                let key: StrDeserializer<FromEnvError> = field.into_deserializer();
+                return seed.deserialize(key).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let child = self.current.take().expect("value requested before key");
+        seed.deserialize(child)
+            .inspect_err(|_| self.nested_error = true)
+    }
+}
+
+/// The string value of a single variable (or of one element of a list variable).
+struct Value {
+    var_name: String,
+    str_value: String,
+    origin: Origin,
+}
+
+impl Value {
+    fn parse<T: std::str::FromStr>(&self, expected_type: &str) -> Result<T, FromEnvError> {
+        self.str_value
+            .parse()
+            .map_err(|_| FromEnvError::ParsingFailure {
+                var_name: self.var_name.clone(),
+                expected_type: expected_type.to_string(),
+                str_value: self.str_value.clone(),
+                origin: self.origin.clone(),
+            })
+    }
+}
+
+impl<'de> IntoDeserializer<'de, FromEnvError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! parse_value {
+    ($($ty:ident => $method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                let value = self.parse::<$ty>(stringify!($ty))?;
+                visitor.$visit(value).map_err(|error| with_var_name(error, &self.var_name))
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = FromEnvError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let var_name = self.var_name;
+        visitor
+            .visit_string(self.str_value)
+            .map_err(|error| with_var_name(error, &var_name))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let var_name = self.var_name;
+        let elements = if self.str_value.is_empty() {
+            Vec::new()
+        } else {
+            self.str_value
+                .split(',')
+                .map(|element| Value {
+                    var_name: var_name.clone(),
+                    str_value: element.to_string(),
+                    origin: self.origin.clone(),
+                })
+                .collect()
+        };
+        visitor
+            .visit_seq(de::value::SeqDeserializer::new(elements.into_iter()))
+            .map_err(|error| with_var_name(error, &var_name))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let var_name = self.var_name;
+        let variant: de::value::StringDeserializer<FromEnvError> =
+            self.str_value.into_deserializer();
+        visitor
+            .visit_enum(variant)
+            .map_err(|error| with_var_name(error, &var_name))
+    }
+
+    parse_value! {
+        bool => deserialize_bool => visit_bool,
+        i8 => deserialize_i8 => visit_i8,
+        i16 => deserialize_i16 => visit_i16,
+        i32 => deserialize_i32 => visit_i32,
+        i64 => deserialize_i64 => visit_i64,
+        i128 => deserialize_i128 => visit_i128,
+        u8 => deserialize_u8 => visit_u8,
+        u16 => deserialize_u16 => visit_u16,
+        u32 => deserialize_u32 => visit_u32,
+        u64 => deserialize_u64 => visit_u64,
+        u128 => deserialize_u128 => visit_u128,
+        f32 => deserialize_f32 => visit_f32,
+        f64 => deserialize_f64 => visit_f64,
+        char => deserialize_char => visit_char,
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct map struct identifier ignored_any
+    }
+}
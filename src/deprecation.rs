@@ -0,0 +1,56 @@
+use std::sync::RwLock;
+
+/// A field read from one of its `#[from_env(deprecated_alias = "...")]` variables, given to the
+/// hook set with [`set_deprecation_hook`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct DeprecationNotice {
+    /// The struct and the field, e.g. `CacheConfig.host`.
+    pub field: String,
+    /// The variable of the field, which was not set.
+    pub var_name: String,
+    /// The deprecated variable the value was read from.
+    pub deprecated_name: String,
+}
+
+impl std::fmt::Display for DeprecationNotice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is deprecated, set {} instead (field {})",
+            self.deprecated_name, self.var_name, self.field
+        )
+    }
+}
+
+static HOOK: RwLock<Option<fn(&DeprecationNotice)>> = RwLock::new(None);
+
+/// Sets the function called whenever a field is read from a deprecated variable, e.g. to count
+/// the services still using the old names, replacing the previous one.
+///
+/// Without a hook, notices are logged as warnings with the `log` feature, and written to stderr
+/// otherwise.
+///
+/// ```rust
+/// use derive_from_env::{set_deprecation_hook, DeprecationNotice};
+///
+/// fn report(notice: &DeprecationNotice) {
+///     eprintln!("[config] {}", notice);
+/// }
+///
+/// set_deprecation_hook(report);
+/// ```
+pub fn set_deprecation_hook(hook: fn(&DeprecationNotice)) {
+    *HOOK.write().unwrap_or_else(|error| error.into_inner()) = Some(hook);
+}
+
+/// Gives the notice to the hook, or logs it.
+pub(crate) fn notify(notice: &DeprecationNotice) {
+    let hook = *HOOK.read().unwrap_or_else(|error| error.into_inner());
+    match hook {
+        Some(hook) => hook(notice),
+        #[cfg(feature = "log")]
+        None => log::warn!("{}", notice),
+        #[cfg(not(feature = "log"))]
+        None => eprintln!("warning: {}", notice),
+    }
+}
@@ -0,0 +1,24 @@
+/// A field holding different values in two configurations, as returned by the `diff()` method
+/// generated with `#[from_env(diff)]`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FieldDiff {
+    /// Path of the field, with nested fields joined by `.` (e.g. `database.host`).
+    pub field: String,
+    /// Name of the variable filling the field, as given by `env_vars()`.
+    pub var_name: String,
+    /// Value of the field in the configuration `diff` is called on, `<redacted>` for `secret`
+    /// fields.
+    pub old: String,
+    /// Value of the field in the other configuration, `<redacted>` for `secret` fields.
+    pub new: String,
+}
+
+impl std::fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}): {} -> {}",
+            self.field, self.var_name, self.old, self.new
+        )
+    }
+}
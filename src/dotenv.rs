@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::{EnvSource, FromEnvError, Origin};
+
+/// The variables of a `.env` file.
+///
+/// Each line holds a `KEY=VALUE` pair, optionally preceded by `export`. Blank lines and lines
+/// starting with `#` are ignored. Values are either:
+/// - unquoted: surrounding whitespace and a trailing comment (` # ...`) are removed;
+/// - single-quoted (`'...'`): taken literally;
+/// - double-quoted (`"..."`): `\n`, `\r`, `\t`, `\"`, `\\` and `\$` escapes are replaced.
+///
+/// When a variable is set several times, the last value wins. Values are not interpolated and
+/// cannot span several lines.
+///
+/// ```rust
+/// use derive_from_env::{DotenvSource, FromEnv};
+///
+/// #[derive(FromEnv)]
+/// struct Config {
+///     port: u16,
+/// }
+///
+/// let path = std::env::temp_dir().join("derive_from_env_dotenv_doc.env");
+/// std::fs::write(&path, "# local settings\nexport PORT=8080\n").unwrap();
+/// let source = DotenvSource::from_path(&path).unwrap();
+/// assert_eq!(Config::from_source(&source).unwrap().port, 8080);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DotenvSource {
+    path: PathBuf,
+    vars: BTreeMap<String, (String, usize)>,
+}
+
+impl DotenvSource {
+    /// Reads and parses the file at `path`.
+    pub fn from_path(path: impl Into<PathBuf>) -> Result<Self, FromEnvError> {
+        let path = path.into();
+        let content =
+            std::fs::read_to_string(&path).map_err(|error| FromEnvError::SourceFailure {
+                var_name: None,
+                message: format!("failed to read {}: {}", path.display(), error),
+            })?;
+        let vars = parse(&content)
+            .map_err(|(line, message)| FromEnvError::SourceFailure {
+                var_name: None,
+                message: format!("{} line {}: {}", path.display(), line, message),
+            })?
+            .into_iter()
+            .map(|entry| (entry.key, (entry.value, entry.line)))
+            .collect();
+        Ok(DotenvSource { path, vars })
+    }
+
+    /// The file read by the source.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl EnvSource for DotenvSource {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        Ok(self.vars.get(key).map(|(value, _)| value.clone()))
+    }
+    fn origin(&self, key: &str) -> Origin {
+        Origin::DotenvFile {
+            path: self.path.clone(),
+            line: self.vars.get(key).map(|(_, line)| *line),
+        }
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        Ok(self.vars.keys().cloned().collect())
+    }
+}
+
+/// Variables read from `KEY=VALUE` lines of any reader, such as a pipe or an inherited file
+/// descriptor passing secrets (`myapp 3< secrets.txt`).
+///
+/// The lines follow the grammar of [`DotenvSource`]. When a variable is set several times, the
+/// last value wins, unless the source is read with [`ReaderSource::from_reader_strict`]. Errors
+/// give the line number of the invalid line, and the origin of a value is its line in the input.
+///
+/// ```rust
+/// use derive_from_env::{FromEnv, ReaderSource};
+///
+/// #[derive(FromEnv)]
+/// struct Secrets {
+///     api_token: String,
+/// }
+///
+/// let input = std::io::Cursor::new("# from the vault\nAPI_TOKEN='s3cr3t#1'\n");
+/// let source = ReaderSource::from_reader(input).unwrap();
+/// assert_eq!(Secrets::from_source(&source).unwrap().api_token, "s3cr3t#1");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReaderSource {
+    vars: BTreeMap<String, (String, usize)>,
+}
+
+impl ReaderSource {
+    /// Reads `reader` to its end and parses its lines.
+    pub fn from_reader(reader: impl Read) -> Result<Self, FromEnvError> {
+        Self::read(reader, false)
+    }
+
+    /// Same as [`ReaderSource::from_reader`], but a variable set several times is an error.
+    pub fn from_reader_strict(reader: impl Read) -> Result<Self, FromEnvError> {
+        Self::read(reader, true)
+    }
+
+    fn read(mut reader: impl Read, strict: bool) -> Result<Self, FromEnvError> {
+        let source_failure = |message: String| FromEnvError::SourceFailure {
+            var_name: None,
+            message,
+        };
+        let mut content = String::new();
+        reader
+            .read_to_string(&mut content)
+            .map_err(|error| source_failure(format!("failed to read the input: {}", error)))?;
+        let entries = parse(&content)
+            .map_err(|(line, message)| source_failure(format!("line {}: {}", line, message)))?;
+        let mut vars = BTreeMap::new();
+        for entry in entries {
+            if let Some((_, first_line)) = vars.insert(entry.key.clone(), (entry.value, entry.line))
+            {
+                if strict {
+                    return Err(source_failure(format!(
+                        "line {}: {} is already set on line {}",
+                        entry.line, entry.key, first_line
+                    )));
+                }
+            }
+        }
+        Ok(ReaderSource { vars })
+    }
+}
+
+impl EnvSource for ReaderSource {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        Ok(self.vars.get(key).map(|(value, _)| value.clone()))
+    }
+    fn origin(&self, key: &str) -> Origin {
+        match self.vars.get(key) {
+            Some((_, line)) => Origin::Remote(format!("input line {}", line)),
+            None => Origin::Remote("input".to_string()),
+        }
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        Ok(self.vars.keys().cloned().collect())
+    }
+}
+
+/// A `KEY=VALUE` pair with its (1-based) line number.
+pub(crate) struct Entry {
+    pub line: usize,
+    pub key: String,
+    pub value: String,
+}
+
+/// Parses `KEY=VALUE` lines, or returns the line number of the first invalid line with a
+/// description of the problem.
+pub(crate) fn parse(content: &str) -> Result<Vec<Entry>, (usize, String)> {
+    let mut vars = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line
+            .strip_prefix("export")
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            return Err((line_number, "expected KEY=VALUE".to_string()));
+        };
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        {
+            return Err((line_number, format!("invalid variable name {:?}", key)));
+        }
+        let value = parse_value(value.trim()).map_err(|message| (line_number, message))?;
+        vars.push(Entry {
+            line: line_number,
+            key: key.to_string(),
+            value,
+        });
+    }
+    Ok(vars)
+}
+
+fn parse_value(value: &str) -> Result<String, String> {
+    let (parsed, rest) = if let Some(quoted) = value.strip_prefix('\'') {
+        let end = quoted
+            .find('\'')
+            .ok_or_else(|| "unterminated single quote".to_string())?;
+        (quoted[..end].to_string(), &quoted[end + 1..])
+    } else if let Some(quoted) = value.strip_prefix('"') {
+        let mut parsed = String::new();
+        let mut chars = quoted.char_indices();
+        let end = loop {
+            match chars.next() {
+                None => return Err("unterminated double quote".to_string()),
+                Some((end, '"')) => break end,
+                Some((_, '\\')) => match chars.next() {
+                    Some((_, 'n')) => parsed.push('\n'),
+                    Some((_, 'r')) => parsed.push('\r'),
+                    Some((_, 't')) => parsed.push('\t'),
+                    Some((_, c @ ('"' | '\\' | '$'))) => parsed.push(c),
+                    Some((_, c)) => {
+                        parsed.push('\\');
+                        parsed.push(c);
+                    }
+                    None => return Err("unterminated double quote".to_string()),
+                },
+                Some((_, c)) => parsed.push(c),
+            }
+        };
+        (parsed, &quoted[end + 1..])
+    } else {
+        let end = value
+            .char_indices()
+            .find(|&(i, c)| c == '#' && value[..i].ends_with(char::is_whitespace))
+            .map_or(value.len(), |(i, _)| i);
+        return Ok(value[..end].trim_end().to_string());
+    };
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') {
+        Ok(parsed)
+    } else {
+        Err(format!(
+            "unexpected characters after the quoted value: {:?}",
+            rest
+        ))
+    }
+}
@@ -0,0 +1,109 @@
+use crate::Origin;
+
+/// Locations consulted to fill a field, in order, as returned by the generated `explain()`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResolutionPlan {
+    /// Path of the field, with nested fields joined by `.` (e.g. `database.host`).
+    pub field: String,
+    /// Locations consulted for the field, the first one holding a value giving it.
+    pub steps: Vec<ResolutionStep>,
+}
+
+impl ResolutionPlan {
+    /// The step giving the value of the field, `None` when the field is left unset (an `Option`
+    /// field) or missing. `append_from` variables add to the value and are never returned.
+    pub fn resolved(&self) -> Option<&ResolutionStep> {
+        self.steps.iter().find(|step| step.gives_value())
+    }
+}
+
+impl std::fmt::Display for ResolutionPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:", self.field)?;
+        let resolved = self.resolved();
+        for (index, step) in self.steps.iter().enumerate() {
+            write!(f, "\n{}. {}", index + 1, step)?;
+            if resolved.is_some_and(|resolved| std::ptr::eq(resolved, step)) {
+                write!(f, " <- used")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A location consulted to fill a field, with its current value.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResolutionStep {
+    pub location: Location,
+    /// Where the value is looked for, for the locations read from a source.
+    pub origin: Option<Origin>,
+    /// Value found at the location, `<redacted>` for `secret` fields. Lookups failing are reported
+    /// as unset.
+    pub value: Option<String>,
+}
+
+impl ResolutionStep {
+    fn gives_value(&self) -> bool {
+        match self.location {
+            Location::AppendFrom(_) => false,
+            Location::TypeDefault | Location::DefaultFn(_) => true,
+            _ => self.value.is_some(),
+        }
+    }
+}
+
+impl std::fmt::Display for ResolutionStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.location)?;
+        if let Some(origin) = &self.origin {
+            write!(f, " in {}", origin)?;
+        }
+        match (&self.value, &self.location) {
+            (_, Location::TypeDefault | Location::DefaultFn(_)) => Ok(()),
+            (Some(value), _) => write!(f, ": {:?}", value),
+            (None, _) => write!(f, ": not set"),
+        }
+    }
+}
+
+/// Kind of location consulted to fill a field.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Location {
+    /// The `credential` file of that name, read when `$CREDENTIALS_DIRECTORY` is set.
+    Credential(String),
+    /// The variable of the field.
+    Variable(String),
+    /// The file named by the `<NAME>_FILE` variable of a `file` field.
+    File(String),
+    /// An `alias` of the variable.
+    Alias(String),
+    /// A `deprecated_alias` of the variable.
+    DeprecatedAlias(String),
+    /// The `default_env` variable.
+    DefaultEnv(String),
+    /// The `default` attribute.
+    Default,
+    /// The `Default` implementation of the type, under `all_defaultable`.
+    TypeDefault,
+    /// The `default_fn` function, by path.
+    DefaultFn(String),
+    /// An `append_from` variable, whose items are added to the value.
+    AppendFrom(String),
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Credential(name) => write!(f, "credential {}", name),
+            Location::Variable(name) => write!(f, "variable {}", name),
+            Location::File(name) => write!(f, "file named by {}", name),
+            Location::Alias(name) => write!(f, "alias {}", name),
+            Location::DeprecatedAlias(name) => write!(f, "deprecated alias {}", name),
+            Location::DefaultEnv(name) => write!(f, "default_env {}", name),
+            Location::Default => write!(f, "default attribute"),
+            Location::TypeDefault => write!(f, "default value of the type"),
+            Location::DefaultFn(path) => write!(f, "default_fn {}", path),
+            Location::AppendFrom(name) => write!(f, "appended from {}", name),
+        }
+    }
+}
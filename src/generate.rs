@@ -0,0 +1,93 @@
+//! Documentation generated from the [`EnvVarSpec`]s returned by `env_vars()`.
+
+use crate::EnvVarSpec;
+
+/// The specs to document, without the hidden ones unless `include_hidden` is set.
+fn shown(specs: &[EnvVarSpec], include_hidden: bool) -> impl Iterator<Item = &EnvVarSpec> {
+    specs
+        .iter()
+        .filter(move |spec| include_hidden || !spec.hidden)
+}
+
+/// Column of the default value: the `default` attribute, or whether the variable is required.
+fn default_column(spec: &EnvVarSpec) -> String {
+    match &spec.default {
+        Some(default) => default.clone(),
+        None if spec.required => "<required>".to_string(),
+        None if spec.optional => "<optional>".to_string(),
+        None => "<type default>".to_string(),
+    }
+}
+
+/// Renders the variables as an aligned table, one line per variable.
+///
+/// ```text
+/// VARIABLE      TYPE    DEFAULT     FIELD
+/// HTTP_PORT     u16     8080        http_port
+/// DATABASE_URL  String  <required>  database.url
+/// ```
+///
+/// Variables of `doc_hidden` fields are left out unless `include_hidden` is set.
+pub fn help_table(specs: &[EnvVarSpec], include_hidden: bool) -> String {
+    let header = ["VARIABLE", "TYPE", "DEFAULT", "FIELD"].map(String::from);
+    let rows = std::iter::once(header)
+        .chain(shown(specs, include_hidden).map(|spec| {
+            [
+                spec.name.clone(),
+                spec.type_name.clone(),
+                default_column(spec),
+                spec.field.clone(),
+            ]
+        }))
+        .collect::<Vec<_>>();
+    let mut widths = [0; 4];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut table = String::new();
+    for row in &rows {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/// Renders the variables as a `.env` template. Required variables are left to fill in, the other
+/// ones are commented out, with their default value if any.
+///
+/// ```text
+/// # http_port (u16)
+/// #HTTP_PORT=8080
+/// # database.url (String), required
+/// DATABASE_URL=
+/// ```
+///
+/// Variables of `doc_hidden` fields are left out unless `include_hidden` is set.
+pub fn dotenv_template(specs: &[EnvVarSpec], include_hidden: bool) -> String {
+    let mut template = String::new();
+    for spec in shown(specs, include_hidden) {
+        let requirement = if spec.required {
+            ", required"
+        } else if spec.optional {
+            ", optional"
+        } else {
+            ""
+        };
+        template.push_str(&format!(
+            "# {} ({}){}\n",
+            spec.field, spec.type_name, requirement
+        ));
+        let comment = if spec.required { "" } else { "#" };
+        let default = spec.default.as_deref().unwrap_or_default();
+        template.push_str(&format!("{}{}={}\n", comment, spec.name, default));
+    }
+    template
+}
@@ -0,0 +1,120 @@
+use std::sync::{Arc, OnceLock};
+
+use crate::_inner_trait::FromEnv;
+use crate::{EnvSource, FromEnvError};
+
+/// Nested section loaded on first access, for `#[from_env(flatten, lazy)]` fields.
+///
+/// Loading the parent struct only captures the prefix of the section and a handle on the source
+/// (see [`EnvSource::shared`]): none of the variables of the section are read, so the parent loads
+/// successfully even when the section is misconfigured. The section is loaded by the first call
+/// to [`Lazy::get`] (or dereference), and the result, error included, is kept for the next ones.
+///
+/// Sources which can't be shared, such as a borrowed [`DotenvSource`](crate::DotenvSource), load
+/// the section right away, errors being reported by `get()` all the same. Wrap them in an `Arc` to
+/// defer the loading.
+pub struct Lazy<T> {
+    value: OnceLock<Result<T, FromEnvError>>,
+    source: Option<Arc<dyn EnvSource + Send + Sync>>,
+    prefix: Option<String>,
+}
+
+impl<T: FromEnv> Lazy<T> {
+    fn new(source: &dyn EnvSource, prefix: Option<&str>) -> Self {
+        let lazy: Self = Lazy {
+            value: OnceLock::new(),
+            source: source.shared(),
+            prefix: prefix.map(str::to_string),
+        };
+        if lazy.source.is_none() {
+            let _ = lazy.value.set(lazy.load(source));
+        }
+        lazy
+    }
+
+    fn load(&self, source: &dyn EnvSource) -> Result<T, FromEnvError> {
+        match &self.prefix {
+            Some(prefix) => T::from_source_with_prefix(source, prefix),
+            None => T::from_source(source),
+        }
+    }
+
+    /// Loads the section on the first call, and returns the result of that loading.
+    pub fn get(&self) -> Result<&T, &FromEnvError> {
+        self.value
+            .get_or_init(|| match &self.source {
+                Some(source) => self.load(source.as_ref()),
+                None => unreachable!("sections of sources which can't be shared are loaded"),
+            })
+            .as_ref()
+    }
+
+    /// Whether the section has been loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.value.get().is_some()
+    }
+}
+
+impl<T> Lazy<T> {
+    /// Section holding `value`, already loaded.
+    pub fn loaded(value: T) -> Self {
+        Lazy {
+            value: OnceLock::from(Ok(value)),
+            source: None,
+            prefix: None,
+        }
+    }
+}
+
+/// Loads the section on first dereference.
+///
+/// # Panics
+/// Panics when the section fails to load, use [`Lazy::get`] to handle the error.
+impl<T: FromEnv> std::ops::Deref for Lazy<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self.get() {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load a lazy section: {}", error),
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.value.get() {
+            Some(Ok(value)) => f.debug_tuple("Lazy").field(value).finish(),
+            Some(Err(error)) => f.debug_tuple("Lazy").field(error).finish(),
+            None => f.write_str("Lazy(<not loaded>)"),
+        }
+    }
+}
+
+impl<T: FromEnv> FromEnv for Lazy<T> {
+    fn from_source(source: &dyn EnvSource) -> Result<Self, FromEnvError> {
+        Ok(Lazy::new(source, None))
+    }
+    fn from_source_with_prefix(source: &dyn EnvSource, prefix: &str) -> Result<Self, FromEnvError> {
+        Ok(Lazy::new(source, Some(prefix)))
+    }
+    fn env_vars() -> Vec<crate::EnvVarSpec> {
+        T::env_vars()
+    }
+    fn env_vars_with_prefix(prefix: &str) -> Vec<crate::EnvVarSpec> {
+        T::env_vars_with_prefix(prefix)
+    }
+    fn explain_from_source(
+        source: &dyn EnvSource,
+        field_path: &str,
+    ) -> Option<crate::ResolutionPlan> {
+        T::explain_from_source(source, field_path)
+    }
+    fn explain_from_source_with_prefix(
+        source: &dyn EnvSource,
+        field_path: &str,
+        prefix: &str,
+    ) -> Option<crate::ResolutionPlan> {
+        T::explain_from_source_with_prefix(source, field_path, prefix)
+    }
+}
@@ -55,39 +55,803 @@
 //!     auth: AuthConfig
 //! }
 //!
-//! fn main() {
-//!     std::env::set_var("EXTERNAL_SERVICE_API_KEY", "api-key");
-//!     std::env::set_var("EXT_SERVICE_URL", "http://external.service/api");
-//!     std::env::set_var("PORT","8080");
-//!     std::env::set_var("AUTH_METHOD","Bearer");
-//!     std::env::set_var("API_KEY","api-key");
-//!     let app_config = AppConfig::from_env().unwrap();
-//!     assert_eq!(app_config, AppConfig {
-//!         port: Some(8080),
-//!         addr: IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
-//!         external_service: ServiceConfig {
-//!             api_key: "api-key".into(),
-//!             base_url: "http://external.service/api".into()
-//!         },
-//!         auth: AuthConfig {
-//!             auth_method: AuthMethod::Bearer,
-//!             api_key: "api-key".into()
-//!         }
-//!     });
-//! }
+//! std::env::set_var("EXTERNAL_SERVICE_API_KEY", "api-key");
+//! std::env::set_var("EXT_SERVICE_URL", "http://external.service/api");
+//! std::env::set_var("PORT","8080");
+//! std::env::set_var("AUTH_METHOD","Bearer");
+//! std::env::set_var("API_KEY","api-key");
+//! let app_config = AppConfig::from_env().unwrap();
+//! assert_eq!(app_config, AppConfig {
+//!     port: Some(8080),
+//!     addr: IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+//!     external_service: ServiceConfig {
+//!         api_key: "api-key".into(),
+//!         base_url: "http://external.service/api".into()
+//!     },
+//!     auth: AuthConfig {
+//!         auth_method: AuthMethod::Bearer,
+//!         api_key: "api-key".into()
+//!     }
+//! });
 //!```
+//!
+//! ## Field attributes
+//! - `var = "NAME"`: reads the variable `NAME`, whatever the prefix. `var = ["NAME", "OTHER"]`
+//!   tries each name in order and reads the first one set, e.g. `GITHUB_TOKEN` then `GH_TOKEN`
+//!   the way `gh` resolves its credentials. A [`MissingEnvVar`](FromEnvError::MissingEnvVar) error
+//!   lists the other names in `fallbacks`, and parsing failures and `explain()` name the variable
+//!   giving the value.
+//! - `default = "value"`: parsed when the variable is not set. `Option` fields with a default are
+//!   `Some` of it rather than `None`, for optional values with a recommended setting; with
+//!   `non_empty`, an empty value takes the default as well.
+//! - `alias = "NAME"` (repeatable): reads the variable `NAME` when the field's variable is not
+//!   set, e.g. a legacy name during a migration. Aliases are tried in order and get the same
+//!   prefixes as the field (`APP_NAME` in a struct with `prefix = "APP_"`), unless the field has a
+//!   `var` attribute. A [`MissingEnvVar`](FromEnvError::MissingEnvVar) error lists them after the
+//!   field's variable, and parsing failures name the variable giving the value.
+//! - `alias_var = "NAME"` (repeatable): reads the variable `NAME`, whatever the prefix, after the
+//!   aliases.
+//! - `deprecated_alias = "OLD_NAME"` (repeatable): reads `OLD_NAME`, prefixed like an `alias`,
+//!   when none of the other names of the field is set, and reports it as a [`DeprecationNotice`]
+//!   naming both variables and the field. Notices go to the hook set with
+//!   [`set_deprecation_hook`], or are logged as warnings with the `log` feature, and written to
+//!   stderr otherwise.
+//! - `default_env = "NAME"`: reads the variable `NAME`, whatever the prefix, when neither the
+//!   field's variable nor its aliases are set, before falling back to the `default` attribute,
+//!   e.g. a `LOG_LEVEL` shared by several services.
+//! - `lenient`: an invalid value gives the default of the field instead of a parsing failure, for
+//!   tuning knobs that shouldn't stop the process, such as `CACHE_TTL=banana`. The failure is
+//!   recorded as an [`EnvWarning`] of kind [`EnvWarningKind::LenientFallback`], returned by
+//!   `from_env_with_warnings()` (and `from_source_with_warnings`). Requires a `default` or
+//!   `default_fn` attribute, a bare `default` or an `Option` field.
+//! - `validate = "path::to::function"`: checks the value with a `fn(&T) -> Result<(), String>`
+//!   function once loaded, defaults included, failing with
+//!   [`ValidationFailure`](FromEnvError::ValidationFailure) and its message otherwise, e.g. to
+//!   reject port `0`. Collections are checked as a whole, and `Option` fields when they are `Some`.
+//! - `range = "1..=65535"`: rejects integers and floats outside the range, with
+//!   [`ValidationFailure`](FromEnvError::ValidationFailure) stating the range and the value. Takes
+//!   `a..b`, `a..=b`, `a..` and `..b` with number bounds, checked at compile time. Lists are
+//!   checked item by item, and defaults like any other value.
+//! - `matches = "^[a-z][a-z0-9-]{2,29}$"` (`regex` feature): rejects strings not matching the
+//!   regular expression, with [`ValidationFailure`](FromEnvError::ValidationFailure) giving the
+//!   pattern, e.g. for tenant identifiers or bucket names. Invalid patterns fail to compile, and
+//!   valid ones are compiled once, when first used. Lists are checked item by item.
+//! - `min_len = 1` and `max_len = 5`: bound the number of characters of a string, or of elements
+//!   of a collection, e.g. `min_len = 40, max_len = 40` for API keys, failing with
+//!   [`ValidationFailure`](FromEnvError::ValidationFailure) giving the limit and the actual size.
+//!   Defaults are checked too, so a bad one fails at startup.
+//! - `default_fn = "path::to::function"`: calls the function when the variable is not set, for
+//!   defaults computed at runtime such as the number of CPUs. It returns the field type or
+//!   something converting `Into` it, `T` or `Option<T>` on `Option` fields, and is not called when
+//!   the variable is set. Mutually exclusive with `default`.
+//! - `from_str`: parses a type unknown to the derive with its `FromStr` implementation. Fields
+//!   with a `default` or a `var` attribute, and the content of `Option` fields, are always parsed
+//!   with `FromStr`.
+//! - `from = "Type"`, `try_from = "Type"`: parses the value as `Type` with its `FromStr`
+//!   implementation, then converts it into the field type with `From<Type>` or `TryFrom<Type>`,
+//!   for types without `FromStr` such as `Cow<'static, str>`. `TryFrom` errors are reported with
+//!   their `Display` message.
+//! - `parse_with = "path::to::function"`: parses the value with a `fn(&str) -> Result<T, E>`
+//!   function instead of `FromStr`, for types from other crates or formats of your own. The
+//!   `Display` message of `E` is part of the parsing failure. Collections are parsed item by item,
+//!   and map values entry by entry. Not supported with `from`, `try_from`, `json`, `format`,
+//!   `base64`, `hex` or `lenient_bool`.
+//! - `with = "path::to::module"`: parses the value with the `parse` function of the module, as
+//!   `parse_with` does, and renders it with its `fn to_string(&T) -> String` in `to_cli_args`,
+//!   keeping both directions side by side. Collections are rendered item by item.
+//! - `type_name = "requests per second (integer)"`: the type named in parsing failures, in
+//!   `env_vars()` and in the generated documentation instead of the Rust type (the content type of
+//!   `Option` fields), for custom types meaning nothing to operators. Not supported on nested
+//!   structs.
+//! - `flatten`: loads a nested struct, with the field name appended to the prefix. On an
+//!   `Option` field, the section is `None` when none of its required variables is set, and loading
+//!   fails when only some of them are; variables with a default or of `Option` fields don't count.
+//! - `default` without a value: loads `Default::default()` when the variable is not set, for
+//!   defaults not written as a string such as an empty `Vec`; the type must implement `Default`.
+//!   On an `Option` field, that is `None`, as without the attribute.
+//!   On a nested struct, the default is loaded when none of the variables of its section is set.
+//!   As soon as one is, the section is loaded as usual. A field can't have both `default` forms.
+//! - `no_prefix`: loads a nested struct without prefix. On other fields, reads the variable named
+//!   after the field alone, whatever the prefixes of the struct and of the fields holding it, e.g.
+//!   `home: PathBuf` reading `HOME` in a struct with `prefix = "APP_"`. Not supported with `var`,
+//!   which bypasses prefixes already.
+//! - `prefix = "SEGMENT"`: on a field read from a single variable, inserts `SEGMENT_` between the
+//!   prefixes and the name of the field (and of its aliases), e.g. `sentry_dsn` reading
+//!   `APP_OBSERVABILITY_SENTRY_DSN` in a struct with `prefix = "APP_"`, without a one-field nested
+//!   struct. Not supported with `var` or `no_prefix`.
+//! - `indexed`: on a `Vec` of a derived struct, loads an element per numbered prefix
+//!   (`UPSTREAMS_0_HOST`, `UPSTREAMS_0_PORT`, `UPSTREAMS_1_HOST`...), each with
+//!   `from_env_with_prefix("UPSTREAMS_0")` so that defaults and nested prefixes apply per element,
+//!   until an element has none of its variables set. Elements after a gap are ignored, or an error
+//!   with `deny_gaps` (detected among the [keys](EnvSource::keys) of the source). `env_vars()`
+//!   describes the variables of the first element.
+//! - `lazy`: on a nested [`Lazy<T>`](Lazy), defers the loading of the section to its first access,
+//!   for sections expensive to load or only used on some code paths. The variables of the section
+//!   are then not read by `from_env`, which succeeds even when the section is misconfigured: its
+//!   errors are only reported by [`Lazy::get`] (or a panic on dereference).
+//! - `path_list`: on a `Vec<PathBuf>`, splits the variable like `PATH`, with
+//!   [`std::env::split_paths`] (on `:` on Unix and on `;` on Windows). The variable is read with
+//!   [`std::env::var_os`] so non UTF-8 paths are kept as-is, and empty entries are dropped.
+//! - `separator = ";"`: on a `Vec` or a map, the separator of the items (`,` by default, or the
+//!   `separator` struct attribute). Separators can be several characters long, and `"\n"` reads
+//!   one item per line. Parsing failures of an item name the separator.
+//! - `tuple_separator = ":"`: on a tuple, the separator of its components (`,` by default).
+//! - `kv_separator = ":"`: on a map, the separator of the keys and values of the entries (`=` by
+//!   default, or the `kv_separator` struct attribute), e.g. `LABELS=env:prod;team:core` with
+//!   `separator = ";"`.
+//! - `split = "whitespace"`: on a list, splits the value on runs of whitespace rather than on a
+//!   separator (`SEEDS="node1 node2"`), a blank value giving an empty list. Not supported with
+//!   `separator` or `quoted`.
+//! - `quoted`: on a list, allows double-quoted items holding the separator, `""` standing for a
+//!   quote (`HEADERS=X-Trace,"X-Forwarded, For"`). Quoted items are not trimmed and may be empty.
+//!   An unterminated quote is a parsing failure naming the position of the item.
+//! - `count_var = "PEER_COUNT"`: on a `Vec`, reads the number of items from the variable
+//!   `PEER_COUNT`, whatever the prefix, and each item from its own variable, named after the field
+//!   (`PEER_0`, `PEER_1`...) rather than splitting a single value. A missing item is reported with
+//!   its name, and a count of `0` reads no item. `Option` fields are `None` when the count is not
+//!   set.
+//! - `deny_duplicates`: on a `HashSet` or a `BTreeSet`, rejects a list holding an item twice
+//!   instead of dropping the duplicate, to catch typos in lists such as feature flags.
+//! - `append_from = "NAME"` (repeatable): on a `Vec`, appends the items of the variable `NAME`,
+//!   when set, to those of the field's variable (or its default), e.g. `FEATURES_EXTRA` adding
+//!   to `FEATURES`.
+//! - `non_empty`: treats an empty value as if the variable was not set, e.g. when a manifest sets
+//!   `REDIS_URL=""` for an undefined value: `Option` fields are `None`, fields with a default take
+//!   it and other fields are missing. With `non_empty = "trim"`, values made of whitespace are
+//!   treated the same way (other values are kept as they are). Not supported on `OsString`,
+//!   `path_list` fields and nested structs.
+//! - `trim`: strips the whitespace surrounding the value before parsing it, such as the trailing
+//!   newline (`\n` or `\r\n`) or space gained by a value pasted into a secret store, so that
+//!   `" 8080\r\n"` reads as `8080`. With `non_empty`, values made only of whitespace are then
+//!   empty. `raw` opts the field out of the `trim` of the struct, reading the value verbatim.
+//! - `lenient_bool`: on a `bool`, also accepts `1`/`0`, `yes`/`no` and `on`/`off`, in any case
+//!   (`True`, `YES`...). Other values are parsing failures listing the accepted spellings.
+//! - `flag`: on a `bool`, reads `true` when the variable is set, whatever its value (even empty),
+//!   and `false` when it is not, without parsing it, like toggles such as `ENABLE_TRACING`. Not
+//!   supported with `default`.
+//! - `base64`: on a `Vec<u8>` or a `String`, decodes the value (and the default) as base64 with
+//!   the standard alphabet, or the URL-safe one with `base64 = "url"`, padding being optional.
+//!   Decoding failures tell what is wrong without showing the value, replaced by `<redacted>`.
+//! - `hex`: on a `Vec<u8>` or a `[u8; N]`, decodes the value (and the default) as hexadecimal in
+//!   any case, with an optional `0x` prefix. Arrays must decode to exactly `N` bytes. As with
+//!   `base64`, failures don't show the value.
+//! - `decimal_comma`: on a float, accepts a single `,` as decimal separator (`1,5`). Values
+//!   containing both `,` and `.` are rejected as ambiguous.
+//! - `percent`: on a float, accepts percentages: `95%` (surrounding whitespace allowed) is read as
+//!   `0.95`, while a value without `%` is taken as already fractional, or rejected with
+//!   `percent = "require_sign"`.
+//! - `credential = "name"`: when `$CREDENTIALS_DIRECTORY` is set (systemd `LoadCredential=`),
+//!   reads the value from the file `name` in that directory, without its trailing newline, instead
+//!   of the variable. A missing file is then an error naming its path, except on `Option` fields
+//!   which fall back to the variable. The variable is read as usual when the directory is not set.
+//! - `file`: when the variable is not set, reads the value from the file named by `<NAME>_FILE`,
+//!   without its trailing newline, the convention of Docker secrets and Kubernetes mounts (e.g.
+//!   `DB_PASSWORD_FILE=/run/secrets/db_password`). Failing to read the file gives a
+//!   [`FileReadFailure`](FromEnvError::FileReadFailure) with its path and the kind of I/O error.
+//!   `no_file` opts the field out of the `file_suffix` of the struct.
+//! - `doc_hidden`: reads the variable as usual but marks its [`EnvVarSpec`] as `hidden`, leaving it
+//!   out of the generated documentation (see [`generate`]) unless hidden variables are requested,
+//!   e.g. for variables injected by the platform rather than set by hand.
+//! - `secret`: hides the value of the field, replaced by `<redacted>`, in its errors (and so their
+//!   reports), keeping the variable name and expected type, in the diffs of structs with the
+//!   `diff` attribute, their command-line arguments and their resolution plans. Its
+//!   [`EnvVarSpec`] and manifest entry are marked `secret`. Messages returned by `validate`
+//!   functions are shown as they are.
+//! - `json` (`json` feature): parses the value as JSON into any `serde::de::DeserializeOwned` type,
+//!   e.g. `RETRY_POLICY={"max": 5, "backoff_ms": 200}`. Parsing failures give the message of
+//!   `serde_json`, with its line and column.
+//! - `format = "yaml"` (`yaml` feature) or `format = "toml"` (`toml` feature): parses the value as
+//!   a YAML or TOML document, the same way as `json` (`format = "json"`). Other formats are
+//!   rejected at compile time.
+//! - `json_var = "DB_CONFIG"` (`json` feature): on a nested struct which also implements
+//!   `serde::de::DeserializeOwned`, reads the whole struct from the JSON document in `DB_CONFIG`
+//!   (whatever the prefix) when it is set, and from the variables of its fields otherwise. An
+//!   invalid document is a parsing failure of `DB_CONFIG`, without falling back to the fields.
+//! - `url_var = "DATABASE_URL"`: on a nested struct which also implements `FromStr`, parses the
+//!   whole struct from `DATABASE_URL` (whatever the prefix) when it is set, and reads it from the
+//!   variables of its fields otherwise. Parsing failures name `DATABASE_URL`. With
+//!   `deny_conflicts`, setting both `DATABASE_URL` and variables of the fields is an error naming
+//!   them, rather than the URL taking precedence.
+//! - `json_pointer = "/path/0/leaf"` (`serde` feature): parses the variable as a JSON document and
+//!   reads the string, number or boolean found at this [RFC 6901](https://www.rfc-editor.org/rfc/rfc6901)
+//!   pointer. A missing target, or a target which is not a scalar, is an error naming the pointer.
+//!   Usually combined with `var`, e.g. `var = "PLATFORM_JSON"`.
+//!
+//! `Vec<T>` fields are read from a list of items separated by `,` (or the `separator`
+//! attribute), each parsed with the `FromStr` implementation of `T`. Items are trimmed and empty
+//! items are dropped, so an empty variable gives an empty list: an `Option<Vec<T>>` field is
+//! `None` when the variable is not set and `Some(vec![])` when it is empty. `HashSet<T>` and
+//! `BTreeSet<T>` fields are read the same way, duplicated items being dropped, or rejected with an
+//! error naming the item when the field has the `deny_duplicates` attribute. Arrays `[T; N]` are
+//! read the same way too, a list holding another number of items than `N` being an error giving
+//! both counts.
+//!
+//! Tuples of 2 or 3 elements, such as `(u32, u32)`, are read from their components separated by
+//! `,` (or the `tuple_separator` attribute), trimmed and each parsed with its own `FromStr`
+//! implementation. A value holding another number of components is an error, and a component
+//! failing to parse is a parsing failure naming its 1-based position.
+//!
+//! `HashMap<K, V>` and `BTreeMap<K, V>` fields are read from `key=value` entries separated the
+//! same way (`LABELS=env=prod,team=core`), keys and values being parsed with `FromStr` and
+//! trimmed. Entries are split on their first `=` (or the `kv_separator` attribute), so values may
+//! contain it. An entry without it, or whose key or value fails to parse, is a parsing failure
+//! showing the raw entry and its 1-based position, and an empty key or a key given twice is an
+//! error.
+//!
+//! `OsString` fields are read as-is with [`EnvSource::get_os`], without parsing, so values which
+//! are not UTF-8 are kept rather than reported as missing. `PathBuf` fields are read the same way,
+//! unless an attribute such as `alias`, `credential` or `from` needs the value as a `String`.
+//!
+//! With the `secrecy` feature, `SecretString` and `SecretBox<T>` fields of
+//! [`secrecy`](https://crates.io/crates/secrecy) (re-exported as `derive_from_env::secrecy`) are
+//! parsed as `String` and `T`, then wrapped. Their values are hidden like those of `secret` fields,
+//! parsing failures included, and they can't have a `default`.
+//!
+//! With the `zeroize` feature, `Zeroizing<String>` and `Zeroizing<Vec<u8>>` fields of
+//! [`zeroize`](https://crates.io/crates/zeroize) (re-exported as `derive_from_env::zeroize`)
+//! marked `#[from_env(zeroize)]` are wiped when dropped. The raw value of the variable or
+//! `credential` file, and the buffers of `base64` and `hex` decoding, are wiped once parsed too.
+//!
+//! `Box<T>`, `Rc<T>` and `Arc<T>` of a derived struct are loaded as the struct itself, both as
+//! nested fields and with the methods of the [`FromEnv`](trait@FromEnv) trait, e.g.
+//! `Arc::<Config>::from_env()`. Pointers to values are parsed as the value they wrap, with the
+//! attributes of the field, `Option<Arc<String>>` and `Arc<str>` included.
+//!
+//! `Range<T>` and `RangeInclusive<T>` fields are read from `start..end` (exclusive) or
+//! `start..=end` (inclusive), or from `start-end`, exclusive or inclusive as the field type. The
+//! `-` separating the bounds is the first one after the start of the value, so negative bounds
+//! stay readable (`-5--1`). A bound which does not parse, or a start greater than the end, is an
+//! error.
+//!
+//! With the `duration` feature, `std::time::Duration` fields (and their defaults) are read from
+//! integers followed by their unit, `d`, `h`, `m`, `s`, `ms`, `us` or `ns`, possibly chained:
+//! `30s`, `5m`, `1h30m`, `250ms`, or `0`. Parsing failures recall this syntax.
+//!
+//! ## Enum values
+//! `#[derive(FromEnvValue)]` on an enum with unit variants implements `FromStr`, matching variant
+//! names case-insensitively, and `Display`. Variants accept `#[from_env(rename = "...")]` to change
+//! their name and `#[from_env(alias = "...")]` (repeatable) for additional accepted values. Such
+//! enums can then be used as any other field:
+//!
+//! ```rust
+//! use derive_from_env::{FromEnv, FromEnvValue};
+//!
+//! #[derive(Debug, PartialEq, FromEnvValue)]
+//! enum LogLevel {
+//!     Debug,
+//!     #[from_env(alias = "information")]
+//!     Info,
+//!     #[from_env(rename = "warn", alias = "warning")]
+//!     Warning,
+//! }
+//!
+//! #[derive(FromEnv)]
+//! struct Config {
+//!     log_level: LogLevel,
+//! }
+//!
+//! std::env::set_var("LOG_LEVEL", "WARNING");
+//! assert_eq!(Config::from_env().unwrap().log_level, LogLevel::Warning);
+//! assert_eq!(LogLevel::Warning.to_string(), "warn");
+//! assert_eq!(
+//!     "trace".parse::<LogLevel>().unwrap_err().to_string(),
+//!     "invalid value \"trace\", expected one of: Debug, Info, warn"
+//! );
+//! ```
+//!
+//! `#[derive(FromEnv)]` on such an enum generates the same implementations, so that it can be
+//! loaded on its own: it is read from the upper-snake-cased enum name (`LOG_LEVEL`), or from the
+//! variable given by `#[from_env(var = "...")]` on the enum, and from the prefix itself with
+//! `from_env_with_prefix`. Derive either `FromEnv` or `FromEnvValue`, not both.
+//!
+//! ## Tagged enums
+//! `#[derive(FromEnv)]` on an enum with struct variants requires `#[from_env(tag = "...")]`,
+//! naming the variable selecting the variant: it is matched case-insensitively against the
+//! variant names, which accept `rename` and `alias` as above. The fields of the selected variant
+//! are then loaded with the same prefix, as those of a struct would be, while the variables of the
+//! other variants are not read. Unit variants read nothing but the tag.
+//!
+//! ```rust
+//! use std::path::PathBuf;
+//!
+//! use derive_from_env::FromEnv;
+//!
+//! #[derive(Debug, PartialEq, FromEnv)]
+//! #[from_env(tag = "TYPE")]
+//! enum Storage {
+//!     S3 { bucket: String },
+//!     Local { path: PathBuf },
+//! }
+//!
+//! #[derive(FromEnv)]
+//! struct Config {
+//!     storage: Storage,
+//! }
+//!
+//! std::env::set_var("STORAGE_TYPE", "s3");
+//! std::env::set_var("STORAGE_BUCKET", "assets");
+//! assert_eq!(
+//!     Config::from_env().unwrap().storage,
+//!     Storage::S3 { bucket: "assets".into() }
+//! );
+//! ```
+//!
+//! In `env_vars()`, the fields of the variants are listed under the name of the variant
+//! (`storage.S3.bucket`), none of them required.
+//!
+//! ## Struct attributes
+//! - `prefix = "..."`, `collapse_prefix`: see [Struct prefix](#struct-prefix).
+//! - `compat = "envy"`: see [Migrating from envy](#migrating-from-envy).
+//! - `all_defaultable`: fields whose variable is not set, and without a `default` attribute, take
+//!   the `Default` value of their type (which must then implement `Default`). Values failing to
+//!   parse are still errors. Nested structs are loaded with their own rules, so they need the
+//!   attribute as well to fall back to defaults.
+//! - `transparent`: on a struct with a single field (named or tuple), reads the field from the
+//!   prefix itself rather than from a name derived from the field, so that a wrapper such as
+//!   `struct Port(u16)` held by an `http_port` field reads `HTTP_PORT`, like a `u16` would.
+//!   Without prefix, the variable is named after the struct (`PORT`). The attribute is an error
+//!   on structs with several fields, and implied on newtype tuple structs.
+//! - `diff`: generates `diff(&other)` (and `diff_with_prefix`) returning a [`FieldDiff`] for each
+//!   field holding different values in two configurations, nested structs included (which need
+//!   the attribute as well). Fields are compared with `PartialEq` and their values rendered with
+//!   `Debug`, except enums deriving `FromEnvValue` which are rendered with `Display`. The values
+//!   of fields with the `secret` attribute are replaced by `<redacted>`.
+//! - `merge`: generates `merge(other, policy)` combining two configurations field by field
+//!   according to a [`MergePolicy`], e.g. to let the variables explicitly set in the process
+//!   environment override those of a file with [`MergePolicy::PreferNonDefault`]. Nested structs
+//!   need the attribute as well, and fields with a `default` attribute must implement `PartialEq`.
+//! - `check_defaults`: emits a `#[cfg(test)]` module with a test per field with a `default`
+//!   attribute, failing with the field and the parsing error when the default is invalid, so that
+//!   `cargo test` catches a `default = "warnn"` on a custom type. The same checks are available
+//!   through the `check_defaults()` method generated with the attribute. The module imports the types of the
+//!   fields from the parent module, so the struct can't be declared inside a function.
+//! - `cli_args`: generates `to_cli_args(style)` rendering the configuration as command-line flags
+//!   named after the kebab-cased field paths (`--database-host=db1`), e.g. to forward it to a
+//!   child process. `None` options are left out, booleans become `--flag` or `--no-flag`, and the
+//!   values of `secret` fields are `<redacted>` unless [`ArgStyle::skip_secrets`] leaves them out.
+//!   Nested structs need the attribute as well.
+//! - `separator = ";"`: the separator of the items of the `Vec` and map fields of the struct
+//!   without a `separator` attribute.
+//! - `kv_separator = ":"`: the separator of the keys and values of the map fields of the struct
+//!   without a `kv_separator` attribute.
+//! - `lenient_bool`: applies `lenient_bool` to every `bool` field of the struct.
+//! - `non_empty`, `non_empty = "trim"`: applies `non_empty` to every field of the struct read as a
+//!   string from a single variable.
+//! - `trim`: applies `trim` to every field of the struct read as a string from a single variable,
+//!   except the `raw` ones.
+//! - `file_suffix = "_FILE"`: applies `file` to every field of the struct read from a single
+//!   variable, the file being named by `<NAME>{file_suffix}`, and to the variables of its nested
+//!   structs, unless the field has `no_file`.
+//! - `file_conflict = "..."`: what `file` fields do when both the variable and its file are set:
+//!   `"prefer_var"` (the default) or `"prefer_file"` reads one of them, `"error"` fails with
+//!   [`FileConflict`](FromEnvError::FileConflict), e.g. to catch leftovers during a migration.
+//! - `defaults_from = "Type"`: generates a trait named after the struct (`ConfigDefaults`), with a
+//!   method per field parsed from a single variable (`default_port() -> Option<u16>`, giving `None`
+//!   unless overridden), and falls back to the implementation of `Type` (often `Self`) when a
+//!   variable is not set, so that defaults can be computed, shared between structs and tested as
+//!   plain Rust code. The `default` attribute of a field takes precedence.
+//! - `crate = "path::to::reexport"`: path of this crate in the generated code, `::derive_from_env`
+//!   by default, for derives used through a crate re-exporting it (or when the dependency is
+//!   renamed). Also accepted on `#[derive(FromEnvValue)]` enums.
+//!
+//! ## Struct prefix
+//! `#[from_env(prefix = "DB_")]` on a struct prepends `DB_` to the names of its fields: `host` is
+//! read from `DB_HOST`, or from `DATABASE_DB_HOST` when the struct is a `database` field of another
+//! struct.
+//!
+//! Adding `collapse_prefix` (`#[from_env(prefix = "DB_", collapse_prefix)]`) avoids such stuttering
+//! names when the incoming prefix already carries the struct prefix: the prefixes are split into
+//! `_`-separated segments, and the longest run of segments ending the incoming prefix and starting
+//! the struct prefix is written only once, as in the incoming prefix. Segments are compared as a
+//! whole, and match when they are equal or when one abbreviates the other, either way round: the
+//! shorter one starts with the same letter and has its other letters in order in the longer one
+//! (`DB` for `DATABASE`, `CFG` for `CONFIG`).
+//!
+//! | incoming prefix | struct prefix  | `host` is read from      |
+//! |-----------------|----------------|--------------------------|
+//! | `DB`            | `DB_`          | `DB_HOST`                |
+//! | `MAIN_DB`       | `DB_`          | `MAIN_DB_HOST`           |
+//! | `APP_DB`        | `DB_PRIMARY_`  | `APP_DB_PRIMARY_HOST`    |
+//! | `XDB`           | `DB_`          | `XDB_DB_HOST`            |
+//! | `DATABASE`      | `DB_`          | `DATABASE_HOST`          |
+//! | `DB`            | `DATABASE_`    | `DB_HOST`                |
+//! | `CACHE`         | `DB_`          | `CACHE_DB_HOST`          |
+//!
+//! The fields of tuple structs with several fields are named after their position:
+//! `struct Bounds(u32, u32)` held by a `range` field reads `RANGE_0` and `RANGE_1`. Their
+//! attributes, such as `default` or `var`, are given on each element.
+//! Fields of generic structs whose type is a type parameter (`max: T`, or `step: Option<T>`) are
+//! parsed with `FromStr`, the bound being added to the generated implementations, and parsing
+//! failures name the type the parameter is instantiated with (`u16`). The bounds needed by `diff`,
+//! `merge` or `cli_args` (`PartialEq`, `Debug`, `Display`) are left to the struct, whose own bounds
+//! and where-clause (`T::Err: Display`, const parameters...) are kept. Type parameters
+//! of `flatten` (or `no_prefix`) fields are loaded as nested structs instead, bounded by
+//! [`FromEnv`] and by the traits generated by `diff`, `merge` and `cli_args` when the struct has
+//! these attributes, so that `Service<B>` can be instantiated with a backend per binary.
+//! `PhantomData` fields (such as `_marker: PhantomData<T>`), and `Option`s of them, read no
+//! variable: they are set to `PhantomData` (`Some(PhantomData)`) without any attribute, and are
+//! left out of `env_vars()` and the command-line arguments.
+//!
+//! Fields guarded by `#[cfg(...)]` attributes are removed before the derive runs when their
+//! condition does not hold, so that their variables are only read, and listed, when they exist.
+//!
+//! Unit structs read no variable, so that marker sections such as `struct NoAuth;` can be loaded
+//! by generic code like any other section.
+//!
+//! `from_env_with_exe_prefix()` uses the name of the executable, upper-snake-cased, as the prefix
+//! given to `from_env_with_prefix`, so that a binary installed under several names (through
+//! symlinks) reads a namespace per name: `my-tool` reads `MY_TOOL_DB_HOST`. The name is given by
+//! [`EnvSource::current_exe`] with `from_source_with_exe_prefix`.
+//!
+//! ## Sources
+//! `from_env` reads the process environment. `from_source` (and `from_source_with_prefix`) reads
+//! the variables from any [`EnvSource`] instead:
+//! - [`ProcessEnv`]: the process environment;
+//! - [`DirSource`]: a directory with a file per variable, such as mounted Kubernetes ConfigMaps and
+//!   Secrets;
+//! - [`DotenvSource`]: a `.env` file;
+//! - [`ReaderSource`]: `KEY=VALUE` lines from any reader, such as a pipe;
+//! - [`Layered`]: several sources, the first one holding a variable giving its value;
+//! - [`SpySource`]: another source, recording the variables looked up;
+//! - [`SandboxSource`]: another source, restricted to an allowlist of variables.
+//!
+//! ```rust
+//! use derive_from_env::{DirSource, FromEnv, Layered, ProcessEnv};
+//!
+//! #[derive(FromEnv)]
+//! struct Config {
+//!     db_host: String,
+//! }
+//!
+//! let dir = std::env::temp_dir().join("derive_from_env_sources_doc");
+//! std::fs::create_dir_all(&dir).unwrap();
+//! std::fs::write(dir.join("DB_HOST"), "db.internal\n").unwrap();
+//! let source = Layered::new().layer(ProcessEnv).layer(DirSource::new(&dir));
+//! assert_eq!(Config::from_source(&source).unwrap().db_host, "db.internal");
+//! ```
+//!
+//! Sources are adapted with [`EnvSource::map_keys`] (renaming the variables looked up),
+//! [`EnvSource::with_prefix`] and [`EnvSource::strip_prefix`] (adding or removing a prefix) and
+//! [`EnvSource::or`] (falling back to another source):
+//!
+//! ```rust
+//! use derive_from_env::{DirSource, EnvSource, FromEnv, ProcessEnv};
+//!
+//! #[derive(FromEnv)]
+//! struct Config {
+//!     db_host: String,
+//! }
+//!
+//! let dir = std::env::temp_dir().join("derive_from_env_adapters_doc");
+//! std::fs::create_dir_all(&dir).unwrap();
+//! std::fs::write(dir.join("db.host"), "db.internal\n").unwrap();
+//! // `STAGING_DB_HOST`, or else the file `db.host`.
+//! let source = ProcessEnv
+//!     .with_prefix("STAGING_")
+//!     .or(DirSource::new(&dir).map_keys(|key| key.to_lowercase().replace('_', ".")));
+//! # std::env::remove_var("STAGING_DB_HOST");
+//! assert_eq!(Config::from_source(&source).unwrap().db_host, "db.internal");
+//! ```
+//!
+//! Errors about a variable carry its [`Origin`]: the file and line of a `.env` entry, the file of a
+//! [`DirSource`], the `default` attribute... Parsing failures always name it, e.g.
+//! `failed to parse PORT="http" as u16 (value from .env.local:12)` or
+//! `(value from process environment)`, and missing variables name where they were looked up
+//! unless it is the process environment.
+//!
+//! ## Inspecting the environment
+//! Derived structs describe the variables they read with `env_vars()` (and
+//! `env_vars_with_prefix`), returning an [`EnvVarSpec`] per variable, nested structs included.
+//! The other names of a field (those of `var`, `alias`, `alias_var`, `deprecated_alias` and
+//! `default_env`) and the `<NAME>_FILE` variables of `file` and `file_suffix` follow its variable
+//! as optional ones, so that [`SandboxSource::from_specs`] allows them.
+//!
+//! `lint_env()` (and `lint_env_with_prefix`) looks these variables up and returns an
+//! [`EnvWarning`] for each likely mistake, without failing: values wrapped in quotes or with
+//! surrounding whitespace, empty values on fields with a default (the empty value is parsed instead
+//! of the default), and unset variables set under a name differing only by case.
+//! [`Layered::detect_shadowing`] similarly warns about variables set with different values in
+//! several layers.
+//!
+//! `explain("database.host")` returns the [`ResolutionPlan`] of a field: every location
+//! consulted, in order (credential file, variable, `file`, aliases, `default_env`, `default`
+//! attribute), with its current value. Its `Display` implementation renders a numbered list
+//! marking the location giving the value, e.g. for a `--explain` flag:
+//!
+//! ```text
+//! database.host:
+//! 1. variable DATABASE_HOST in process environment: not set
+//! 2. alias DB_HOST in process environment: "db1" <- used
+//! 3. default attribute: "localhost"
+//! ```
+//!
+//! ```rust
+//! use derive_from_env::{EnvWarningKind, FromEnv};
+//!
+//! #[derive(FromEnv)]
+//! struct Config {
+//!     #[from_env(default = "8080")]
+//!     http_port: u16,
+//! }
+//!
+//! std::env::set_var("HTTP_PORT", "");
+//! let warnings = Config::lint_env();
+//! assert_eq!(warnings[0].var_name, "HTTP_PORT");
+//! assert_eq!(warnings[0].kind, EnvWarningKind::EmptyWithDefault);
+//! ```
+//!
+//! These descriptions are rendered by [`generate::help_table`] and [`generate::dotenv_template`],
+//! which [`cli::handle_env_flags`] prints in response to the `--env-help` and `--env-template`
+//! command-line flags.
+//!
+//! ### Build-time manifest
+//! When `DERIVE_FROM_ENV_EMIT_MANIFEST` is set while compiling, the derive writes a
+//! `<crate>.<Struct>.<hash>.jsonl` file per derived struct, in the directory named by the variable
+//! (or in `$OUT_DIR/derive_from_env_manifest` when set to `1`, which fails to compile in crates
+//! without a build script). The hash of the file content tells apart structs of the same name in
+//! different modules; clear the directory before building to drop the manifests of edited structs.
+//! Each line is a JSON object describing a field, either read from a variable:
+//!
+//! ```json
+//! {"struct":"Config","field":"port","var":"PORT","type":"u16","required":false,"optional":false,"default":"8080"}
+//! ```
+//!
+//! or loaded as a nested struct, given a prefix (`null` with `no_prefix`):
+//!
+//! ```json
+//! {"struct":"Config","field":"database","nested":"Database","prefix":"DATABASE"}
+//! ```
+//!
+//! Variable names are the ones read by `from_env()`. Writing the manifest is best-effort: errors
+//! are ignored and never fail the build. As with any environment read by a procedural macro, cargo
+//! does not track the variable, so a clean build may be needed for the manifest to be written.
+//!
+//! ## Deserialize-only types
+//! With the `serde` feature, types only implementing `serde::Deserialize` can be read from the
+//! environment with [`Deserializer`], which follows the same naming rules as the derive. Inside a
+//! derived struct, mark such a field with `#[from_env(flatten, deserialize)]`:
+//!
+//! ```rust
+//! # #[cfg(feature = "serde")] {
+//! use derive_from_env::FromEnv;
+//!
+//! #[derive(serde::Deserialize)]
+//! struct Pool {
+//!     size: u32,
+//! }
+//!
+//! #[derive(FromEnv)]
+//! struct Config {
+//!     #[from_env(flatten, deserialize)]
+//!     pool: Pool,
+//! }
+//!
+//! std::env::set_var("POOL_SIZE", "8");
+//! assert_eq!(Config::from_env().unwrap().pool.size, 8);
+//! # }
+//! ```
+//!
+//! ## Migrating from envy
+//! Structs previously deserialized with [envy](https://crates.io/crates/envy) can keep reading the
+//! exact same variables with the struct attribute `#[from_env(compat = "envy")]`. The following
+//! behaviours of `envy::prefixed(prefix).from_env()` are emulated:
+//! - prefix joining: the prefix given to `from_env_with_prefix` is prepended as-is, no `_` separator
+//!   is inserted (`from_env_with_prefix("APP_")` reads `APP_PORT`, like `envy::prefixed("APP_")`);
+//! - case: the prefix is matched case-sensitively, the rest of the name is compared to the field
+//!   name ignoring case (`APP_port` and `APP_PORT` both fill `port`). Where envy would pick one
+//!   of several matching variables arbitrarily, the upper-case one is used, and loading fails
+//!   when there is none (`APP_port` and `APP_Port`);
+//! - nested structs behave like `#[serde(flatten)]`: they are loaded with the parent prefix, without
+//!   inserting their field name (they should be `compat = "envy"` themselves to be read the same way);
+//! - `Option` fields: an unset variable gives `None`, an empty one is parsed as `Some("")` (so
+//!   `Option<String>` gives `Some(String::new())` and `Option<u16>` fails to parse).
+//!
+//! `var = "..."` keeps naming an exact variable.
+//!
+//! ```rust
+//! use derive_from_env::FromEnv;
+//!
+//! #[derive(FromEnv)]
+//! #[from_env(compat = "envy")]
+//! struct Config {
+//!     port: u16,
+//! }
+//!
+//! std::env::set_var("APP_PORT", "8080");
+//! assert_eq!(Config::from_env_with_prefix("APP_").unwrap().port, 8080);
+//! ```
 
+pub mod _inner_helpers;
 pub mod _inner_trait;
-pub use derive_from_env_proc::FromEnv;
+pub mod cli;
+mod cli_args;
+#[cfg(feature = "serde")]
+mod de;
+mod deprecation;
+mod diff;
+mod dotenv;
+mod explain;
+pub mod generate;
+mod lazy;
+mod lint;
+mod merge;
+mod report;
+mod source;
+mod spec;
+pub mod test_util;
+/// The trait implemented by `#[derive(FromEnv)]`, in scope with the derive macro, giving access to
+/// its methods on generic types such as `Arc<Config>`.
+pub use _inner_trait::FromEnv;
+pub use cli_args::ArgStyle;
+#[cfg(feature = "serde")]
+pub use de::Deserializer;
+pub use deprecation::{set_deprecation_hook, DeprecationNotice};
+pub use derive_from_env_proc::{from_env_test, FromEnv, FromEnvValue};
+pub use diff::FieldDiff;
+pub use dotenv::{DotenvSource, ReaderSource};
+pub use explain::{Location, ResolutionPlan, ResolutionStep};
+pub use lazy::Lazy;
+pub use lint::{lint_env, EnvWarning, EnvWarningKind};
+pub use merge::MergePolicy;
+pub use report::{MainResult, Report, EX_CONFIG};
+/// The version of `secrecy` whose types can be used as fields.
+#[cfg(feature = "secrecy")]
+pub use secrecy;
+pub use source::{
+    DirSource, EnvSource, Layered, Lookup, MapKeys, Or, Origin, ProcessEnv, SandboxSource,
+    SpySource, StripPrefix, WithPrefix,
+};
+pub use spec::EnvVarSpec;
+/// The version of `zeroize` whose `Zeroizing` wrapper is used by `#[from_env(zeroize)]` fields.
+#[cfg(feature = "zeroize")]
+pub use zeroize;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum FromEnvError {
     MissingEnvVar {
         var_name: String,
+        /// Where the variable was looked for.
+        origin: Origin,
+        /// Variables looked up in turn after `var_name`: the other names of `var`, `alias`,
+        /// `alias_var`, `deprecated_alias` and `default_env`.
+        fallbacks: Vec<String>,
     },
     ParsingFailure {
         var_name: String,
         expected_type: String,
         str_value: String,
+        /// Where the value comes from.
+        origin: Origin,
+    },
+    /// Invalid value described by a message, e.g. from a `serde::Deserialize` implementation (see
+    /// `Deserializer`) or an attribute such as `decimal_comma` or `percent`.
+    Custom {
+        var_name: Option<String>,
+        message: String,
     },
+    /// Failure of an [`EnvSource`] to look a variable up, e.g. an unreadable file.
+    SourceFailure {
+        var_name: Option<String>,
+        message: String,
+    },
+    /// Value rejected by the `validate` function or the `range` of its field, after defaults are
+    /// applied.
+    ValidationFailure { var_name: String, message: String },
+    /// Failure to read the file named by the `<NAME>_FILE` variable of a `file` field.
+    FileReadFailure {
+        /// The `<NAME>_FILE` variable.
+        var_name: String,
+        path: std::path::PathBuf,
+        kind: std::io::ErrorKind,
+    },
+    /// Both the variable of a `file` field and its `<NAME>_FILE` variable are set, under
+    /// `#[from_env(file_conflict = "error")]`.
+    FileConflict { var_name: String, file_var: String },
+}
+
+impl std::fmt::Display for FromEnvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FromEnvError::MissingEnvVar {
+                var_name,
+                origin,
+                fallbacks,
+            } => {
+                write!(f, "missing environment variable {}", var_name)?;
+                if !fallbacks.is_empty() {
+                    write!(f, " (also tried {})", fallbacks.join(", "))?;
+                }
+                match origin {
+                    Origin::ProcessEnv => Ok(()),
+                    origin => write!(f, " (looked up in {})", origin),
+                }
+            }
+            FromEnvError::ParsingFailure {
+                var_name,
+                expected_type,
+                str_value,
+                origin,
+            } => {
+                write!(
+                    f,
+                    "failed to parse {}={:?} as {} (value from {})",
+                    var_name, str_value, expected_type, origin
+                )
+            }
+            FromEnvError::Custom {
+                var_name: Some(var_name),
+                message,
+            } => write!(f, "{}: {}", var_name, message),
+            FromEnvError::Custom {
+                var_name: None,
+                message,
+            } => write!(f, "{}", message),
+            FromEnvError::SourceFailure {
+                var_name: Some(var_name),
+                message,
+            } => write!(f, "failed to look {} up: {}", var_name, message),
+            FromEnvError::SourceFailure {
+                var_name: None,
+                message,
+            } => write!(f, "{}", message),
+            FromEnvError::ValidationFailure { var_name, message } => {
+                write!(f, "invalid value of {}: {}", var_name, message)
+            }
+            FromEnvError::FileReadFailure {
+                var_name,
+                path,
+                kind,
+            } => write!(
+                f,
+                "failed to read the file {} named by {}: {}",
+                path.display(),
+                var_name,
+                kind
+            ),
+            FromEnvError::FileConflict { var_name, file_var } => write!(
+                f,
+                "both {} and {} are set, set only one of them",
+                var_name, file_var
+            ),
+        }
+    }
+}
+
+impl FromEnvError {
+    /// The origin of the variable of a [`MissingEnvVar`](FromEnvError::MissingEnvVar) or
+    /// [`ParsingFailure`](FromEnvError::ParsingFailure) error.
+    pub fn origin(&self) -> Option<&Origin> {
+        match self {
+            FromEnvError::MissingEnvVar { origin, .. }
+            | FromEnvError::ParsingFailure { origin, .. } => Some(origin),
+            FromEnvError::Custom { .. }
+            | FromEnvError::SourceFailure { .. }
+            | FromEnvError::ValidationFailure { .. }
+            | FromEnvError::FileReadFailure { .. }
+            | FromEnvError::FileConflict { .. } => None,
+        }
+    }
 }
+
+impl std::error::Error for FromEnvError {}
+
+/// Error returned by the `FromStr` implementation generated by [`FromEnvValue`], listing the
+/// accepted values.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseValueError {
+    pub value: String,
+    pub expected: &'static [&'static str],
+}
+
+impl std::fmt::Display for ParseValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid value {:?}, expected one of: {}",
+            self.value,
+            self.expected.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ParseValueError {}
@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+
+use crate::{EnvVarSpec, Origin};
+
+/// Suspicious environment value reported by `lint_env()`. Such values are still loaded, the
+/// warning only points at a likely mistake.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EnvWarning {
+    pub var_name: String,
+    pub kind: EnvWarningKind,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum EnvWarningKind {
+    /// The value is wrapped in `"` or `'`, which are kept as part of the value.
+    QuotedValue,
+    /// The value starts or ends with whitespace, which is kept as part of the value.
+    SurroundingWhitespace,
+    /// The value is empty on a field with a default: the empty value is parsed instead of the
+    /// default.
+    EmptyWithDefault,
+    /// The variable is not set, but `found` differs from its name only by case.
+    CaseMismatch { found: String },
+    /// The variable is set with different values in several layers of a
+    /// [`Layered`](crate::Layered) source: the value of `winner` is used, those of `shadowed`
+    /// are ignored.
+    Shadowed {
+        winner: Origin,
+        shadowed: Vec<Origin>,
+    },
+    /// The value of a `lenient` field failed to parse, the field taking its default instead.
+    LenientFallback,
+}
+
+impl std::fmt::Display for EnvWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+thread_local! {
+    /// Warnings recorded by the load in progress on this thread, `None` when nobody collects them.
+    static COLLECTED: RefCell<Option<Vec<EnvWarning>>> = const { RefCell::new(None) };
+}
+
+/// Runs `load`, returning the warnings it recorded with [`record_warning`].
+pub(crate) fn collect_warnings<R>(load: impl FnOnce() -> R) -> (R, Vec<EnvWarning>) {
+    let outer = COLLECTED.with(|collected| collected.replace(Some(Vec::new())));
+    let result = load();
+    let warnings = COLLECTED.with(|collected| collected.replace(outer));
+    (result, warnings.unwrap_or_default())
+}
+
+/// Records a warning of the load in progress, dropped unless it is collected.
+pub(crate) fn record_warning(warning: EnvWarning) {
+    COLLECTED.with(|collected| {
+        if let Some(warnings) = collected.borrow_mut().as_mut() {
+            warnings.push(warning);
+        }
+    });
+}
+
+/// Inspects the variables described by `specs` (see `env_vars()`) in the current environment.
+pub fn lint_env(specs: &[EnvVarSpec]) -> Vec<EnvWarning> {
+    let mut warnings = Vec::new();
+    for spec in specs {
+        let var_name = &spec.name;
+        let mut warn = |kind, message| {
+            warnings.push(EnvWarning {
+                var_name: var_name.clone(),
+                kind,
+                message,
+            })
+        };
+        let Some(value) = std::env::var_os(var_name) else {
+            let found = std::env::vars_os()
+                .filter_map(|(key, _)| key.into_string().ok())
+                .find(|key| key.eq_ignore_ascii_case(var_name));
+            if let Some(found) = found {
+                warn(
+                    EnvWarningKind::CaseMismatch {
+                        found: found.clone(),
+                    },
+                    format!(
+                        "{} is not set, but {} is: names are case-sensitive",
+                        var_name, found
+                    ),
+                );
+            }
+            continue;
+        };
+        // Non UTF-8 values are reported by the loading itself.
+        let Some(value) = value.to_str() else {
+            continue;
+        };
+        if value.is_empty() && spec.has_default() {
+            warn(
+                EnvWarningKind::EmptyWithDefault,
+                format!(
+                    "{} is set to an empty value, which is used instead of the default",
+                    var_name
+                ),
+            );
+        }
+        // Values of `secret` fields are left out of the messages.
+        let shown_value = match spec.secret {
+            true => "<redacted>".to_string(),
+            false => format!("{:?}", value),
+        };
+        if value.trim() != value {
+            warn(
+                EnvWarningKind::SurroundingWhitespace,
+                format!(
+                    "{}={} has surrounding whitespace, kept as part of the value",
+                    var_name, shown_value
+                ),
+            );
+        }
+        let trimmed = value.trim();
+        let quoted = ['"', '\''].iter().any(|&quote| {
+            trimmed.len() >= 2 && trimmed.starts_with(quote) && trimmed.ends_with(quote)
+        });
+        if quoted {
+            warn(
+                EnvWarningKind::QuotedValue,
+                format!(
+                    "{}={} is wrapped in quotes, kept as part of the value",
+                    var_name, shown_value
+                ),
+            );
+        }
+    }
+    warnings
+}
@@ -0,0 +1,13 @@
+/// How the `merge()` method generated with `#[from_env(merge)]` combines two configurations.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MergePolicy {
+    /// Keeps every field of `self`.
+    PreferSelf,
+    /// Takes every field of `other`.
+    PreferOther,
+    /// Takes the fields of `other` holding something else than their default, and keeps those of
+    /// `self` otherwise. The default of a field is the value of its `default` attribute, `None`
+    /// for `Option` fields, or `Default::default()` under `all_defaultable`; fields without
+    /// default are always taken from `other`. Nested structs are merged field by field.
+    PreferNonDefault,
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+use std::process::{ExitCode, Termination};
+
+use crate::{FromEnvError, Origin};
+
+/// Exit code for configuration errors (`EX_CONFIG` in `sysexits.h`).
+pub const EX_CONFIG: u8 = 78;
+
+/// Result type for `main` functions loading their configuration from the environment.
+///
+/// Returning an error from `main` prints the [`Report`] instead of the `Debug` dump of
+/// [`FromEnvError`]. The process then exits with code 1, as for any error returned from `main`;
+/// see [`Report`] to exit with [`EX_CONFIG`] instead.
+pub type MainResult<T = ()> = Result<T, Report>;
+
+/// A [`FromEnvError`] rendered as a multi-line report, meant to be shown to whoever runs the program.
+///
+/// Its `Debug` implementation renders the report, so that it is what gets printed when returned
+/// from `main`. It also implements [`Termination`], which prints the report to stderr and gives the
+/// [`EX_CONFIG`] exit code:
+///
+/// ```rust,no_run
+/// use std::process::{ExitCode, Termination};
+/// use derive_from_env::{FromEnv, MainResult};
+///
+/// #[derive(FromEnv)]
+/// struct Config {
+///     port: u16,
+/// }
+///
+/// fn run() -> MainResult {
+///     let config = Config::from_env()?;
+///     println!("listening on {}", config.port);
+///     Ok(())
+/// }
+///
+/// fn main() -> ExitCode {
+///     match run() {
+///         Ok(()) => ExitCode::SUCCESS,
+///         Err(report) => report.report(),
+///     }
+/// }
+/// ```
+#[derive(PartialEq, Clone)]
+pub struct Report(pub FromEnvError);
+
+impl Report {
+    /// The underlying error.
+    pub fn error(&self) -> &FromEnvError {
+        &self.0
+    }
+}
+
+impl From<FromEnvError> for Report {
+    fn from(error: FromEnvError) -> Self {
+        Report(error)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "failed to load the configuration from the environment")?;
+        match &self.0 {
+            FromEnvError::MissingEnvVar {
+                var_name,
+                origin,
+                fallbacks,
+            } => {
+                writeln!(f, "  missing environment variable")?;
+                write!(f, "    variable: {}", var_name)?;
+                if !fallbacks.is_empty() {
+                    write!(f, "\n    tried:    {}", fallbacks.join(", "))?;
+                }
+                write_origin(f, origin)
+            }
+            FromEnvError::ParsingFailure {
+                var_name,
+                expected_type,
+                str_value,
+                origin,
+            } => {
+                writeln!(f, "  invalid value")?;
+                writeln!(f, "    variable: {}", var_name)?;
+                writeln!(f, "    value:    {:?}", str_value)?;
+                write!(f, "    expected: {}", expected_type)?;
+                write_origin(f, origin)
+            }
+            FromEnvError::Custom { var_name, message } => {
+                writeln!(f, "  invalid value")?;
+                if let Some(var_name) = var_name {
+                    writeln!(f, "    variable: {}", var_name)?;
+                }
+                write!(f, "    error:    {}", message)
+            }
+            FromEnvError::SourceFailure { var_name, message } => {
+                writeln!(f, "  failed to read the source")?;
+                if let Some(var_name) = var_name {
+                    writeln!(f, "    variable: {}", var_name)?;
+                }
+                write!(f, "    error:    {}", message)
+            }
+            FromEnvError::ValidationFailure { var_name, message } => {
+                writeln!(f, "  invalid value")?;
+                writeln!(f, "    variable: {}", var_name)?;
+                write!(f, "    error:    {}", message)
+            }
+            FromEnvError::FileReadFailure {
+                var_name,
+                path,
+                kind,
+            } => {
+                writeln!(f, "  failed to read the file")?;
+                writeln!(f, "    variable: {}", var_name)?;
+                writeln!(f, "    path:     {}", path.display())?;
+                write!(f, "    error:    {}", kind)
+            }
+            FromEnvError::FileConflict { var_name, file_var } => {
+                writeln!(f, "  conflicting variables")?;
+                writeln!(f, "    variable: {}", var_name)?;
+                write!(f, "    file:     {}", file_var)
+            }
+        }
+    }
+}
+
+/// Adds a line with the origin of the variable, unless it is the process environment.
+fn write_origin(f: &mut fmt::Formatter<'_>, origin: &Origin) -> fmt::Result {
+    match origin {
+        Origin::ProcessEnv => Ok(()),
+        origin => write!(f, "\n    origin:   {}", origin),
+    }
+}
+
+impl fmt::Debug for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for Report {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl Termination for Report {
+    fn report(self) -> ExitCode {
+        eprintln!("Error: {}", self);
+        ExitCode::from(EX_CONFIG)
+    }
+}
@@ -0,0 +1,839 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::{EnvWarning, EnvWarningKind, FromEnvError};
+
+/// Where a value comes from, or where a missing variable was looked for.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Origin {
+    ProcessEnv,
+    /// A `.env` file, with the line of the variable when it is set.
+    DotenvFile {
+        path: PathBuf,
+        line: Option<usize>,
+    },
+    /// A file holding the value, e.g. in a [`DirSource`].
+    File(PathBuf),
+    /// A `#[from_env(credential = "...")]` file.
+    Credential(PathBuf),
+    /// Any other source, by name.
+    Remote(String),
+    /// The `default` attribute of the field.
+    Default,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::ProcessEnv => write!(f, "process environment"),
+            Origin::DotenvFile {
+                path,
+                line: Some(line),
+            } => write!(f, "{}:{}", path.display(), line),
+            Origin::DotenvFile { path, line: None } => write!(f, "{}", path.display()),
+            Origin::File(path) => write!(f, "{}", path.display()),
+            Origin::Credential(path) => write!(f, "credential {}", path.display()),
+            Origin::Remote(name) => write!(f, "{}", name),
+            Origin::Default => write!(f, "default attribute"),
+        }
+    }
+}
+
+/// Where the variables are read from by `from_source` and `from_source_with_prefix`, the process
+/// environment ([`ProcessEnv`]) being used by `from_env`.
+pub trait EnvSource {
+    /// Value of the variable `key`, `None` when it is not set.
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError>;
+
+    /// Where the value of `key` comes from, or would be looked for when it is not set. Defaults to
+    /// [`Origin::Remote`] with the name of the source type.
+    fn origin(&self, _key: &str) -> Origin {
+        Origin::Remote(std::any::type_name::<Self>().to_string())
+    }
+
+    /// Value of the variable `key` along with its origin.
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, FromEnvError> {
+        Ok(self.get(key)?.map(|value| (value, self.origin(key))))
+    }
+
+    /// Value of the variable `key` for `path_list` fields, which accept non UTF-8 values when the
+    /// source supports them.
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        Ok(self.get(key)?.map(OsString::from))
+    }
+
+    /// Path of the running executable, whose name gives the prefix of `from_env_with_exe_prefix`.
+    /// Defaults to [`std::env::current_exe`].
+    fn current_exe(&self) -> Result<PathBuf, FromEnvError> {
+        std::env::current_exe().map_err(|e| FromEnvError::SourceFailure {
+            var_name: None,
+            message: format!("failed to determine the path of the executable: {}", e),
+        })
+    }
+
+    /// Owned handle on the source, kept by [`Lazy`](crate::Lazy) fields to load their section on
+    /// first access. `None` unless implemented, the section being loaded right away.
+    fn shared(&self) -> Option<Arc<dyn EnvSource + Send + Sync>> {
+        None
+    }
+
+    /// Names of the variables held by the source, needed when names are not known in advance
+    /// (`compat = "envy"` lookups, maps of the `serde` `Deserializer`). Empty unless implemented.
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        Ok(Vec::new())
+    }
+
+    /// Looks the variable `key` up as `f(key)`, e.g. to read `APP_DB_HOST` from a file named
+    /// `db.host`. The keys of the source are not listed, the mapping being one-way.
+    fn map_keys<F: Fn(&str) -> String>(self, f: F) -> MapKeys<Self, F>
+    where
+        Self: Sized,
+    {
+        MapKeys { source: self, f }
+    }
+
+    /// Looks the variable `key` up as `{prefix}{key}`, e.g. `STAGING_DB_HOST` for `DB_HOST`.
+    fn with_prefix(self, prefix: impl Into<String>) -> WithPrefix<Self>
+    where
+        Self: Sized,
+    {
+        WithPrefix {
+            source: self,
+            prefix: prefix.into(),
+        }
+    }
+
+    /// Looks the variable `{prefix}{key}` up as `key`, e.g. `APP_DB_HOST` as `DB_HOST`. Variables
+    /// without the prefix are not set.
+    fn strip_prefix(self, prefix: impl Into<String>) -> StripPrefix<Self>
+    where
+        Self: Sized,
+    {
+        StripPrefix {
+            source: self,
+            prefix: prefix.into(),
+        }
+    }
+
+    /// Falls back to `other` for the variables which are not set in this source, like a
+    /// two-layer [`Layered`] without boxing.
+    fn or<B: EnvSource>(self, other: B) -> Or<Self, B>
+    where
+        Self: Sized,
+    {
+        Or {
+            first: self,
+            second: other,
+        }
+    }
+}
+
+impl<S: EnvSource + ?Sized> EnvSource for &S {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        (**self).get(key)
+    }
+    fn origin(&self, key: &str) -> Origin {
+        (**self).origin(key)
+    }
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, FromEnvError> {
+        (**self).lookup(key)
+    }
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        (**self).get_os(key)
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        (**self).keys()
+    }
+    fn current_exe(&self) -> Result<PathBuf, FromEnvError> {
+        (**self).current_exe()
+    }
+    fn shared(&self) -> Option<Arc<dyn EnvSource + Send + Sync>> {
+        (**self).shared()
+    }
+}
+
+impl<S: EnvSource + ?Sized> EnvSource for Box<S> {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        (**self).get(key)
+    }
+    fn origin(&self, key: &str) -> Origin {
+        (**self).origin(key)
+    }
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, FromEnvError> {
+        (**self).lookup(key)
+    }
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        (**self).get_os(key)
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        (**self).keys()
+    }
+    fn current_exe(&self) -> Result<PathBuf, FromEnvError> {
+        (**self).current_exe()
+    }
+    fn shared(&self) -> Option<Arc<dyn EnvSource + Send + Sync>> {
+        (**self).shared()
+    }
+}
+
+/// Shares the source with [`Lazy`](crate::Lazy) fields, e.g. `Arc<SpySource<...>>` to inspect the
+/// lookups of sections loaded later on.
+impl<S: EnvSource + Send + Sync + 'static> EnvSource for Arc<S> {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        (**self).get(key)
+    }
+    fn origin(&self, key: &str) -> Origin {
+        (**self).origin(key)
+    }
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, FromEnvError> {
+        (**self).lookup(key)
+    }
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        (**self).get_os(key)
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        (**self).keys()
+    }
+    fn current_exe(&self) -> Result<PathBuf, FromEnvError> {
+        (**self).current_exe()
+    }
+    fn shared(&self) -> Option<Arc<dyn EnvSource + Send + Sync>> {
+        Some(self.clone())
+    }
+}
+
+/// The environment of the process. Values which are not valid UTF-8 are treated as unset, except
+/// for `path_list` fields.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessEnv;
+
+impl EnvSource for ProcessEnv {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        Ok(std::env::var(key).ok())
+    }
+    fn origin(&self, _key: &str) -> Origin {
+        Origin::ProcessEnv
+    }
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        Ok(std::env::var_os(key))
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        Ok(std::env::vars_os()
+            .filter_map(|(key, _)| key.into_string().ok())
+            .collect())
+    }
+    fn shared(&self) -> Option<Arc<dyn EnvSource + Send + Sync>> {
+        Some(Arc::new(ProcessEnv))
+    }
+}
+
+/// Sources stacked on top of each other: a variable is read from the first layer where it is set.
+///
+/// ```rust
+/// use derive_from_env::{DirSource, FromEnv, Layered, ProcessEnv};
+///
+/// #[derive(FromEnv)]
+/// struct Config {
+///     port: u16,
+/// }
+///
+/// // The process environment overrides the files of the mounted directory.
+/// let source = Layered::new()
+///     .layer(ProcessEnv)
+///     .layer(DirSource::new("/etc/app/config"));
+/// # std::env::set_var("PORT", "8080");
+/// let config = Config::from_source(&source).unwrap();
+/// # assert_eq!(config.port, 8080);
+/// ```
+#[derive(Default)]
+pub struct Layered {
+    layers: Vec<Box<dyn EnvSource>>,
+}
+
+impl Layered {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a layer, below the previous ones.
+    pub fn layer(mut self, source: impl EnvSource + 'static) -> Self {
+        self.layers.push(Box::new(source));
+        self
+    }
+
+    /// Warns about each of `keys` set in several layers with different values, naming the layer
+    /// whose value is used and the ones it shadows. Values are not included in the warnings, as
+    /// they may be secrets. Layers holding the same value as the used one are not reported.
+    ///
+    /// The keys are usually the names of the variables of a struct:
+    /// `layered.detect_shadowing(Config::env_vars().iter().map(|spec| &spec.name))`.
+    pub fn detect_shadowing(
+        &self,
+        keys: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<Vec<EnvWarning>, FromEnvError> {
+        let mut warnings = Vec::new();
+        for key in keys {
+            let key = key.as_ref();
+            let mut winner = None;
+            let mut shadowed = Vec::new();
+            for layer in &self.layers {
+                let Some((value, origin)) = layer.lookup(key)? else {
+                    continue;
+                };
+                match &winner {
+                    None => winner = Some((value, origin)),
+                    Some((winning_value, _)) if *winning_value != value => shadowed.push(origin),
+                    Some(_) => {}
+                }
+            }
+            let Some((_, winner)) = winner.filter(|_| !shadowed.is_empty()) else {
+                continue;
+            };
+            let shadowed_list = shadowed
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            warnings.push(EnvWarning {
+                var_name: key.to_string(),
+                message: format!(
+                    "{} has different values in several layers: {} is used, {} ignored",
+                    key, winner, shadowed_list
+                ),
+                kind: EnvWarningKind::Shadowed { winner, shadowed },
+            });
+        }
+        Ok(warnings)
+    }
+}
+
+impl EnvSource for Layered {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        for layer in &self.layers {
+            if let Some(value) = layer.get(key)? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+    /// The origin given by the layer holding `key`, or by the first layer when none does.
+    fn origin(&self, key: &str) -> Origin {
+        let layer = self
+            .layers
+            .iter()
+            .find(|layer| matches!(layer.get(key), Ok(Some(_))))
+            .or(self.layers.first());
+        match layer {
+            Some(layer) => layer.origin(key),
+            None => Origin::Remote("empty layered source".to_string()),
+        }
+    }
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, FromEnvError> {
+        for layer in &self.layers {
+            if let Some(value) = layer.lookup(key)? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        for layer in &self.layers {
+            if let Some(value) = layer.get_os(key)? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        let mut keys = Vec::new();
+        for layer in &self.layers {
+            for key in layer.keys()? {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// A directory holding a file per variable, named after the variable and containing its value,
+/// such as Kubernetes ConfigMaps and Secrets mounted as volumes.
+///
+/// The variable `APP_DB_HOST` is read from the file `APP_DB_HOST`, or else from a file with the
+/// same name in a different case (`app_db_host`). A single trailing newline is removed from the
+/// content. Hidden files, such as the `..data` links of Kubernetes volumes, are ignored.
+///
+/// The directory is listed on the first lookup and the listing is kept afterwards: create a new
+/// source, or call [`DirSource::refresh`], to pick up files added later.
+pub struct DirSource {
+    path: PathBuf,
+    listing: Mutex<Option<BTreeMap<String, PathBuf>>>,
+}
+
+impl DirSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        DirSource {
+            path: path.into(),
+            listing: Mutex::new(None),
+        }
+    }
+
+    /// The directory read by the source.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Forgets the listing of the directory, which is read again on the next lookup.
+    pub fn refresh(&self) {
+        *self
+            .listing
+            .lock()
+            .unwrap_or_else(|error| error.into_inner()) = None;
+    }
+
+    /// Path of the file holding `key`, if any.
+    fn file_path(&self, key: &str) -> Result<Option<PathBuf>, FromEnvError> {
+        self.with_listing(Some(key), |listing| {
+            listing
+                .get(key)
+                .or_else(|| {
+                    listing
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                        .map(|(_, path)| path)
+                })
+                .cloned()
+        })
+    }
+
+    /// Runs `f` on the files of the directory by name, listing it if needed.
+    fn with_listing<R>(
+        &self,
+        key: Option<&str>,
+        f: impl FnOnce(&BTreeMap<String, PathBuf>) -> R,
+    ) -> Result<R, FromEnvError> {
+        let mut listing = self
+            .listing
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        if listing.is_none() {
+            let source_failure = |error: std::io::Error| FromEnvError::SourceFailure {
+                var_name: key.map(str::to_string),
+                message: format!("failed to list {}: {}", self.path.display(), error),
+            };
+            let mut files = BTreeMap::new();
+            for entry in std::fs::read_dir(&self.path).map_err(source_failure)? {
+                let entry = entry.map_err(source_failure)?;
+                let Ok(name) = entry.file_name().into_string() else {
+                    continue;
+                };
+                if !name.starts_with('.') && entry.path().is_file() {
+                    files.insert(name, entry.path());
+                }
+            }
+            *listing = Some(files);
+        }
+        Ok(f(listing.as_ref().unwrap()))
+    }
+}
+
+impl EnvSource for DirSource {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        let Some(path) = self.file_path(key)? else {
+            return Ok(None);
+        };
+        let content =
+            std::fs::read_to_string(&path).map_err(|error| FromEnvError::SourceFailure {
+                var_name: Some(key.to_string()),
+                message: format!("failed to read {}: {}", path.display(), error),
+            })?;
+        Ok(Some(
+            crate::_inner_helpers::trim_trailing_newline(&content).to_string(),
+        ))
+    }
+    fn origin(&self, key: &str) -> Origin {
+        match self.file_path(key) {
+            Ok(Some(path)) => Origin::File(path),
+            _ => Origin::File(self.path.join(key)),
+        }
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        self.with_listing(None, |listing| listing.keys().cloned().collect())
+    }
+}
+
+/// Source adapter returned by [`EnvSource::map_keys`]. Origins are given by the inner source for
+/// the mapped key, naming the file or variable actually read.
+#[derive(Debug, Clone)]
+pub struct MapKeys<S, F> {
+    source: S,
+    f: F,
+}
+
+impl<S: EnvSource, F: Fn(&str) -> String> EnvSource for MapKeys<S, F> {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        self.source.get(&(self.f)(key))
+    }
+    fn origin(&self, key: &str) -> Origin {
+        self.source.origin(&(self.f)(key))
+    }
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, FromEnvError> {
+        self.source.lookup(&(self.f)(key))
+    }
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        self.source.get_os(&(self.f)(key))
+    }
+    fn current_exe(&self) -> Result<PathBuf, FromEnvError> {
+        self.source.current_exe()
+    }
+}
+
+/// Source adapter returned by [`EnvSource::with_prefix`].
+#[derive(Debug, Clone)]
+pub struct WithPrefix<S> {
+    source: S,
+    prefix: String,
+}
+
+impl<S: EnvSource> WithPrefix<S> {
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+}
+
+impl<S: EnvSource> EnvSource for WithPrefix<S> {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        self.source.get(&self.key(key))
+    }
+    fn origin(&self, key: &str) -> Origin {
+        self.source.origin(&self.key(key))
+    }
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, FromEnvError> {
+        self.source.lookup(&self.key(key))
+    }
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        self.source.get_os(&self.key(key))
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        Ok(self
+            .source
+            .keys()?
+            .into_iter()
+            .filter_map(|key| key.strip_prefix(self.prefix.as_str()).map(str::to_string))
+            .collect())
+    }
+    fn current_exe(&self) -> Result<PathBuf, FromEnvError> {
+        self.source.current_exe()
+    }
+}
+
+/// Source adapter returned by [`EnvSource::strip_prefix`].
+#[derive(Debug, Clone)]
+pub struct StripPrefix<S> {
+    source: S,
+    prefix: String,
+}
+
+impl<S: EnvSource> StripPrefix<S> {
+    fn key<'k>(&self, key: &'k str) -> Option<&'k str> {
+        key.strip_prefix(self.prefix.as_str())
+    }
+}
+
+impl<S: EnvSource> EnvSource for StripPrefix<S> {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        match self.key(key) {
+            Some(key) => self.source.get(key),
+            None => Ok(None),
+        }
+    }
+    fn origin(&self, key: &str) -> Origin {
+        self.source.origin(self.key(key).unwrap_or(key))
+    }
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, FromEnvError> {
+        match self.key(key) {
+            Some(key) => self.source.lookup(key),
+            None => Ok(None),
+        }
+    }
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        match self.key(key) {
+            Some(key) => self.source.get_os(key),
+            None => Ok(None),
+        }
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        Ok(self
+            .source
+            .keys()?
+            .into_iter()
+            .map(|key| format!("{}{}", self.prefix, key))
+            .collect())
+    }
+    fn current_exe(&self) -> Result<PathBuf, FromEnvError> {
+        self.source.current_exe()
+    }
+}
+
+/// Source adapter returned by [`EnvSource::or`].
+#[derive(Debug, Clone)]
+pub struct Or<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: EnvSource, B: EnvSource> EnvSource for Or<A, B> {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        match self.first.get(key)? {
+            Some(value) => Ok(Some(value)),
+            None => self.second.get(key),
+        }
+    }
+    /// The origin given by the source holding `key`, or by the first one when none does.
+    fn origin(&self, key: &str) -> Origin {
+        let is_set = |source: &dyn EnvSource| matches!(source.get(key), Ok(Some(_)));
+        if !is_set(&self.first) && is_set(&self.second) {
+            self.second.origin(key)
+        } else {
+            self.first.origin(key)
+        }
+    }
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, FromEnvError> {
+        match self.first.lookup(key)? {
+            Some(value) => Ok(Some(value)),
+            None => self.second.lookup(key),
+        }
+    }
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        match self.first.get_os(key)? {
+            Some(value) => Ok(Some(value)),
+            None => self.second.get_os(key),
+        }
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        let mut keys = self.first.keys()?;
+        for key in self.second.keys()? {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
+    fn current_exe(&self) -> Result<PathBuf, FromEnvError> {
+        self.first.current_exe()
+    }
+}
+
+/// A variable looked up through a [`SpySource`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Lookup {
+    pub key: String,
+    /// Whether the variable was set.
+    pub hit: bool,
+}
+
+/// Wrapper recording every variable looked up in the inner source, in order, with whether it
+/// was set, to check which variables a configuration reads.
+///
+/// ```rust
+/// use derive_from_env::{FromEnv, Lookup, ProcessEnv, SpySource};
+///
+/// #[derive(FromEnv)]
+/// struct Config {
+///     #[from_env(default = "8080")]
+///     port: u16,
+/// }
+///
+/// # std::env::remove_var("PORT");
+/// let spy = SpySource::new(ProcessEnv);
+/// Config::from_source(&spy).unwrap();
+/// assert_eq!(spy.lookups(), [Lookup { key: "PORT".into(), hit: false }]);
+/// ```
+///
+/// Lookups failing with an error are not recorded. Calls to [`EnvSource::origin`] and
+/// [`EnvSource::keys`] are not recorded either.
+#[derive(Debug, Default)]
+pub struct SpySource<S> {
+    source: S,
+    lookups: Mutex<Vec<Lookup>>,
+}
+
+impl<S: EnvSource> SpySource<S> {
+    pub fn new(source: S) -> Self {
+        SpySource {
+            source,
+            lookups: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The lookups recorded so far, in order.
+    pub fn lookups(&self) -> Vec<Lookup> {
+        self.recorded().clone()
+    }
+
+    /// The keys looked up so far, in order.
+    pub fn keys_looked_up(&self) -> Vec<String> {
+        self.recorded()
+            .iter()
+            .map(|lookup| lookup.key.clone())
+            .collect()
+    }
+
+    /// Forgets the lookups recorded so far.
+    pub fn clear(&self) {
+        self.recorded().clear();
+    }
+
+    /// The inner source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+
+    fn recorded(&self) -> std::sync::MutexGuard<'_, Vec<Lookup>> {
+        self.lookups
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+
+    fn record<T>(&self, key: &str, value: Option<T>) -> Option<T> {
+        self.recorded().push(Lookup {
+            key: key.to_string(),
+            hit: value.is_some(),
+        });
+        value
+    }
+}
+
+impl<S: EnvSource> EnvSource for SpySource<S> {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        Ok(self.record(key, self.source.get(key)?))
+    }
+    fn origin(&self, key: &str) -> Origin {
+        self.source.origin(key)
+    }
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, FromEnvError> {
+        Ok(self.record(key, self.source.lookup(key)?))
+    }
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        Ok(self.record(key, self.source.get_os(key)?))
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        self.source.keys()
+    }
+    fn current_exe(&self) -> Result<PathBuf, FromEnvError> {
+        self.source.current_exe()
+    }
+}
+
+/// Wrapper restricting the variables read from the inner source to an allowlist, so that a
+/// configuration cannot read (and later log) other variables.
+///
+/// Patterns are variable names, or prefixes followed by `*` (`APP_*`). Variables outside the
+/// allowlist are reported as not set, whatever the inner source holds, or are an error once
+/// [`SandboxSource::strict`] is called.
+///
+/// ```rust
+/// use derive_from_env::{FromEnv, ProcessEnv, SandboxSource};
+///
+/// #[derive(FromEnv)]
+/// struct Config {
+///     #[from_env(default = "8080")]
+///     port: u16,
+/// }
+///
+/// // The allowlist generated from the variables read by `Config`.
+/// let source = SandboxSource::from_specs(ProcessEnv, &Config::env_vars()).strict();
+/// Config::from_source(&source).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SandboxSource<S> {
+    source: S,
+    allowlist: Vec<String>,
+    strict: bool,
+}
+
+impl<S: EnvSource> SandboxSource<S> {
+    pub fn new(source: S, allowlist: &[&str]) -> Self {
+        SandboxSource {
+            source,
+            allowlist: allowlist
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+            strict: false,
+        }
+    }
+
+    /// Allows the variables described by `specs`, e.g. the `env_vars()` of a derived struct.
+    pub fn from_specs(source: S, specs: &[crate::EnvVarSpec]) -> Self {
+        SandboxSource {
+            source,
+            allowlist: specs.iter().map(|spec| spec.name.clone()).collect(),
+            strict: false,
+        }
+    }
+
+    /// Makes lookups of variables outside the allowlist fail with
+    /// [`FromEnvError::SourceFailure`] instead of being reported as not set.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Whether `key` matches a pattern of the allowlist.
+    pub fn allows(&self, key: &str) -> bool {
+        self.allowlist
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => key.starts_with(prefix),
+                None => key == pattern,
+            })
+    }
+
+    /// Runs `f` when `key` is allowed, returning `None` or an error otherwise.
+    fn check<T>(
+        &self,
+        key: &str,
+        f: impl FnOnce() -> Result<Option<T>, FromEnvError>,
+    ) -> Result<Option<T>, FromEnvError> {
+        if self.allows(key) {
+            f()
+        } else if self.strict {
+            Err(FromEnvError::SourceFailure {
+                var_name: Some(key.to_string()),
+                message: format!("{} is not in the allowlist of the source", key),
+            })
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<S: EnvSource> EnvSource for SandboxSource<S> {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        self.check(key, || self.source.get(key))
+    }
+    fn origin(&self, key: &str) -> Origin {
+        self.source.origin(key)
+    }
+    fn lookup(&self, key: &str) -> Result<Option<(String, Origin)>, FromEnvError> {
+        self.check(key, || self.source.lookup(key))
+    }
+    fn get_os(&self, key: &str) -> Result<Option<OsString>, FromEnvError> {
+        self.check(key, || self.source.get_os(key))
+    }
+    fn keys(&self) -> Result<Vec<String>, FromEnvError> {
+        Ok(self
+            .source
+            .keys()?
+            .into_iter()
+            .filter(|key| self.allows(key))
+            .collect())
+    }
+    fn current_exe(&self) -> Result<PathBuf, FromEnvError> {
+        self.source.current_exe()
+    }
+}
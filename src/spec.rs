@@ -0,0 +1,31 @@
+/// Description of an environment variable read by a [`FromEnv`](crate::FromEnv) type, as returned
+/// by the generated `env_vars()`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EnvVarSpec {
+    /// Name of the variable.
+    pub name: String,
+    /// Path of the field filled by the variable, with nested fields joined by `.` (e.g.
+    /// `database.host`).
+    pub field: String,
+    /// Type of the field, the content type for `Option` fields.
+    pub type_name: String,
+    /// Value of the `default` attribute, if any.
+    pub default: Option<String>,
+    /// Whether loading fails when the variable is not set.
+    pub required: bool,
+    /// Whether the field is an `Option`.
+    pub optional: bool,
+    /// Whether the field has the `doc_hidden` attribute: the variable is read as usual but left
+    /// out of the generated documentation.
+    pub hidden: bool,
+    /// Whether the values of the field are hidden, by the `secret` attribute or a `secrecy` type.
+    pub secret: bool,
+}
+
+impl EnvVarSpec {
+    /// Whether an unset variable is replaced by a value, from a `default` attribute or the
+    /// `all_defaultable` struct attribute.
+    pub fn has_default(&self) -> bool {
+        !self.required && !self.optional
+    }
+}
@@ -0,0 +1,164 @@
+//! Helpers for tests depending on environment variables.
+//!
+//! The [`from_env_test`](crate::from_env_test) attribute wraps a test function so that it runs
+//! with a given environment:
+//!
+//! ```rust
+//! use derive_from_env::{from_env_test, FromEnv};
+//!
+//! #[derive(FromEnv)]
+//! struct Config {
+//!     port: u16,
+//!     debug: Option<bool>,
+//! }
+//!
+//! #[from_env_test(vars("PORT" = "8080"), unset("DEBUG"))]
+//! fn test_config() {
+//!     let config = Config::from_env().unwrap();
+//!     assert_eq!(config.port, 8080);
+//!     assert_eq!(config.debug, None);
+//! }
+//! # test_config();
+//! ```
+//!
+//! It works on `#[test]` as well as `async` (e.g. `#[tokio::test]`) functions. While the test runs,
+//! it holds a process-wide lock shared by every [`EnvGuard`], so that such tests don't observe each
+//! other's variables. Tests modifying the same variables through other means (`std::env::set_var`,
+//! `temp_env`, ...) are not synchronized with it.
+//!
+//! Tests needing a pristine environment, e.g. to check the behavior when nothing at all is set,
+//! can run in a child process with [`run_isolated`].
+
+use std::ffi::OsString;
+use std::process::Command;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// Sets (or removes) environment variables until dropped, restoring their previous values
+/// afterwards, even when the test panics.
+///
+/// Guards can't be nested: acquiring a second guard on the same thread deadlocks.
+pub struct EnvGuard {
+    previous: Vec<(String, Option<OsString>)>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl EnvGuard {
+    /// Acquires the environment lock, then sets each variable given with `Some(value)` and removes
+    /// each variable given with `None`.
+    pub fn acquire(vars: &[(&str, Option<&str>)]) -> Self {
+        // A test panicking while holding the lock doesn't leave the environment in a bad state:
+        // the guard restored it while unwinding.
+        let lock = ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let previous = vars
+            .iter()
+            .map(|(key, _)| (key.to_string(), std::env::var_os(key)))
+            .collect();
+        for (key, value) in vars {
+            match value {
+                Some(value) => std::env::set_var(key, value),
+                None => std::env::remove_var(key),
+            }
+        }
+        EnvGuard {
+            previous,
+            _lock: lock,
+        }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        for (key, value) in self.previous.iter().rev() {
+            match value {
+                Some(value) => std::env::set_var(key, value),
+                None => std::env::remove_var(key),
+            }
+        }
+    }
+}
+
+/// Variable set in the child processes of [`run_isolated`] to the path of the test to run.
+const ISOLATED_TEST_VAR: &str = "__DERIVE_FROM_ENV_ISOLATED_TEST";
+
+/// Variable set in the child processes of [`run_isolated`] to the platform variables passed on
+/// from the current process, separated by commas.
+const ISOLATED_KEPT_VAR: &str = "__DERIVE_FROM_ENV_ISOLATED_KEPT";
+
+/// Variables of the current process kept by [`run_isolated`], which programs need to run on the
+/// platform. Outside of Windows they are only needed to start the test binary, and are removed
+/// again before running the test.
+#[cfg(windows)]
+pub const PLATFORM_VARS: &[&str] = &["SystemRoot", "SystemDrive", "windir", "PATH", "TEMP", "TMP"];
+#[cfg(not(windows))]
+pub const PLATFORM_VARS: &[&str] = &[
+    "LD_LIBRARY_PATH",
+    "DYLD_LIBRARY_PATH",
+    "DYLD_FALLBACK_LIBRARY_PATH",
+];
+
+/// Runs `test` in a child process whose environment holds exactly `vars` (and on Windows the
+/// [`PLATFORM_VARS`] it doesn't set), without touching the environment of the current process, and
+/// panics with the output of the child if it fails.
+///
+/// Must be called from the `#[test]` function whose path, as listed by the test harness, is
+/// `test_path`: the current test binary is executed again, filtered on that test, and the call to
+/// `run_isolated` made by the test in the child process runs `test` directly. The rest of the test
+/// body runs in both processes, so `run_isolated` should be its only statement.
+///
+/// ```rust,no_run
+/// use derive_from_env::test_util::run_isolated;
+///
+/// #[test]
+/// fn test_pristine_environment() {
+///     run_isolated("test_pristine_environment", &[("PORT", "8080")], || {
+///         assert_eq!(std::env::var("PORT").unwrap(), "8080");
+///     });
+/// }
+/// ```
+pub fn run_isolated(test_path: &str, vars: &[(&str, &str)], test: fn()) {
+    if std::env::var_os(ISOLATED_TEST_VAR).is_some_and(|path| path == test_path) {
+        // Child process: the markers are removed so that the test sees exactly `vars`.
+        std::env::remove_var(ISOLATED_TEST_VAR);
+        let kept = std::env::var(ISOLATED_KEPT_VAR).unwrap_or_default();
+        std::env::remove_var(ISOLATED_KEPT_VAR);
+        if !cfg!(windows) {
+            for key in kept.split(',').filter(|key| !key.is_empty()) {
+                std::env::remove_var(key);
+            }
+        }
+        test();
+        return;
+    }
+    let kept = PLATFORM_VARS
+        .iter()
+        .filter(|key| vars.iter().all(|(name, _)| name != *key))
+        .filter_map(|key| Some((*key, std::env::var_os(key)?)))
+        .collect::<Vec<_>>();
+    let exe = std::env::current_exe().expect("failed to determine the path of the test binary");
+    let output = Command::new(exe)
+        .args([test_path, "--exact", "--nocapture", "--test-threads=1"])
+        .env_clear()
+        .envs(kept.iter().cloned())
+        .envs(vars.iter().copied())
+        .env(ISOLATED_TEST_VAR, test_path)
+        .env(
+            ISOLATED_KEPT_VAR,
+            kept.iter()
+                .map(|(key, _)| *key)
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+        .output()
+        .expect("failed to run the isolated test");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // The filter matching no test would succeed as well.
+    if !output.status.success() || !stdout.contains("1 passed") {
+        panic!(
+            "isolated test {} failed ({})\n--- stdout\n{}\n--- stderr\n{}",
+            test_path, output.status, stdout, stderr
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin, ProcessEnv, SandboxSource};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Cache {
+    #[from_env(alias = "REDIS_HOST", alias = "MEMCACHED_HOST")]
+    host: String,
+    #[from_env(alias = "REDIS_PORT", alias_var = "LEGACY_PORT", default = "6379")]
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct Config {
+    cache: Cache,
+    #[from_env(var = "TIMEOUT", alias = "LEGACY_TIMEOUT")]
+    timeout: u64,
+}
+
+const VARS: [&str; 7] = [
+    "APP_CACHE_HOST",
+    "APP_CACHE_REDIS_HOST",
+    "APP_CACHE_MEMCACHED_HOST",
+    "APP_CACHE_PORT",
+    "APP_CACHE_REDIS_PORT",
+    "LEGACY_PORT",
+    "TIMEOUT",
+];
+
+fn load(set: &[(&'static str, &'static str)]) -> Result<Config, FromEnvError> {
+    let vars = VARS
+        .iter()
+        .chain(&["LEGACY_TIMEOUT"])
+        .map(|&name| {
+            let value = set
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value);
+            (name, value)
+        })
+        .collect::<Vec<_>>();
+    with_vars(vars, Config::from_env)
+}
+
+#[test]
+fn test_primary_name_first() {
+    let config = load(&[
+        ("APP_CACHE_HOST", "cache1"),
+        ("APP_CACHE_REDIS_HOST", "redis1"),
+        ("TIMEOUT", "5"),
+    ])
+    .unwrap();
+    assert_eq!(config.cache.host, "cache1");
+}
+
+#[test]
+fn test_aliases_in_order_under_the_prefix() {
+    let config = load(&[
+        ("APP_CACHE_REDIS_HOST", "redis1"),
+        ("APP_CACHE_MEMCACHED_HOST", "memcached1"),
+        ("LEGACY_PORT", "6380"),
+        ("LEGACY_TIMEOUT", "10"),
+    ])
+    .unwrap();
+    assert_eq!(
+        config,
+        Config {
+            cache: Cache {
+                host: "redis1".to_string(),
+                port: 6380,
+            },
+            timeout: 10,
+        }
+    );
+}
+
+#[test]
+fn test_missing_lists_the_names_tried() {
+    let error = load(&[("TIMEOUT", "5")]).unwrap_err();
+    assert_eq!(
+        error,
+        FromEnvError::MissingEnvVar {
+            var_name: "APP_CACHE_HOST".to_string(),
+            origin: Origin::ProcessEnv,
+            fallbacks: vec![
+                "APP_CACHE_REDIS_HOST".to_string(),
+                "APP_CACHE_MEMCACHED_HOST".to_string(),
+            ],
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "missing environment variable APP_CACHE_HOST (also tried APP_CACHE_REDIS_HOST, APP_CACHE_MEMCACHED_HOST)"
+    );
+}
+
+#[test]
+fn test_parsing_failure_names_the_alias() {
+    let error = load(&[
+        ("APP_CACHE_HOST", "cache1"),
+        ("APP_CACHE_REDIS_PORT", "none"),
+        ("TIMEOUT", "5"),
+    ])
+    .unwrap_err();
+    assert_eq!(
+        error,
+        FromEnvError::ParsingFailure {
+            var_name: "APP_CACHE_REDIS_PORT".to_string(),
+            str_value: "none".to_string(),
+            origin: Origin::ProcessEnv,
+            expected_type: "u16".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_specs_allow_the_aliases() {
+    let specs = Config::env_vars();
+    assert_eq!(
+        specs
+            .iter()
+            .map(|spec| (spec.name.as_str(), spec.optional))
+            .collect::<Vec<_>>(),
+        [
+            ("APP_CACHE_HOST", false),
+            ("APP_CACHE_REDIS_HOST", true),
+            ("APP_CACHE_MEMCACHED_HOST", true),
+            ("APP_CACHE_PORT", false),
+            ("APP_CACHE_REDIS_PORT", true),
+            ("LEGACY_PORT", true),
+            ("TIMEOUT", false),
+            ("LEGACY_TIMEOUT", true),
+        ]
+    );
+    let source = SandboxSource::from_specs(ProcessEnv, &specs).strict();
+    with_vars(
+        [
+            ("APP_CACHE_MEMCACHED_HOST", Some("memcached1")),
+            ("LEGACY_TIMEOUT", Some("10")),
+        ],
+        || {
+            let config = Config::from_source(&source).unwrap();
+            assert_eq!(config.cache.host, "memcached1");
+            assert_eq!(config.timeout, 10);
+        },
+    );
+}
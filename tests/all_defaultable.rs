@@ -0,0 +1,104 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(all_defaultable)]
+struct Tracing {
+    endpoint: String,
+    sample_rate: f64,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(all_defaultable)]
+struct Knobs {
+    name: String,
+    workers: u32,
+    #[from_env(default = "8080")]
+    port: u16,
+    verbose: Option<bool>,
+    #[from_env(flatten)]
+    tracing: Tracing,
+}
+
+#[test]
+fn test_all_defaultable_unset() {
+    with_vars(
+        vec![
+            ("KNOBS_NAME", None::<&str>),
+            ("KNOBS_WORKERS", None),
+            ("KNOBS_PORT", None),
+            ("KNOBS_VERBOSE", None),
+            ("KNOBS_TRACING_ENDPOINT", None),
+            ("KNOBS_TRACING_SAMPLE_RATE", None),
+        ],
+        || {
+            assert_eq!(
+                Knobs::from_env_with_prefix("KNOBS").unwrap(),
+                Knobs {
+                    name: String::new(),
+                    workers: 0,
+                    port: 8080,
+                    verbose: None,
+                    tracing: Tracing {
+                        endpoint: String::new(),
+                        sample_rate: 0.
+                    }
+                }
+            );
+        },
+    )
+}
+
+#[test]
+fn test_all_defaultable_set() {
+    with_vars(
+        vec![
+            ("KNOBS_NAME", Some("knobs")),
+            ("KNOBS_WORKERS", Some("4")),
+            ("KNOBS_PORT", None),
+            ("KNOBS_VERBOSE", Some("true")),
+            ("KNOBS_TRACING_ENDPOINT", None),
+            ("KNOBS_TRACING_SAMPLE_RATE", Some("0.5")),
+        ],
+        || {
+            assert_eq!(
+                Knobs::from_env_with_prefix("KNOBS").unwrap(),
+                Knobs {
+                    name: "knobs".into(),
+                    workers: 4,
+                    port: 8080,
+                    verbose: Some(true),
+                    tracing: Tracing {
+                        endpoint: String::new(),
+                        sample_rate: 0.5
+                    }
+                }
+            );
+        },
+    )
+}
+
+#[test]
+fn test_all_defaultable_parse_failure() {
+    with_vars(
+        vec![
+            ("KNOBS_NAME", None),
+            ("KNOBS_WORKERS", Some("four")),
+            ("KNOBS_PORT", None),
+            ("KNOBS_VERBOSE", None),
+            ("KNOBS_TRACING_ENDPOINT", None),
+            ("KNOBS_TRACING_SAMPLE_RATE", None),
+        ],
+        || {
+            assert_eq!(
+                Knobs::from_env_with_prefix("KNOBS").unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "KNOBS_WORKERS".into(),
+                    expected_type: "u32".into(),
+                    str_value: "four".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+        },
+    )
+}
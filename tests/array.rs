@@ -0,0 +1,51 @@
+use derive_from_env::{FromEnv, FromEnvError};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    shard_hosts: [String; 4],
+    #[from_env(default = "255, 128, 0")]
+    rgb: [u8; 3],
+    #[from_env(separator = ":")]
+    pair: Option<[u16; 2]>,
+}
+
+fn load(shard_hosts: &str, rgb: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![
+            ("SHARD_HOSTS", Some(shard_hosts)),
+            ("RGB", rgb),
+            ("PAIR", Some("80:443")),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_array() {
+    let config = load("a,b,c,d", None).unwrap();
+    assert_eq!(config.shard_hosts, ["a", "b", "c", "d"]);
+    assert_eq!(config.rgb, [255, 128, 0]);
+    assert_eq!(config.pair, Some([80, 443]));
+}
+
+#[test]
+fn test_array_length_mismatch() {
+    assert_eq!(
+        load("a,b,c", None).unwrap_err(),
+        FromEnvError::Custom {
+            var_name: Some("SHARD_HOSTS".to_string()),
+            message: "expected 4 items, found 3 in \"a,b,c\"".to_string(),
+        }
+    );
+    assert_eq!(
+        load("a,b,c,d", Some("1,2,3,4")).unwrap_err().to_string(),
+        "RGB: expected 3 items, found 4 in \"1,2,3,4\""
+    );
+}
+
+#[test]
+fn test_array_specs() {
+    let specs = Config::env_vars();
+    assert_eq!(specs[0].type_name, "[String; 4]");
+}
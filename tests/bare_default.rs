@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use derive_from_env::{FromEnv, FromEnvError, Location, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(default)]
+    tags: Vec<String>,
+    #[from_env(default)]
+    retries: u32,
+    #[from_env(default)]
+    cache_dir: PathBuf,
+    name: String,
+}
+
+#[test]
+fn test_bare_default_unset() {
+    with_vars(
+        vec![
+            ("TAGS", None::<&str>),
+            ("RETRIES", None),
+            ("CACHE_DIR", None),
+            ("NAME", Some("api")),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap(),
+                Config {
+                    tags: Vec::new(),
+                    retries: 0,
+                    cache_dir: PathBuf::new(),
+                    name: "api".to_string(),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_bare_default_set() {
+    with_vars(
+        vec![
+            ("TAGS", Some("a,b")),
+            ("RETRIES", Some("x")),
+            ("CACHE_DIR", Some("/tmp")),
+            ("NAME", Some("api")),
+        ],
+        || {
+            // Values failing to parse are still errors.
+            assert_eq!(
+                Config::from_env().unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "RETRIES".into(),
+                    str_value: "x".into(),
+                    origin: Origin::ProcessEnv,
+                    expected_type: "u32".into(),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_bare_default_specs_and_explain() {
+    let specs = Config::env_vars();
+    assert!(specs[0].has_default());
+    assert!(specs[3].required);
+    with_vars(vec![("TAGS", None::<&str>)], || {
+        assert_eq!(
+            Config::explain("tags")
+                .unwrap()
+                .resolved()
+                .unwrap()
+                .location,
+            Location::TypeDefault
+        );
+    });
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct OptionalConfig {
+    #[from_env(default)]
+    ports: Option<Vec<u16>>,
+}
+
+#[test]
+fn test_bare_default_option() {
+    with_vars(vec![("PORTS", None::<&str>)], || {
+        assert_eq!(
+            OptionalConfig::from_env().unwrap(),
+            OptionalConfig { ports: None }
+        );
+    });
+    with_vars(vec![("PORTS", Some("80,443"))], || {
+        assert_eq!(
+            OptionalConfig::from_env().unwrap(),
+            OptionalConfig {
+                ports: Some(vec![80, 443])
+            }
+        );
+    });
+}
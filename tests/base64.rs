@@ -0,0 +1,61 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(base64)]
+    hmac_key: Vec<u8>,
+    #[from_env(base64 = "url", default = "aGk_Pz8-")]
+    token: String,
+    #[from_env(base64)]
+    tls_key: Option<Vec<u8>>,
+}
+
+fn load(hmac_key: &str, token: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![
+            ("HMAC_KEY", Some(hmac_key)),
+            ("TOKEN", token),
+            ("TLS_KEY", None),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_base64() {
+    let config = load("AAEC/w==", None).unwrap();
+    assert_eq!(config.hmac_key, vec![0, 1, 2, 255]);
+    assert_eq!(config.token, "hi???>");
+    assert_eq!(config.tls_key, None);
+    assert_eq!(load("aGVsbG8", None).unwrap().hmac_key, b"hello");
+    assert_eq!(load("", Some("c2VjcmV0")).unwrap().token, "secret");
+}
+
+#[test]
+fn test_base64_failures() {
+    assert_eq!(
+        load("c2Vj*mV0", None).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "HMAC_KEY".into(),
+            expected_type: "base64 (standard alphabet), invalid character at position 5".into(),
+            str_value: "<redacted>".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+    assert_eq!(
+        load("c2VjcmV0", Some("aGk/")).unwrap_err().to_string(),
+        "failed to parse TOKEN=\"<redacted>\" as base64 (URL-safe alphabet), invalid character \
+         at position 4 (value from process environment)"
+    );
+    assert_eq!(
+        load("c2Vjc", None).unwrap_err().to_string(),
+        "failed to parse HMAC_KEY=\"<redacted>\" as base64 (standard alphabet), invalid length 5 \
+         (value from process environment)"
+    );
+    assert_eq!(
+        load("c2VjcmV0", Some("_w")).unwrap_err().to_string(),
+        "failed to parse TOKEN=\"<redacted>\" as base64 (URL-safe alphabet) of UTF-8 text (value \
+         from process environment)"
+    );
+}
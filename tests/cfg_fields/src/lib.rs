@@ -0,0 +1,86 @@
+//! The `metrics` feature of this crate guards fields of the derived structs, which must compile
+//! and load the same way whether it is enabled or not.
+
+use derive_from_env::FromEnv;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(diff, merge, cli_args)]
+pub struct Metrics {
+    pub endpoint: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(diff, merge, cli_args, check_defaults)]
+pub struct Config {
+    pub port: u16,
+    #[cfg(feature = "metrics")]
+    #[from_env(default = "10")]
+    pub metrics_interval: u64,
+    #[cfg(feature = "metrics")]
+    #[from_env(flatten)]
+    pub metrics: Metrics,
+    #[cfg(not(feature = "metrics"))]
+    pub fallback: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use temp_env::with_vars;
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_with_feature() {
+        with_vars(
+            vec![
+                ("PORT", Some("8080")),
+                ("METRICS_INTERVAL", None),
+                ("METRICS_ENDPOINT", Some("localhost:9090")),
+            ],
+            || {
+                assert_eq!(
+                    Config::from_env().unwrap(),
+                    Config {
+                        port: 8080,
+                        metrics_interval: 10,
+                        metrics: Metrics {
+                            endpoint: "localhost:9090".into(),
+                        },
+                    }
+                );
+            },
+        );
+        let names = Config::env_vars()
+            .into_iter()
+            .map(|spec| spec.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["PORT", "METRICS_INTERVAL", "METRICS_ENDPOINT"]);
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    #[test]
+    fn test_without_feature() {
+        with_vars(
+            vec![
+                ("PORT", Some("8080")),
+                ("METRICS_INTERVAL", Some("not a number")),
+                ("METRICS_ENDPOINT", None),
+                ("FALLBACK", None),
+            ],
+            || {
+                assert_eq!(
+                    Config::from_env().unwrap(),
+                    Config {
+                        port: 8080,
+                        fallback: None,
+                    }
+                );
+            },
+        );
+        let names = Config::env_vars()
+            .into_iter()
+            .map(|spec| spec.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["PORT", "FALLBACK"]);
+    }
+}
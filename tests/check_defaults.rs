@@ -0,0 +1,54 @@
+use derive_from_env::{FromEnv, FromEnvError, FromEnvValue, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnvValue)]
+enum LogLevel {
+    Warn,
+    Info,
+}
+
+// The generated `__from_env_default_checks_config` module runs a test per default.
+#[derive(Debug, FromEnv)]
+#[from_env(check_defaults)]
+#[allow(dead_code)]
+struct Config {
+    #[from_env(default = "info")]
+    level: LogLevel,
+    #[from_env(default = "8080")]
+    port: u16,
+    #[from_env(default = "95%", percent)]
+    ratio: f64,
+    name: String,
+}
+
+/// With `check_defaults`, the generated test of `level` would fail with:
+/// `invalid default of BadConfig.level: failed to parse LEVEL="warnn" as LogLevel (value from
+/// default attribute)`.
+#[derive(Debug, FromEnv)]
+#[allow(dead_code)]
+struct BadConfig {
+    #[from_env(default = "8080")]
+    port: u16,
+    #[from_env(default = "warnn")]
+    level: LogLevel,
+}
+
+#[test]
+fn test_check_defaults() {
+    assert_eq!(Config::check_defaults(), Ok(()));
+    // The same failure as when loading the struct without the variable.
+    let error = with_vars(
+        vec![("PORT", None::<&str>), ("LEVEL", None)],
+        BadConfig::from_env,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        FromEnvError::ParsingFailure {
+            var_name: "LEVEL".into(),
+            expected_type: "LogLevel".into(),
+            str_value: "warnn".into(),
+            origin: Origin::Default,
+        }
+    );
+}
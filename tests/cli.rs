@@ -0,0 +1,135 @@
+use derive_from_env::cli::run_env_flags;
+use derive_from_env::{FromEnv, EX_CONFIG};
+use temp_env::with_vars;
+
+#[derive(FromEnv)]
+#[allow(dead_code)]
+struct Database {
+    url: String,
+}
+
+#[derive(FromEnv)]
+#[allow(dead_code)]
+struct CliConfig {
+    #[from_env(var = "CLI_HTTP_PORT", default = "8080")]
+    http_port: u16,
+    #[from_env(var = "CLI_TOKEN")]
+    token: Option<String>,
+    #[from_env(no_prefix)]
+    cli_database: Database,
+}
+
+fn run(args: &[&str]) -> (Option<i32>, String) {
+    let mut out = Vec::new();
+    let status = run_env_flags::<CliConfig>(args, &mut out).unwrap();
+    (status, String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn test_no_flag() {
+    assert_eq!(run(&["app"]), (None, String::new()));
+    assert_eq!(run(&["app", "--verbose"]), (None, String::new()));
+    // The program name is not a flag.
+    assert_eq!(run(&["--env-help"]), (None, String::new()));
+}
+
+#[test]
+fn test_env_help() {
+    assert_eq!(
+        run(&["app", "--env-help"]),
+        (
+            Some(0),
+            concat!(
+                "VARIABLE       TYPE    DEFAULT     FIELD\n",
+                "CLI_HTTP_PORT  u16     8080        http_port\n",
+                "CLI_TOKEN      String  <optional>  token\n",
+                "URL            String  <required>  cli_database.url\n",
+            )
+            .to_string()
+        )
+    );
+}
+
+#[test]
+fn test_env_template() {
+    assert_eq!(
+        run(&["app", "--env-template"]),
+        (
+            Some(0),
+            concat!(
+                "# http_port (u16)\n",
+                "#CLI_HTTP_PORT=8080\n",
+                "# token (String), optional\n",
+                "#CLI_TOKEN=\n",
+                "# cli_database.url (String), required\n",
+                "URL=\n",
+            )
+            .to_string()
+        )
+    );
+}
+
+#[test]
+fn test_env_check() {
+    with_vars(vec![("URL", None::<&str>), ("CLI_HTTP_PORT", None)], || {
+        assert_eq!(
+            run(&["app", "--env-check"]),
+            (
+                Some(EX_CONFIG.into()),
+                "missing environment variable URL (cli_database.url, String)\n".to_string()
+            )
+        );
+    });
+    with_vars(
+        vec![
+            ("URL", Some("postgres://db")),
+            ("CLI_HTTP_PORT", Some("http")),
+        ],
+        || {
+            let (status, out) = run(&["app", "--env-check"]);
+            assert_eq!(status, Some(EX_CONFIG.into()));
+            assert!(out.contains("variable: CLI_HTTP_PORT"), "{}", out);
+        },
+    );
+    with_vars(
+        vec![("URL", Some("postgres://db")), ("CLI_HTTP_PORT", None)],
+        || {
+            assert_eq!(
+                run(&["app", "--env-check"]),
+                (Some(0), "configuration loaded successfully\n".to_string())
+            );
+        },
+    );
+}
+
+#[derive(FromEnv)]
+struct HiddenConfig {
+    #[from_env(var = "HIDDEN_PORT", default = "8080")]
+    port: u16,
+    #[from_env(var = "HIDDEN_POD_UID", doc_hidden)]
+    pod_uid: String,
+}
+
+#[test]
+fn test_doc_hidden() {
+    with_vars(vec![("HIDDEN_POD_UID", Some("1234"))], || {
+        let config = HiddenConfig::from_env().unwrap();
+        assert_eq!((config.port, config.pod_uid.as_str()), (8080, "1234"));
+    });
+    let specs = HiddenConfig::env_vars();
+    assert!(specs[1].hidden);
+    assert_eq!(
+        derive_from_env::generate::dotenv_template(&specs, false),
+        "# port (u16)\n#HIDDEN_PORT=8080\n"
+    );
+    assert_eq!(
+        derive_from_env::generate::dotenv_template(&specs, true),
+        "# port (u16)\n#HIDDEN_PORT=8080\n# pod_uid (String), required\nHIDDEN_POD_UID=\n"
+    );
+    let mut out = Vec::new();
+    run_env_flags::<HiddenConfig>(&["app", "--env-help"], &mut out).unwrap();
+    assert!(!String::from_utf8(out).unwrap().contains("HIDDEN_POD_UID"));
+    let mut out = Vec::new();
+    run_env_flags::<HiddenConfig>(&["app", "--env-help", "--include-hidden"], &mut out).unwrap();
+    assert!(String::from_utf8(out).unwrap().contains("HIDDEN_POD_UID"));
+}
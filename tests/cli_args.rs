@@ -0,0 +1,78 @@
+use derive_from_env::{ArgStyle, FromEnv};
+use temp_env::with_vars;
+
+#[derive(Debug, FromEnv)]
+#[from_env(cli_args)]
+struct Database {
+    host: String,
+    #[from_env(default = "5432")]
+    port: u16,
+    #[from_env(secret)]
+    password: String,
+}
+
+#[derive(Debug, FromEnv)]
+#[from_env(cli_args)]
+struct Config {
+    service_name: String,
+    debug: bool,
+    replica: Option<String>,
+    tags: Vec<String>,
+    #[from_env(flatten)]
+    database: Database,
+}
+
+fn load(replica: Option<&str>) -> Config {
+    with_vars(
+        [
+            ("SERVICE_NAME", Some("api")),
+            ("DEBUG", Some("false")),
+            ("REPLICA", replica),
+            ("TAGS", Some("a,b")),
+            ("DATABASE_HOST", Some("db1")),
+            ("DATABASE_PORT", None),
+            ("DATABASE_PASSWORD", Some("hunter2")),
+        ],
+        || Config::from_env().unwrap(),
+    )
+}
+
+#[test]
+fn test_cli_args() {
+    let config = load(Some("db2"));
+    assert_eq!(
+        config.to_cli_args(ArgStyle::default()),
+        [
+            "--service-name=api",
+            "--no-debug",
+            "--replica=db2",
+            "--tags=a,b",
+            "--database-host=db1",
+            "--database-port=5432",
+            "--database-password=<redacted>",
+        ]
+    );
+}
+
+#[test]
+fn test_cli_args_space_separated_without_secrets() {
+    let config = load(None);
+    let style = ArgStyle {
+        space_separated: true,
+        skip_secrets: true,
+    };
+    assert_eq!(
+        config.to_cli_args(style),
+        [
+            "--service-name",
+            "api",
+            "--no-debug",
+            "--tags",
+            "a,b",
+            "--database-host",
+            "db1",
+            "--database-port",
+            "5432",
+        ]
+    );
+}
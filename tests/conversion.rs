@@ -0,0 +1,73 @@
+use std::borrow::Cow;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+/// A domain type validated on conversion, without `FromStr`.
+#[derive(Debug, PartialEq)]
+struct Percentage(u8);
+
+impl TryFrom<u64> for Percentage {
+    type Error = String;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        if value <= 100 {
+            Ok(Percentage(value as u8))
+        } else {
+            Err(format!("{} is greater than 100", value))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Limits {
+    #[from_env(from = "String")]
+    name: Cow<'static, str>,
+    #[from_env(try_from = "u64")]
+    threshold: Percentage,
+    #[from_env(try_from = "u64")]
+    burst: Option<Percentage>,
+}
+
+fn load(threshold: &str) -> Result<Limits, FromEnvError> {
+    with_vars(
+        vec![
+            ("LIMITS_NAME", Some("api")),
+            ("LIMITS_THRESHOLD", Some(threshold)),
+            ("LIMITS_BURST", None),
+        ],
+        || Limits::from_env_with_prefix("LIMITS"),
+    )
+}
+
+#[test]
+fn test_from_and_try_from() {
+    assert_eq!(
+        load("80").unwrap(),
+        Limits {
+            name: Cow::Borrowed("api"),
+            threshold: Percentage(80),
+            burst: None,
+        }
+    );
+}
+
+#[test]
+fn test_try_from_failure() {
+    assert_eq!(
+        load("150").unwrap_err(),
+        FromEnvError::Custom {
+            var_name: Some("LIMITS_THRESHOLD".into()),
+            message: "invalid value \"150\": 150 is greater than 100".into(),
+        }
+    );
+    assert_eq!(
+        load("-1").unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "LIMITS_THRESHOLD".into(),
+            expected_type: "u64".into(),
+            str_value: "-1".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+}
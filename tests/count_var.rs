@@ -0,0 +1,63 @@
+use std::io::Cursor;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin, ReaderSource, SpySource};
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(count_var = "PEER_COUNT")]
+    peer: Vec<String>,
+    #[from_env(count_var = "WEIGHT_COUNT", var = "W")]
+    weights: Option<Vec<u8>>,
+}
+
+fn source(input: &str) -> ReaderSource {
+    ReaderSource::from_reader(Cursor::new(input.to_string())).unwrap()
+}
+
+#[test]
+fn test_count_var() {
+    let config = Config::from_source(&source(
+        "PEER_COUNT=3\nPEER_0=a\nPEER_1=b\nPEER_2=c\nPEER_3=ignored\nWEIGHT_COUNT=1\nW_0=7\n",
+    ))
+    .unwrap();
+    assert_eq!(config.peer, ["a", "b", "c"]);
+    assert_eq!(config.weights, Some(vec![7]));
+}
+
+#[test]
+fn test_count_var_zero() {
+    let spy = SpySource::new(source("PEER_COUNT=0\nPEER_0=a\n"));
+    let config = Config::from_source(&spy).unwrap();
+    assert_eq!(config.peer, Vec::<String>::new());
+    assert_eq!(config.weights, None);
+    assert_eq!(spy.keys_looked_up(), ["PEER_COUNT", "WEIGHT_COUNT"]);
+}
+
+#[test]
+fn test_count_var_failures() {
+    assert_eq!(
+        Config::from_source(&source("PEER_COUNT=3\nPEER_0=a\nPEER_2=c\n")).unwrap_err(),
+        FromEnvError::MissingEnvVar {
+            var_name: "PEER_1".to_string(),
+            origin: Origin::Remote("input".to_string()),
+            fallbacks: Vec::new(),
+        }
+    );
+    assert_eq!(
+        Config::from_source(&source("")).unwrap_err(),
+        FromEnvError::MissingEnvVar {
+            var_name: "PEER_COUNT".to_string(),
+            origin: Origin::Remote("input".to_string()),
+            fallbacks: Vec::new(),
+        }
+    );
+    assert_eq!(
+        Config::from_source(&source("PEER_COUNT=0\nWEIGHT_COUNT=1\nW_0=heavy\n")).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "W_0".to_string(),
+            expected_type: "u8".to_string(),
+            str_value: "heavy".to_string(),
+            origin: Origin::Remote("input line 3".to_string()),
+        }
+    );
+}
@@ -0,0 +1,64 @@
+//! `derive_from_env` is renamed in the manifest of this crate, so `::derive_from_env` paths do not
+//! resolve here: the derives only compile thanks to their `crate` attribute.
+
+/// A facade re-exporting the crate, as a platform crate would.
+pub mod config {
+    pub use renamed_from_env::*;
+}
+
+use config::{FromEnv, FromEnvValue};
+
+#[derive(Debug, PartialEq, FromEnvValue)]
+#[from_env(crate = "crate::config")]
+pub enum Mode {
+    Fast,
+    Safe,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(crate = "crate::config")]
+pub struct Database {
+    pub url: String,
+    #[from_env(default = "5")]
+    pub pool: u32,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(crate = "renamed_from_env")]
+pub struct Service {
+    pub mode: Mode,
+    pub database: Database,
+    pub tags: Option<Vec<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use temp_env::with_vars;
+
+    #[test]
+    fn test_crate_attribute() {
+        with_vars(
+            vec![
+                ("APP_MODE", Some("safe")),
+                ("APP_DATABASE_URL", Some("postgres://db")),
+                ("APP_DATABASE_POOL", None),
+                ("APP_TAGS", Some("a,b")),
+            ],
+            || {
+                assert_eq!(
+                    Service::from_env_with_prefix("APP").unwrap(),
+                    Service {
+                        mode: Mode::Safe,
+                        database: Database {
+                            url: "postgres://db".into(),
+                            pool: 5,
+                        },
+                        tags: Some(vec!["a".into(), "b".into()]),
+                    }
+                );
+            },
+        );
+        assert_eq!(Service::env_vars().len(), 4);
+    }
+}
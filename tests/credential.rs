@@ -0,0 +1,104 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Credentials {
+    #[from_env(credential = "db-password")]
+    db_password: String,
+    #[from_env(credential = "pool-size")]
+    pool_size: u32,
+    #[from_env(credential = "api-token")]
+    api_token: Option<String>,
+}
+
+#[test]
+fn test_credentials_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("db-password"), "s3cret\n").unwrap();
+    std::fs::write(dir.path().join("pool-size"), "8").unwrap();
+    with_vars(
+        vec![
+            ("CREDENTIALS_DIRECTORY", Some(dir.path().to_str().unwrap())),
+            ("CRED_DB_PASSWORD", Some("from-env")),
+            ("CRED_POOL_SIZE", None),
+            ("CRED_API_TOKEN", None),
+        ],
+        || {
+            assert_eq!(
+                Credentials::from_env_with_prefix("CRED").unwrap(),
+                Credentials {
+                    db_password: "s3cret".into(),
+                    pool_size: 8,
+                    api_token: None,
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_credentials_fallback_to_env() {
+    with_vars(
+        vec![
+            ("CREDENTIALS_DIRECTORY", None),
+            ("CRED_DB_PASSWORD", Some("from-env")),
+            ("CRED_POOL_SIZE", Some("4")),
+            ("CRED_API_TOKEN", Some("token")),
+        ],
+        || {
+            assert_eq!(
+                Credentials::from_env_with_prefix("CRED").unwrap(),
+                Credentials {
+                    db_password: "from-env".into(),
+                    pool_size: 4,
+                    api_token: Some("token".into()),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_credentials_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("db-password"), "s3cret\r\n").unwrap();
+    std::fs::write(dir.path().join("pool-size"), "eight\n").unwrap();
+    let pool_size_path = dir.path().join("pool-size").display().to_string();
+    let dir_name = dir.path().to_str().unwrap();
+    with_vars(
+        vec![
+            ("CREDENTIALS_DIRECTORY", Some(dir_name)),
+            ("CRED_POOL_SIZE", Some("4")),
+        ],
+        || {
+            assert_eq!(
+                Credentials::from_env_with_prefix("CRED").unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "CRED_POOL_SIZE".into(),
+                    expected_type: "u32".into(),
+                    str_value: "eight".into(),
+                    origin: Origin::Credential(dir.path().join("pool-size")),
+                }
+            );
+        },
+    );
+    std::fs::remove_file(dir.path().join("pool-size")).unwrap();
+    with_vars(
+        vec![
+            ("CREDENTIALS_DIRECTORY", Some(dir_name)),
+            ("CRED_POOL_SIZE", Some("4")),
+        ],
+        || {
+            let error = Credentials::from_env_with_prefix("CRED").unwrap_err();
+            let FromEnvError::Custom { var_name, message } = error else {
+                panic!("unexpected error {:?}", error);
+            };
+            assert_eq!(var_name, Some(pool_size_path.clone()));
+            assert!(
+                message.starts_with("failed to read credential file"),
+                "{}",
+                message
+            );
+        },
+    );
+}
@@ -0,0 +1,102 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Pricing {
+    #[from_env(decimal_comma)]
+    price_factor: f64,
+    #[from_env(decimal_comma, default = "0,25")]
+    discount: f32,
+    #[from_env(decimal_comma)]
+    tax: Option<f64>,
+}
+
+#[test]
+fn test_decimal_comma() {
+    with_vars(
+        vec![
+            ("PRICE_FACTOR", Some("1,5")),
+            ("DISCOUNT", None),
+            ("TAX", Some("0.2")),
+        ],
+        || {
+            assert_eq!(
+                Pricing::from_env().unwrap(),
+                Pricing {
+                    price_factor: 1.5,
+                    discount: 0.25,
+                    tax: Some(0.2)
+                }
+            );
+        },
+    );
+    with_vars(
+        vec![
+            ("PRICE_FACTOR", Some("1.5")),
+            ("DISCOUNT", Some("0,1")),
+            ("TAX", Some("0,2")),
+        ],
+        || {
+            assert_eq!(
+                Pricing::from_env().unwrap(),
+                Pricing {
+                    price_factor: 1.5,
+                    discount: 0.1,
+                    tax: Some(0.2)
+                }
+            );
+        },
+    )
+}
+
+#[test]
+fn test_decimal_comma_ambiguous() {
+    with_vars(
+        vec![
+            ("PRICE_FACTOR", Some("1,500.2")),
+            ("DISCOUNT", None),
+            ("TAX", None),
+        ],
+        || {
+            let error = Pricing::from_env().unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "PRICE_FACTOR: ambiguous decimal separator in \"1,500.2\", use either a single ',' or '.'"
+            );
+        },
+    );
+    with_vars(
+        vec![
+            ("PRICE_FACTOR", Some("1,500,000")),
+            ("DISCOUNT", None),
+            ("TAX", None),
+        ],
+        || {
+            assert_eq!(
+                Pricing::from_env().unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "PRICE_FACTOR".into(),
+                    expected_type: "f64".into(),
+                    str_value: "1,500,000".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+        },
+    )
+}
+
+#[derive(Debug, FromEnv)]
+struct Strict {
+    #[allow(dead_code)]
+    price_factor: f64,
+}
+
+#[test]
+fn test_strict_by_default() {
+    with_vars(vec![("PRICE_FACTOR", Some("1,5"))], || {
+        assert!(matches!(
+            Strict::from_env().unwrap_err(),
+            FromEnvError::ParsingFailure { .. }
+        ));
+    })
+}
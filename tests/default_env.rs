@@ -0,0 +1,79 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct Config {
+    #[from_env(default_env = "AWS_REGION", default = "us-east-1")]
+    region: String,
+    #[from_env(default_env = "PLATFORM_TIMEOUT")]
+    timeout: u64,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Service {
+    app: Config,
+}
+
+fn load(vars: [Option<&str>; 4]) -> Result<Service, FromEnvError> {
+    with_vars(
+        [
+            ("APP_APP_REGION", vars[0]),
+            ("AWS_REGION", vars[1]),
+            ("APP_APP_TIMEOUT", vars[2]),
+            ("PLATFORM_TIMEOUT", vars[3]),
+        ],
+        Service::from_env,
+    )
+}
+
+#[test]
+fn test_default_env_read_whatever_the_prefix() {
+    assert_eq!(
+        load([None, Some("eu-west-3"), None, Some("30")])
+            .unwrap()
+            .app,
+        Config {
+            region: "eu-west-3".to_string(),
+            timeout: 30,
+        }
+    );
+}
+
+#[test]
+fn test_primary_variable_first_then_default() {
+    assert_eq!(
+        load([Some("ap-south-1"), Some("eu-west-3"), Some("5"), Some("30")])
+            .unwrap()
+            .app,
+        Config {
+            region: "ap-south-1".to_string(),
+            timeout: 5,
+        }
+    );
+    assert_eq!(
+        load([None, None, Some("5"), None]).unwrap().app.region,
+        "us-east-1"
+    );
+}
+
+#[test]
+fn test_default_env_errors() {
+    assert_eq!(
+        load([None, None, None, Some("soon")]).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "PLATFORM_TIMEOUT".to_string(),
+            str_value: "soon".to_string(),
+            origin: Origin::ProcessEnv,
+            expected_type: "u64".to_string(),
+        }
+    );
+    assert_eq!(
+        load([None, None, None, None]).unwrap_err(),
+        FromEnvError::MissingEnvVar {
+            var_name: "APP_APP_TIMEOUT".to_string(),
+            origin: Origin::ProcessEnv,
+            fallbacks: vec!["PLATFORM_TIMEOUT".to_string()],
+        }
+    );
+}
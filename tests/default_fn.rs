@@ -0,0 +1,99 @@
+use std::cell::Cell;
+
+use derive_from_env::{FromEnv, Location};
+use temp_env::with_vars;
+
+thread_local! {
+    static CALLS: Cell<u32> = const { Cell::new(0) };
+}
+
+fn workers() -> usize {
+    CALLS.with(|calls| calls.set(calls.get() + 1));
+    8
+}
+
+fn request_prefix() -> &'static str {
+    "req"
+}
+
+fn region() -> String {
+    "eu-west-1".to_string()
+}
+
+fn no_timeout() -> Option<u64> {
+    None
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(default_fn = "workers")]
+    workers: usize,
+    #[from_env(default_fn = "request_prefix")]
+    request_prefix: String,
+    #[from_env(default_fn = "region", var = "AWS_REGION")]
+    region: Option<String>,
+    #[from_env(default_fn = "no_timeout")]
+    timeout: Option<u64>,
+}
+
+#[test]
+fn test_default_fn_when_unset() {
+    with_vars(
+        vec![
+            ("WORKERS", None::<&str>),
+            ("REQUEST_PREFIX", None),
+            ("AWS_REGION", None),
+            ("TIMEOUT", None),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap(),
+                Config {
+                    workers: 8,
+                    request_prefix: "req".to_string(),
+                    region: Some("eu-west-1".to_string()),
+                    timeout: None,
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_default_fn_not_called_when_set() {
+    CALLS.with(|calls| calls.set(0));
+    with_vars(
+        vec![
+            ("WORKERS", Some("2")),
+            ("REQUEST_PREFIX", Some("job")),
+            ("AWS_REGION", Some("us-east-1")),
+            ("TIMEOUT", Some("30")),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap(),
+                Config {
+                    workers: 2,
+                    request_prefix: "job".to_string(),
+                    region: Some("us-east-1".to_string()),
+                    timeout: Some(30),
+                }
+            );
+        },
+    );
+    assert_eq!(CALLS.with(Cell::get), 0);
+}
+
+#[test]
+fn test_default_fn_specs_and_explain() {
+    let specs = Config::env_vars();
+    assert!(specs[0].has_default());
+    assert_eq!(specs[0].default, None);
+    with_vars(vec![("WORKERS", None::<&str>)], || {
+        let plan = Config::explain("workers").unwrap();
+        assert_eq!(
+            plan.resolved().unwrap().location,
+            Location::DefaultFn("workers".to_string())
+        );
+    });
+}
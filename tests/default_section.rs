@@ -0,0 +1,92 @@
+use derive_from_env::{FromEnv, FromEnvError};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Metrics {
+    endpoint: String,
+    interval: u64,
+    namespace: Option<String>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics {
+            endpoint: "localhost:9090".to_string(),
+            interval: 60,
+            namespace: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct Config {
+    port: u16,
+    #[from_env(flatten, default)]
+    metrics: Metrics,
+}
+
+#[test]
+fn test_unset_section() {
+    with_vars(vec![("APP_PORT", Some("8080"))], || {
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.metrics, Metrics::default());
+    })
+}
+
+#[test]
+fn test_set_section() {
+    with_vars(
+        vec![
+            ("APP_PORT", Some("8080")),
+            ("APP_METRICS_ENDPOINT", Some("metrics:9090")),
+            ("APP_METRICS_INTERVAL", Some("10")),
+        ],
+        || {
+            let config = Config::from_env().unwrap();
+            assert_eq!(
+                config.metrics,
+                Metrics {
+                    endpoint: "metrics:9090".to_string(),
+                    interval: 10,
+                    namespace: None,
+                }
+            );
+        },
+    )
+}
+
+#[test]
+fn test_optional_var_set_required_missing() {
+    with_vars(
+        vec![
+            ("APP_PORT", Some("8080")),
+            ("APP_METRICS_NAMESPACE", Some("app")),
+        ],
+        || {
+            let error = Config::from_env().unwrap_err();
+            assert!(matches!(
+                error,
+                FromEnvError::MissingEnvVar { var_name, .. } if var_name == "APP_METRICS_ENDPOINT"
+            ));
+        },
+    )
+}
+
+#[test]
+fn test_section_specs_not_required() {
+    let specs = Config::env_vars();
+    let endpoint = specs
+        .iter()
+        .find(|spec| spec.name == "APP_METRICS_ENDPOINT")
+        .unwrap();
+    assert!(!endpoint.required);
+    assert!(endpoint.has_default());
+    assert!(
+        specs
+            .iter()
+            .find(|spec| spec.name == "APP_PORT")
+            .unwrap()
+            .required
+    );
+}
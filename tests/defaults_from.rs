@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use derive_from_env::FromEnv;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(defaults_from = "Self")]
+struct Config {
+    #[from_env(default = "8080")]
+    port: u16,
+    workers: usize,
+    #[from_env(from = "u64")]
+    timeout: Secs,
+    name: String,
+    log_level: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Secs(Duration);
+
+impl From<u64> for Secs {
+    fn from(secs: u64) -> Self {
+        Secs(Duration::from_secs(secs))
+    }
+}
+
+impl ConfigDefaults for Config {
+    // Overridden by the `default` attribute.
+    fn default_port() -> Option<u16> {
+        Some(1)
+    }
+    fn default_workers() -> Option<usize> {
+        Some(std::thread::available_parallelism().map_or(1, usize::from) * 2)
+    }
+    fn default_timeout() -> Option<Secs> {
+        Some(Secs(Duration::from_millis(1500)))
+    }
+}
+
+/// Defaults shared by several structs.
+struct Shared;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(defaults_from = "Shared", all_defaultable)]
+struct Worker {
+    name: String,
+    retries: u8,
+}
+
+impl WorkerDefaults for Shared {
+    fn default_name() -> Option<String> {
+        Some(format!("worker-{}", 1))
+    }
+}
+
+const VARS: [&str; 6] = ["PORT", "WORKERS", "TIMEOUT", "NAME", "LOG_LEVEL", "RETRIES"];
+
+#[test]
+fn test_trait_defaults() {
+    let mut vars = VARS.map(|name| (name, None::<&str>));
+    vars[3].1 = Some("api");
+    with_vars(vars, || {
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.port, 8080);
+        assert!(config.workers >= 2);
+        assert_eq!(config.timeout, Secs(Duration::from_millis(1500)));
+        assert_eq!(config.log_level, None);
+    });
+    let mut vars = VARS.map(|name| (name, None::<&str>));
+    vars[0].1 = Some("9090");
+    vars[1].1 = Some("3");
+    vars[2].1 = Some("2");
+    vars[3].1 = Some("api");
+    with_vars(vars, || {
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.port, 9090);
+        assert_eq!(config.workers, 3);
+        assert_eq!(config.timeout, Secs(Duration::from_secs(2)));
+    });
+}
+
+#[test]
+fn test_missing_without_trait_default() {
+    with_vars(VARS.map(|name| (name, None::<&str>)), || {
+        let error = Config::from_env().unwrap_err();
+        assert_eq!(error.to_string(), "missing environment variable NAME");
+        assert_eq!(
+            Worker::from_env().unwrap(),
+            Worker {
+                name: "worker-1".to_string(),
+                retries: 0,
+            }
+        );
+    });
+}
+
+#[test]
+fn test_specs() {
+    let required = Config::env_vars()
+        .into_iter()
+        .filter(|spec| spec.required)
+        .map(|spec| spec.name)
+        .collect::<Vec<_>>();
+    assert_eq!(required, ["NAME"]);
+}
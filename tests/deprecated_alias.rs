@@ -0,0 +1,86 @@
+use std::sync::Mutex;
+
+use derive_from_env::{set_deprecation_hook, DeprecationNotice, FromEnv, Location};
+use temp_env::with_vars;
+
+static NOTICES: Mutex<Vec<DeprecationNotice>> = Mutex::new(Vec::new());
+
+fn record(notice: &DeprecationNotice) {
+    NOTICES.lock().unwrap().push(notice.clone());
+}
+
+/// Loads the configuration, returning the notices reported while loading it.
+fn load(
+    host: Option<&str>,
+    redis_host: Option<&str>,
+    cache_host: Option<&str>,
+) -> (CacheConfig, Vec<DeprecationNotice>) {
+    set_deprecation_hook(record);
+    with_vars(
+        [
+            ("CACHE_HOST", host),
+            ("CACHE_LEGACY_HOST", None),
+            ("CACHE_REDIS_HOST", redis_host),
+            ("MEMCACHED_HOST", cache_host),
+        ],
+        || {
+            NOTICES.lock().unwrap().clear();
+            let config = CacheConfig::from_env().unwrap();
+            (config, NOTICES.lock().unwrap().clone())
+        },
+    )
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "CACHE_")]
+struct CacheConfig {
+    #[from_env(
+        alias = "LEGACY_HOST",
+        deprecated_alias = "REDIS_HOST",
+        default_env = "MEMCACHED_HOST"
+    )]
+    host: String,
+}
+
+#[test]
+fn test_new_name_without_notice() {
+    let (config, notices) = load(Some("cache1"), Some("redis1"), None);
+    assert_eq!(config.host, "cache1");
+    assert_eq!(notices, []);
+}
+
+#[test]
+fn test_deprecated_name_with_notice() {
+    let (config, notices) = load(None, Some("redis1"), Some("memcached1"));
+    assert_eq!(config.host, "redis1");
+    assert_eq!(
+        notices,
+        [DeprecationNotice {
+            field: "CacheConfig.host".to_string(),
+            var_name: "CACHE_HOST".to_string(),
+            deprecated_name: "CACHE_REDIS_HOST".to_string(),
+        }]
+    );
+    assert_eq!(
+        notices[0].to_string(),
+        "CACHE_REDIS_HOST is deprecated, set CACHE_HOST instead (field CacheConfig.host)"
+    );
+}
+
+#[test]
+fn test_default_env_after_deprecated_name() {
+    let (config, notices) = load(None, None, Some("memcached1"));
+    assert_eq!(config.host, "memcached1");
+    assert_eq!(notices, []);
+}
+
+#[test]
+fn test_explain_deprecated_name() {
+    with_vars([("CACHE_REDIS_HOST", Some("redis1"))], || {
+        let plan = CacheConfig::explain("host").unwrap();
+        assert_eq!(
+            plan.steps[2].location,
+            Location::DeprecatedAlias("CACHE_REDIS_HOST".to_string())
+        );
+    });
+}
@@ -0,0 +1,92 @@
+use derive_from_env::{FieldDiff, FromEnv, FromEnvValue};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnvValue)]
+enum Mode {
+    Fast,
+    Safe,
+}
+
+#[derive(Debug, FromEnv)]
+#[from_env(diff)]
+struct Database {
+    host: String,
+    #[from_env(default = "5432")]
+    port: u16,
+    #[from_env(secret)]
+    password: String,
+}
+
+#[derive(Debug, FromEnv)]
+#[from_env(diff)]
+struct Config {
+    name: String,
+    mode: Mode,
+    database: Database,
+    #[from_env(var = "API_TOKEN", secret)]
+    token: Option<String>,
+}
+
+fn load(prefix: &str, vars: [(&str, &str); 5]) -> Config {
+    with_vars(
+        vars.iter()
+            .map(|(name, value)| (format!("{}_{}", prefix, name), Some(*value)))
+            .chain([("API_TOKEN".to_string(), Some(vars[4].1))])
+            .collect::<Vec<_>>(),
+        || Config::from_env_with_prefix(prefix).unwrap(),
+    )
+}
+
+#[test]
+fn test_diff() {
+    let blue = load(
+        "BLUE",
+        [
+            ("NAME", "api"),
+            ("MODE", "fast"),
+            ("DATABASE_HOST", "db-blue"),
+            ("DATABASE_PASSWORD", "hunter2"),
+            ("TOKEN", "t1"),
+        ],
+    );
+    let green = load(
+        "GREEN",
+        [
+            ("NAME", "api"),
+            ("MODE", "safe"),
+            ("DATABASE_HOST", "db-green"),
+            ("DATABASE_PASSWORD", "hunter3"),
+            ("TOKEN", "t2"),
+        ],
+    );
+    let diff = |field: &str, var_name: &str, old: &str, new: &str| FieldDiff {
+        field: field.into(),
+        var_name: var_name.into(),
+        old: old.into(),
+        new: new.into(),
+    };
+    assert_eq!(
+        blue.diff(&green),
+        [
+            diff("mode", "MODE", "Fast", "Safe"),
+            diff(
+                "database.host",
+                "DATABASE_HOST",
+                "\"db-blue\"",
+                "\"db-green\""
+            ),
+            diff(
+                "database.password",
+                "DATABASE_PASSWORD",
+                "<redacted>",
+                "<redacted>"
+            ),
+            diff("token", "API_TOKEN", "<redacted>", "<redacted>"),
+        ]
+    );
+    assert_eq!(
+        blue.diff_with_prefix(&green, "APP")[1].to_string(),
+        "database.host (APP_DATABASE_HOST): \"db-blue\" -> \"db-green\""
+    );
+    assert_eq!(blue.diff(&blue), []);
+}
@@ -0,0 +1,114 @@
+use derive_from_env::{DirSource, EnvSource, FromEnv, FromEnvError, Layered, ProcessEnv};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Mounted {
+    name: String,
+    replicas: Option<u8>,
+    db: Database,
+}
+
+fn config_dir() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("APP_NAME"), "mounted\n").unwrap();
+    std::fs::write(dir.path().join("app_db_host"), "db.internal").unwrap();
+    std::fs::write(dir.path().join("APP_DB_PORT"), "5432\n\n").unwrap();
+    std::fs::write(dir.path().join("..data"), "ignored").unwrap();
+    dir
+}
+
+#[test]
+fn test_dir_source() {
+    let dir = config_dir();
+    let source = DirSource::new(dir.path());
+    assert_eq!(source.get("APP_NAME").unwrap(), Some("mounted".into()));
+    // Only a single trailing newline is removed.
+    assert_eq!(source.get("APP_DB_PORT").unwrap(), Some("5432\n".into()));
+    assert_eq!(
+        source.get("APP_DB_HOST").unwrap(),
+        Some("db.internal".into())
+    );
+    assert_eq!(source.get("APP_REPLICAS").unwrap(), None);
+    assert_eq!(source.get("..data").unwrap(), None);
+    assert_eq!(
+        source.keys().unwrap(),
+        vec!["APP_DB_PORT", "APP_NAME", "app_db_host"]
+    );
+}
+
+#[test]
+fn test_dir_source_listing_cache() {
+    let dir = config_dir();
+    let source = DirSource::new(dir.path());
+    assert_eq!(source.get("APP_REPLICAS").unwrap(), None);
+    std::fs::write(dir.path().join("APP_REPLICAS"), "3").unwrap();
+    assert_eq!(source.get("APP_REPLICAS").unwrap(), None);
+    source.refresh();
+    assert_eq!(source.get("APP_REPLICAS").unwrap(), Some("3".into()));
+}
+
+#[test]
+fn test_dir_source_layered() {
+    let dir = config_dir();
+    std::fs::write(dir.path().join("APP_DB_PORT"), "5432\n").unwrap();
+    let source = Layered::new()
+        .layer(ProcessEnv)
+        .layer(DirSource::new(dir.path()));
+    with_vars(
+        vec![
+            ("APP_NAME", Some("from-env")),
+            ("APP_REPLICAS", Some("2")),
+            ("APP_DB_HOST", None),
+            ("APP_DB_PORT", None),
+        ],
+        || {
+            assert_eq!(
+                Mounted::from_source_with_prefix(&source, "APP").unwrap(),
+                Mounted {
+                    name: "from-env".into(),
+                    replicas: Some(2),
+                    db: Database {
+                        host: "db.internal".into(),
+                        port: 5432,
+                    },
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_dir_source_errors() {
+    let dir = config_dir();
+    std::fs::write(dir.path().join("APP_NAME"), [0xff, 0xfe]).unwrap();
+    let source = DirSource::new(dir.path());
+    let FromEnvError::SourceFailure { var_name, message } =
+        Mounted::from_source_with_prefix(&source, "APP").unwrap_err()
+    else {
+        panic!("expected a source failure");
+    };
+    assert_eq!(var_name, Some("APP_NAME".into()));
+    let path = dir.path().join("APP_NAME").display().to_string();
+    assert!(
+        message.starts_with(&format!("failed to read {}", path)),
+        "{}",
+        message
+    );
+
+    let missing = dir.path().join("missing");
+    let source = DirSource::new(&missing);
+    let FromEnvError::SourceFailure { message, .. } = source.get("APP_NAME").unwrap_err() else {
+        panic!("expected a source failure");
+    };
+    assert!(
+        message.starts_with(&format!("failed to list {}", missing.display())),
+        "{}",
+        message
+    );
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "duration")]
+
+use std::time::Duration;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    timeout: Duration,
+    #[from_env(default = "30s")]
+    keepalive: std::time::Duration,
+    retry_delay: Option<Duration>,
+}
+
+fn load(timeout: &str, retry_delay: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![("TIMEOUT", Some(timeout)), ("RETRY_DELAY", retry_delay)],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_duration() {
+    let config = load("1h30m", None).unwrap();
+    assert_eq!(config.timeout, Duration::from_secs(5400));
+    assert_eq!(config.keepalive, Duration::from_secs(30));
+    assert_eq!(config.retry_delay, None);
+    let config = load("250ms", Some("1m 5s")).unwrap();
+    assert_eq!(config.timeout, Duration::from_millis(250));
+    assert_eq!(config.retry_delay, Some(Duration::from_secs(65)));
+    assert_eq!(load("0", None).unwrap().timeout, Duration::ZERO);
+    assert_eq!(
+        load("2d", None).unwrap().timeout,
+        Duration::from_secs(172800)
+    );
+    assert_eq!(
+        load("10us", None).unwrap().timeout,
+        Duration::from_micros(10)
+    );
+}
+
+#[test]
+fn test_duration_failures() {
+    for value in ["30", "", "5 minutes", "1.5s", "-1s", "s"] {
+        assert_eq!(
+            load(value, None).unwrap_err(),
+            FromEnvError::ParsingFailure {
+                var_name: "TIMEOUT".into(),
+                expected_type: "duration (numbers followed by d, h, m, s, ms, us or ns, e.g. 30s, \
+                    5m, 1h30m or 250ms)"
+                    .into(),
+                str_value: value.into(),
+                origin: Origin::ProcessEnv,
+            },
+            "{:?}",
+            value
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use serde::Deserialize;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, Deserialize, FromEnv)]
+#[from_env(compat = "envy")]
+struct Limits {
+    region: String,
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromEnv)]
+#[from_env(compat = "envy")]
+struct EnvyConfig {
+    host: String,
+    port: u16,
+    debug: Option<bool>,
+    name: Option<String>,
+    #[serde(flatten)]
+    limits: Limits,
+}
+
+#[test]
+fn test_envy_same_variables() {
+    with_vars(
+        vec![
+            ("APP_HOST", Some("localhost")),
+            ("APP_port", Some("8080")),
+            ("APP_REGION", Some("eu")),
+            ("APP_NAME", Some("")),
+            ("HOST", Some("ignored")),
+        ],
+        || {
+            let expected = envy::prefixed("APP_").from_env::<EnvyConfig>().unwrap();
+            assert_eq!(EnvyConfig::from_env_with_prefix("APP_").unwrap(), expected);
+            assert_eq!(
+                expected,
+                EnvyConfig {
+                    host: "localhost".into(),
+                    port: 8080,
+                    debug: None,
+                    name: Some("".into()),
+                    limits: Limits {
+                        region: "eu".into()
+                    }
+                }
+            );
+        },
+    )
+}
+
+#[test]
+fn test_envy_without_prefix() {
+    with_vars(
+        vec![
+            ("host", Some("localhost")),
+            ("PORT", Some("80")),
+            ("Region", Some("us")),
+        ],
+        || {
+            let expected = envy::from_env::<EnvyConfig>().unwrap();
+            assert_eq!(EnvyConfig::from_env().unwrap(), expected);
+        },
+    )
+}
+
+#[test]
+fn test_envy_errors() {
+    with_vars(
+        vec![
+            ("APP_HOST", Some("localhost")),
+            ("APP_REGION", Some("eu")),
+            ("APP_PORT", None),
+        ],
+        || {
+            assert_eq!(
+                EnvyConfig::from_env_with_prefix("APP_").unwrap_err(),
+                FromEnvError::MissingEnvVar {
+                    var_name: "APP_PORT".into(),
+                    origin: Origin::ProcessEnv,
+                    fallbacks: Vec::new(),
+                }
+            );
+        },
+    );
+    with_vars(
+        vec![
+            ("APP_HOST", Some("localhost")),
+            ("APP_Port", Some("http")),
+            ("APP_REGION", Some("eu")),
+        ],
+        || {
+            assert_eq!(
+                EnvyConfig::from_env_with_prefix("APP_").unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "APP_Port".into(),
+                    expected_type: "u16".into(),
+                    str_value: "http".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+        },
+    )
+}
+
+#[test]
+fn test_envy_several_matches() {
+    with_vars(
+        vec![
+            ("APP_HOST", Some("localhost")),
+            ("APP_port", Some("80")),
+            ("APP_PORT", Some("8080")),
+            ("APP_REGION", Some("eu")),
+        ],
+        || {
+            assert_eq!(EnvyConfig::from_env_with_prefix("APP_").unwrap().port, 8080);
+        },
+    );
+    with_vars(
+        vec![
+            ("APP_HOST", Some("localhost")),
+            ("APP_port", Some("80")),
+            ("APP_Port", Some("8080")),
+            ("APP_PORT", None),
+            ("APP_REGION", Some("eu")),
+        ],
+        || {
+            assert_eq!(
+                EnvyConfig::from_env_with_prefix("APP_").unwrap_err(),
+                FromEnvError::Custom {
+                    var_name: Some("APP_PORT".into()),
+                    message: "ambiguous variables APP_Port, APP_port for field port".into(),
+                }
+            );
+        },
+    );
+}
@@ -0,0 +1,78 @@
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use derive_from_env::_inner_helpers::exe_prefix;
+use derive_from_env::{EnvSource, FromEnv, FromEnvError, ReaderSource};
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    db_host: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "DB_")]
+struct Database {
+    host: String,
+}
+
+/// Source faking the path of the executable.
+struct Persona {
+    exe: PathBuf,
+    vars: ReaderSource,
+}
+
+impl Persona {
+    fn new(exe: &str) -> Self {
+        let input = "MY_TOOL_DB_HOST=tool\nMY_TOOL_V2_DB_HOST=tool-v2\nOTHER_DB_HOST=other\n";
+        Persona {
+            exe: PathBuf::from(exe),
+            vars: ReaderSource::from_reader(Cursor::new(input)).unwrap(),
+        }
+    }
+}
+
+impl EnvSource for Persona {
+    fn get(&self, key: &str) -> Result<Option<String>, FromEnvError> {
+        self.vars.get(key)
+    }
+    fn current_exe(&self) -> Result<PathBuf, FromEnvError> {
+        Ok(self.exe.clone())
+    }
+}
+
+#[test]
+fn test_exe_prefix_normalization() {
+    assert_eq!(exe_prefix(Path::new("/usr/bin/other")).unwrap(), "OTHER");
+    assert_eq!(
+        exe_prefix(Path::new("/usr/bin/my-tool")).unwrap(),
+        "MY_TOOL"
+    );
+    assert_eq!(
+        exe_prefix(Path::new("my-tool.v2.exe")).unwrap(),
+        "MY_TOOL_V2"
+    );
+    assert_eq!(
+        exe_prefix(Path::new("/")),
+        Err(FromEnvError::SourceFailure {
+            var_name: None,
+            message: "failed to determine the name of the executable from \"/\"".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_exe_prefix() {
+    for (exe, host) in [
+        ("/usr/bin/my-tool", "tool"),
+        ("/usr/bin/my-tool-v2", "tool-v2"),
+        ("/opt/my.tool.exe", "tool"),
+        ("/usr/bin/other", "other"),
+    ] {
+        let config = Config::from_source_with_exe_prefix(&Persona::new(exe)).unwrap();
+        assert_eq!(config.db_host, host);
+    }
+    // Combined with the struct prefix like any prefix.
+    let database = Database::from_source_with_exe_prefix(&Persona::new("my-tool")).unwrap();
+    assert_eq!(database.host, "tool");
+    assert!(Config::from_source_with_exe_prefix(&Persona::new("unknown")).is_err());
+}
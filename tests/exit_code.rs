@@ -0,0 +1,50 @@
+//! Runs itself as a child process to check the exit code and output of a `main` returning a
+//! [`Report`].
+
+use std::process::{Command, ExitCode, Termination};
+
+use derive_from_env::{FromEnv, MainResult};
+
+const CHILD_MARKER: &str = "DERIVE_FROM_ENV_EXIT_CODE_CHILD";
+
+#[derive(FromEnv)]
+struct Config {
+    #[allow(dead_code)]
+    port: u16,
+}
+
+fn run() -> MainResult {
+    Config::from_env_with_prefix("EXIT_CODE_TEST")?;
+    Ok(())
+}
+
+fn child_main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(report) => report.report(),
+    }
+}
+
+fn main() -> ExitCode {
+    if std::env::var_os(CHILD_MARKER).is_some() {
+        return child_main();
+    }
+    let output = Command::new(std::env::current_exe().unwrap())
+        .env(CHILD_MARKER, "1")
+        .env("EXIT_CODE_TEST_PORT", "http")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(78));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: failed to load the configuration from the environment\n  invalid value\n    variable: EXIT_CODE_TEST_PORT\n    value:    \"http\"\n    expected: u16\n"
+    );
+    let output = Command::new(std::env::current_exe().unwrap())
+        .env(CHILD_MARKER, "1")
+        .env("EXIT_CODE_TEST_PORT", "8080")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    println!("exit code tests passed");
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,109 @@
+use derive_from_env::{FromEnv, Location, Origin, ResolutionStep};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Database {
+    #[from_env(alias_var = "DB_HOST", default = "localhost")]
+    host: String,
+    #[from_env(secret, alias_var = "DB_PASS")]
+    password: Option<String>,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(default_env = "LOG_LEVEL", default = "info")]
+    api_log_level: String,
+    database: Database,
+}
+
+fn variable(location: Location, value: Option<&str>) -> ResolutionStep {
+    ResolutionStep {
+        location,
+        origin: Some(Origin::ProcessEnv),
+        value: value.map(str::to_string),
+    }
+}
+
+const VARS: [&str; 5] = [
+    "API_LOG_LEVEL",
+    "LOG_LEVEL",
+    "DATABASE_HOST",
+    "DB_HOST",
+    "DB_PASS",
+];
+
+#[test]
+fn test_fallback_variables() {
+    let mut vars = VARS.map(|name| (name, None::<&str>));
+    vars[1].1 = Some("debug");
+    vars[3].1 = Some("db1");
+    with_vars(vars, || {
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.api_log_level, "debug");
+        assert_eq!(config.database.host, "db1");
+    });
+    with_vars(VARS.map(|name| (name, Some("set"))), || {
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.api_log_level, "set");
+        assert_eq!(config.database.password.as_deref(), Some("set"));
+    });
+    with_vars(VARS.map(|name| (name, None::<&str>)), || {
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.api_log_level, "info");
+        assert_eq!(config.database.host, "localhost");
+        assert_eq!(config.database.password, None);
+    });
+}
+
+#[test]
+fn test_explain() {
+    let mut vars = VARS.map(|name| (name, None::<&str>));
+    vars[1].1 = Some("debug");
+    vars[3].1 = Some("db1");
+    vars[4].1 = Some("hunter2");
+    with_vars(vars, || {
+        let plan = Config::explain("api_log_level").unwrap();
+        assert_eq!(plan.field, "api_log_level");
+        assert_eq!(
+            plan.steps,
+            [
+                variable(Location::Variable("API_LOG_LEVEL".to_string()), None),
+                variable(Location::DefaultEnv("LOG_LEVEL".to_string()), Some("debug")),
+                ResolutionStep {
+                    location: Location::Default,
+                    origin: None,
+                    value: Some("info".to_string()),
+                },
+            ]
+        );
+        assert_eq!(plan.resolved(), Some(&plan.steps[1]));
+
+        let plan = Config::explain("database.host").unwrap();
+        assert_eq!(
+            plan.to_string(),
+            "database.host:\n\
+             1. variable DATABASE_HOST in process environment: not set\n\
+             2. alias DB_HOST in process environment: \"db1\" <- used\n\
+             3. default attribute: \"localhost\""
+        );
+
+        let plan = Config::explain("database.password").unwrap();
+        assert_eq!(
+            plan.steps[1],
+            variable(Location::Alias("DB_PASS".to_string()), Some("<redacted>"))
+        );
+
+        assert_eq!(Config::explain("database"), None);
+        assert_eq!(Config::explain("database.port"), None);
+    });
+}
+
+#[test]
+fn test_explain_unset() {
+    with_vars(VARS.map(|name| (name, None::<&str>)), || {
+        let plan = Config::explain("api_log_level").unwrap();
+        assert_eq!(plan.resolved().unwrap().location, Location::Default);
+        let plan = Config::explain("database.password").unwrap();
+        assert_eq!(plan.resolved(), None);
+    });
+}
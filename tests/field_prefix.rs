@@ -0,0 +1,79 @@
+use derive_from_env::FromEnv;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct AppConfig {
+    #[from_env(prefix = "OBSERVABILITY")]
+    sentry_dsn: String,
+    #[from_env(prefix = "OBSERVABILITY", alias = "TRACES_RATE", default = "0.1")]
+    sample_rate: f64,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Deployment {
+    app: AppConfig,
+}
+
+#[test]
+fn test_field_prefix_segment() {
+    with_vars(
+        [
+            (
+                "APP_OBSERVABILITY_SENTRY_DSN",
+                Some("https://key@sentry.io/1"),
+            ),
+            ("APP_OBSERVABILITY_SAMPLE_RATE", None),
+            ("APP_OBSERVABILITY_TRACES_RATE", Some("0.5")),
+            ("APP_NAME", Some("api")),
+        ],
+        || {
+            assert_eq!(
+                AppConfig::from_env().unwrap(),
+                AppConfig {
+                    sentry_dsn: "https://key@sentry.io/1".to_string(),
+                    sample_rate: 0.5,
+                    name: "api".to_string(),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_field_prefix_segment_under_nested_prefix() {
+    with_vars(
+        [
+            (
+                "APP_APP_OBSERVABILITY_SENTRY_DSN",
+                Some("https://key@sentry.io/2"),
+            ),
+            ("APP_APP_OBSERVABILITY_SAMPLE_RATE", None),
+            ("APP_APP_OBSERVABILITY_TRACES_RATE", None),
+            ("APP_APP_NAME", Some("worker")),
+        ],
+        || {
+            assert_eq!(
+                Deployment::from_env().unwrap().app,
+                AppConfig {
+                    sentry_dsn: "https://key@sentry.io/2".to_string(),
+                    sample_rate: 0.1,
+                    name: "worker".to_string(),
+                }
+            );
+        },
+    );
+    assert_eq!(
+        AppConfig::env_vars()
+            .into_iter()
+            .map(|spec| spec.name)
+            .collect::<Vec<_>>(),
+        [
+            "APP_OBSERVABILITY_SENTRY_DSN",
+            "APP_OBSERVABILITY_SAMPLE_RATE",
+            "APP_OBSERVABILITY_TRACES_RATE",
+            "APP_NAME"
+        ]
+    );
+}
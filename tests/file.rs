@@ -0,0 +1,190 @@
+use derive_from_env::{FromEnv, FromEnvError, Location, Origin, ProcessEnv, SandboxSource};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "DB_")]
+struct DbConfig {
+    #[from_env(file)]
+    password: String,
+    #[from_env(file, default = "5")]
+    pool_size: u32,
+    #[from_env(file)]
+    token: Option<String>,
+}
+
+#[test]
+fn test_value_from_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let password = dir.path().join("db_password");
+    std::fs::write(&password, "s3cret\n\n").unwrap();
+    let pool_size = dir.path().join("pool_size");
+    std::fs::write(&pool_size, "8\r\n").unwrap();
+    with_vars(
+        [
+            ("DB_PASSWORD", None),
+            ("DB_PASSWORD_FILE", password.to_str()),
+            ("DB_POOL_SIZE", None),
+            ("DB_POOL_SIZE_FILE", pool_size.to_str()),
+            ("DB_TOKEN", None),
+            ("DB_TOKEN_FILE", None),
+        ],
+        || {
+            assert_eq!(
+                DbConfig::from_env().unwrap(),
+                DbConfig {
+                    password: "s3cret\n".to_string(),
+                    pool_size: 8,
+                    token: None,
+                }
+            );
+            let plan = DbConfig::explain("password").unwrap();
+            assert_eq!(
+                plan.steps[1].location,
+                Location::File("DB_PASSWORD_FILE".to_string())
+            );
+            assert_eq!(plan.steps[1].value.as_deref(), Some("s3cret\n"));
+        },
+    );
+}
+
+#[test]
+fn test_variable_takes_precedence() {
+    with_vars(
+        [
+            ("DB_PASSWORD", Some("from-env")),
+            ("DB_PASSWORD_FILE", Some("/nonexistent/db_password")),
+            ("DB_POOL_SIZE", None),
+            ("DB_POOL_SIZE_FILE", None),
+            ("DB_TOKEN", None),
+            ("DB_TOKEN_FILE", None),
+        ],
+        || {
+            assert_eq!(
+                DbConfig::from_env().unwrap(),
+                DbConfig {
+                    password: "from-env".to_string(),
+                    pool_size: 5,
+                    token: None,
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_file_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let pool_size = dir.path().join("pool_size");
+    std::fs::write(&pool_size, "eight\n").unwrap();
+    let missing = dir.path().join("db_password");
+    with_vars(
+        [
+            ("DB_PASSWORD", None),
+            ("DB_PASSWORD_FILE", missing.to_str()),
+            ("DB_POOL_SIZE", None),
+            ("DB_POOL_SIZE_FILE", None),
+            ("DB_TOKEN", None),
+            ("DB_TOKEN_FILE", None),
+        ],
+        || {
+            assert_eq!(
+                DbConfig::from_env().unwrap_err(),
+                FromEnvError::FileReadFailure {
+                    var_name: "DB_PASSWORD_FILE".to_string(),
+                    path: missing.clone(),
+                    kind: std::io::ErrorKind::NotFound,
+                }
+            );
+        },
+    );
+    with_vars(
+        [
+            ("DB_PASSWORD", Some("s3cret")),
+            ("DB_PASSWORD_FILE", None),
+            ("DB_POOL_SIZE", None),
+            ("DB_POOL_SIZE_FILE", pool_size.to_str()),
+            ("DB_TOKEN", None),
+            ("DB_TOKEN_FILE", None),
+        ],
+        || {
+            assert_eq!(
+                DbConfig::from_env().unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "DB_POOL_SIZE".to_string(),
+                    expected_type: "u32".to_string(),
+                    str_value: "eight".to_string(),
+                    origin: Origin::File(pool_size.clone()),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_missing_variable_and_file() {
+    with_vars(
+        [
+            ("DB_PASSWORD", None::<&str>),
+            ("DB_PASSWORD_FILE", None),
+            ("DB_POOL_SIZE", None),
+            ("DB_POOL_SIZE_FILE", None),
+            ("DB_TOKEN", None),
+            ("DB_TOKEN_FILE", None),
+        ],
+        || {
+            let error = DbConfig::from_env().unwrap_err();
+            assert_eq!(
+                error,
+                FromEnvError::MissingEnvVar {
+                    var_name: "DB_PASSWORD".to_string(),
+                    origin: Origin::ProcessEnv,
+                    fallbacks: vec!["DB_PASSWORD_FILE".to_string()],
+                }
+            );
+            assert_eq!(
+                error.to_string(),
+                "missing environment variable DB_PASSWORD (also tried DB_PASSWORD_FILE)"
+            );
+        },
+    );
+}
+
+#[test]
+fn test_specs_list_the_file_variables() {
+    let specs = DbConfig::env_vars();
+    assert_eq!(
+        specs
+            .iter()
+            .map(|spec| (spec.name.as_str(), spec.type_name.as_str(), spec.required))
+            .collect::<Vec<_>>(),
+        [
+            ("DB_PASSWORD", "String", true),
+            ("DB_PASSWORD_FILE", "path of a file holding String", false),
+            ("DB_POOL_SIZE", "u32", false),
+            ("DB_POOL_SIZE_FILE", "path of a file holding u32", false),
+            ("DB_TOKEN", "String", false),
+            ("DB_TOKEN_FILE", "path of a file holding String", false),
+        ]
+    );
+    let source = SandboxSource::from_specs(ProcessEnv, &specs).strict();
+    with_vars(
+        [
+            ("DB_PASSWORD", Some("s3cret")),
+            ("DB_PASSWORD_FILE", None),
+            ("DB_POOL_SIZE", None),
+            ("DB_POOL_SIZE_FILE", None),
+            ("DB_TOKEN", None),
+            ("DB_TOKEN_FILE", None),
+        ],
+        || {
+            assert_eq!(
+                DbConfig::from_source(&source).unwrap(),
+                DbConfig {
+                    password: "s3cret".to_string(),
+                    pool_size: 5,
+                    token: None,
+                }
+            );
+        },
+    );
+}
@@ -0,0 +1,151 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use std::path::Path;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Database {
+    password: String,
+    #[from_env(default = "5")]
+    pool_size: u32,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_", file_suffix = "_PATH")]
+struct AppConfig {
+    api_key: String,
+    #[from_env(no_file)]
+    region: Option<String>,
+    database: Database,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "MIG_", file_conflict = "error")]
+struct Migration {
+    #[from_env(file)]
+    token: String,
+    #[from_env(file, default = "1")]
+    version: u32,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "PF_", file_suffix = "_FILE", file_conflict = "prefer_file")]
+struct PreferFile {
+    token: String,
+}
+
+fn write(dir: &Path, name: &str, content: &str) -> String {
+    let path = dir.join(name);
+    std::fs::write(&path, content).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_struct_and_nested_fields_read_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let api_key = write(dir.path(), "api_key", "k3y\n");
+    let password = write(dir.path(), "password", "s3cret\n");
+    let region = write(dir.path(), "region", "eu\n");
+    with_vars(
+        [
+            ("APP_API_KEY", None),
+            ("APP_API_KEY_PATH", Some(api_key.as_str())),
+            ("APP_REGION", None),
+            ("APP_REGION_PATH", Some(region.as_str())),
+            ("APP_DATABASE_PASSWORD", None),
+            ("APP_DATABASE_PASSWORD_PATH", Some(password.as_str())),
+            ("APP_DATABASE_POOL_SIZE", Some("8")),
+            ("APP_DATABASE_POOL_SIZE_PATH", None),
+        ],
+        || {
+            assert_eq!(
+                AppConfig::from_env().unwrap(),
+                AppConfig {
+                    api_key: "k3y".to_string(),
+                    region: None,
+                    database: Database {
+                        password: "s3cret".to_string(),
+                        pool_size: 8,
+                    },
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_specs_list_the_file_variables() {
+    assert_eq!(
+        AppConfig::env_vars()
+            .into_iter()
+            .map(|spec| spec.name)
+            .collect::<Vec<_>>(),
+        [
+            "APP_API_KEY",
+            "APP_API_KEY_PATH",
+            "APP_REGION",
+            "APP_DATABASE_PASSWORD",
+            "APP_DATABASE_PASSWORD_PATH",
+            "APP_DATABASE_POOL_SIZE",
+            "APP_DATABASE_POOL_SIZE_PATH",
+        ]
+    );
+}
+
+#[test]
+fn test_missing_variable_lists_the_file() {
+    with_vars(
+        [("APP_API_KEY", None::<&str>), ("APP_API_KEY_PATH", None)],
+        || {
+            assert_eq!(
+                AppConfig::from_env().unwrap_err(),
+                FromEnvError::MissingEnvVar {
+                    var_name: "APP_API_KEY".to_string(),
+                    origin: Origin::ProcessEnv,
+                    fallbacks: vec!["APP_API_KEY_PATH".to_string()],
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_conflict_policies() {
+    let dir = tempfile::tempdir().unwrap();
+    let token = write(dir.path(), "token", "from-file\n");
+    with_vars(
+        [
+            ("MIG_TOKEN", Some("from-env")),
+            ("MIG_TOKEN_FILE", Some(token.as_str())),
+            ("MIG_VERSION", None),
+            ("MIG_VERSION_FILE", None),
+        ],
+        || {
+            let error = Migration::from_env().unwrap_err();
+            assert_eq!(
+                error,
+                FromEnvError::FileConflict {
+                    var_name: "MIG_TOKEN".to_string(),
+                    file_var: "MIG_TOKEN_FILE".to_string(),
+                }
+            );
+            assert_eq!(
+                error.to_string(),
+                "both MIG_TOKEN and MIG_TOKEN_FILE are set, set only one of them"
+            );
+        },
+    );
+    with_vars(
+        [
+            ("PF_TOKEN", Some("from-env")),
+            ("PF_TOKEN_FILE", Some(token.as_str())),
+        ],
+        || {
+            assert_eq!(
+                PreferFile::from_env().unwrap(),
+                PreferFile {
+                    token: "from-file".to_string(),
+                }
+            );
+        },
+    );
+}
@@ -0,0 +1,48 @@
+use derive_from_env::{FromEnv, Location};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct Config {
+    #[from_env(flag)]
+    enable_tracing: bool,
+    #[from_env(flag, var = "NO_COLOR")]
+    no_color: bool,
+}
+
+#[test]
+fn test_flag() {
+    for value in ["1", "false", "", "anything"] {
+        with_vars(
+            vec![("APP_ENABLE_TRACING", Some(value)), ("NO_COLOR", None)],
+            || {
+                let config = Config::from_env().unwrap();
+                assert!(config.enable_tracing, "{:?}", value);
+                assert!(!config.no_color);
+            },
+        );
+    }
+    with_vars(
+        vec![("APP_ENABLE_TRACING", None::<&str>), ("NO_COLOR", Some(""))],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap(),
+                Config {
+                    enable_tracing: false,
+                    no_color: true
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_flag_specs_and_plan() {
+    let specs = Config::env_vars();
+    assert_eq!(specs[0].name, "APP_ENABLE_TRACING");
+    assert!(!specs[0].required);
+    with_vars(vec![("APP_ENABLE_TRACING", None::<&str>)], || {
+        let plan = Config::explain("enable_tracing").unwrap();
+        assert_eq!(plan.resolved().unwrap().location, Location::TypeDefault);
+    });
+}
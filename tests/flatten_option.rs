@@ -0,0 +1,94 @@
+use derive_from_env::{FromEnv, FromEnvError};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct TlsConfig {
+    cert_path: String,
+    key_path: String,
+    #[from_env(default = "1.2")]
+    min_version: String,
+    ciphers: Option<String>,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct Config {
+    port: u16,
+    #[from_env(flatten)]
+    tls: Option<TlsConfig>,
+}
+
+#[test]
+fn test_unset_section() {
+    with_vars(
+        vec![
+            ("APP_PORT", Some("8080")),
+            // Variables with a default or of `Option` fields don't count.
+            ("APP_TLS_MIN_VERSION", Some("1.3")),
+            ("APP_TLS_CIPHERS", Some("AES256")),
+        ],
+        || {
+            let config = Config::from_env().unwrap();
+            assert_eq!(config.tls, None);
+        },
+    )
+}
+
+#[test]
+fn test_set_section() {
+    with_vars(
+        vec![
+            ("APP_PORT", Some("8080")),
+            ("APP_TLS_CERT_PATH", Some("cert.pem")),
+            ("APP_TLS_KEY_PATH", Some("key.pem")),
+        ],
+        || {
+            let config = Config::from_env().unwrap();
+            assert_eq!(
+                config.tls,
+                Some(TlsConfig {
+                    cert_path: "cert.pem".to_string(),
+                    key_path: "key.pem".to_string(),
+                    min_version: "1.2".to_string(),
+                    ciphers: None,
+                })
+            );
+        },
+    )
+}
+
+#[test]
+fn test_partially_set_section() {
+    with_vars(
+        vec![
+            ("APP_PORT", Some("8080")),
+            ("APP_TLS_CERT_PATH", Some("cert.pem")),
+            ("APP_TLS_KEY_PATH", None),
+        ],
+        || {
+            let error = Config::from_env().unwrap_err();
+            assert!(matches!(error, FromEnvError::Custom { .. }));
+            assert_eq!(
+                error.to_string(),
+                "partially set section: APP_TLS_CERT_PATH set but APP_TLS_KEY_PATH missing"
+            );
+        },
+    )
+}
+
+#[test]
+fn test_section_specs_not_required() {
+    let specs = Config::env_vars();
+    let cert_path = specs
+        .iter()
+        .find(|spec| spec.name == "APP_TLS_CERT_PATH")
+        .unwrap();
+    assert_eq!(cert_path.field, "tls.cert_path");
+    assert!(!cert_path.required);
+    assert!(cert_path.optional);
+    let min_version = specs
+        .iter()
+        .find(|spec| spec.name == "APP_TLS_MIN_VERSION")
+        .unwrap();
+    assert!(min_version.has_default());
+}
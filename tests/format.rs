@@ -0,0 +1,84 @@
+#![cfg(all(feature = "yaml", feature = "toml"))]
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use serde::Deserialize;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Limits {
+    max: u32,
+    burst: Option<u32>,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(format = "yaml")]
+    limits: Limits,
+    #[from_env(format = "toml", default = "max = 1")]
+    quotas: Limits,
+    #[from_env(format = "json")]
+    fallback: Option<Limits>,
+}
+
+fn load(limits: &str, quotas: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![
+            ("LIMITS", Some(limits)),
+            ("QUOTAS", quotas),
+            ("FALLBACK", Some(r#"{"max": 3}"#)),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_format() {
+    let config = load("max: 5\nburst: 10", None).unwrap();
+    assert_eq!(
+        config.limits,
+        Limits {
+            max: 5,
+            burst: Some(10)
+        }
+    );
+    assert_eq!(
+        config.quotas,
+        Limits {
+            max: 1,
+            burst: None
+        }
+    );
+    assert_eq!(
+        config.fallback,
+        Some(Limits {
+            max: 3,
+            burst: None
+        })
+    );
+    let config = load("{max: 2}", Some("max = 7\nburst = 8")).unwrap();
+    assert_eq!(config.limits.max, 2);
+    assert_eq!(config.quotas.burst, Some(8));
+}
+
+#[test]
+fn test_format_failures() {
+    assert_eq!(
+        load("burst: 10", None).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "LIMITS".into(),
+            expected_type: "YAML Limits (missing field `max`)".into(),
+            str_value: "burst: 10".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+    assert_eq!(
+        load("max: 1", Some("max = 1\nburst = \"x\"")).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "QUOTAS".into(),
+            expected_type:
+                "TOML Limits (invalid type: string \"x\", expected u32 at line 2 column 9)".into(),
+            str_value: "max = 1\nburst = \"x\"".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+}
@@ -0,0 +1,57 @@
+use std::env::VarError;
+
+use derive_from_env::{from_env_test, FromEnv};
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    port: u16,
+    debug: Option<bool>,
+}
+
+#[test]
+#[from_env_test(vars("APP_PORT" = "8080", "APP_DEBUG" = "true"))]
+fn test_vars_are_set() {
+    assert_eq!(
+        Config::from_env_with_prefix("APP").unwrap(),
+        Config {
+            port: 8080,
+            debug: Some(true)
+        }
+    );
+}
+
+#[test]
+#[from_env_test(vars("APP_PORT" = "80"), unset("APP_DEBUG"))]
+fn test_vars_are_unset() {
+    assert_eq!(
+        Config::from_env_with_prefix("APP").unwrap(),
+        Config {
+            port: 80,
+            debug: None
+        }
+    );
+}
+
+#[from_env_test(vars("APP_PORT" = "8080"))]
+#[tokio::test]
+async fn test_async_body() {
+    tokio::task::yield_now().await;
+    assert_eq!(Config::from_env_with_prefix("APP").unwrap().port, 8080);
+}
+
+#[from_env_test(vars("GUARD_PANIC_SET" = "during"), unset("GUARD_PANIC_UNSET"))]
+fn panicking_test() {
+    assert_eq!(std::env::var("GUARD_PANIC_SET").unwrap(), "during");
+    panic!("test failure");
+}
+
+#[test]
+fn test_restore_on_panic() {
+    std::env::set_var("GUARD_PANIC_UNSET", "before");
+    assert!(std::panic::catch_unwind(panicking_test).is_err());
+    assert_eq!(std::env::var("GUARD_PANIC_SET"), Err(VarError::NotPresent));
+    assert_eq!(std::env::var("GUARD_PANIC_UNSET").unwrap(), "before");
+    // The lock is usable again after a panicking test.
+    assert!(std::panic::catch_unwind(panicking_test).is_err());
+    assert_eq!(std::env::var("GUARD_PANIC_UNSET").unwrap(), "before");
+}
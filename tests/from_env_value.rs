@@ -0,0 +1,126 @@
+use derive_from_env::{FromEnv, FromEnvError, FromEnvValue, Origin, ParseValueError};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnvValue)]
+enum Environment {
+    Development,
+    #[from_env(alias = "stage", alias = "preprod")]
+    Staging,
+    #[from_env(rename = "prod")]
+    Production,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Deployment {
+    environment: Environment,
+    previous: Option<Environment>,
+    #[from_env(default = "development")]
+    fallback: Environment,
+}
+
+#[test]
+fn test_from_str() {
+    assert_eq!("development".parse(), Ok(Environment::Development));
+    assert_eq!("DEVELOPMENT".parse(), Ok(Environment::Development));
+    assert_eq!("Staging".parse(), Ok(Environment::Staging));
+    assert_eq!("PreProd".parse(), Ok(Environment::Staging));
+    assert_eq!("stage".parse(), Ok(Environment::Staging));
+    assert_eq!("PROD".parse(), Ok(Environment::Production));
+    assert_eq!(
+        "production".parse::<Environment>(),
+        Err(ParseValueError {
+            value: "production".into(),
+            expected: &["Development", "Staging", "prod"]
+        })
+    );
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(Environment::Development.to_string(), "Development");
+    assert_eq!(Environment::Production.to_string(), "prod");
+    assert_eq!(
+        "x".parse::<Environment>().unwrap_err().to_string(),
+        "invalid value \"x\", expected one of: Development, Staging, prod"
+    );
+}
+
+#[test]
+fn test_enum_fields() {
+    with_vars(
+        vec![
+            ("DEPLOY_ENVIRONMENT", Some("prod")),
+            ("DEPLOY_PREVIOUS", Some("stage")),
+            ("DEPLOY_FALLBACK", None),
+        ],
+        || {
+            assert_eq!(
+                Deployment::from_env_with_prefix("DEPLOY").unwrap(),
+                Deployment {
+                    environment: Environment::Production,
+                    previous: Some(Environment::Staging),
+                    fallback: Environment::Development,
+                }
+            );
+        },
+    );
+    with_vars(
+        vec![
+            ("DEPLOY_ENVIRONMENT", Some("qa")),
+            ("DEPLOY_PREVIOUS", None),
+            ("DEPLOY_FALLBACK", None),
+        ],
+        || {
+            let error = Deployment::from_env_with_prefix("DEPLOY").unwrap_err();
+            assert_eq!(
+                error,
+                FromEnvError::ParsingFailure {
+                    var_name: "DEPLOY_ENVIRONMENT".into(),
+                    expected_type: "one of: Development, Staging, prod".into(),
+                    str_value: "qa".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+        },
+    );
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(var = "APP_MODE")]
+enum Mode {
+    Development,
+    #[from_env(rename = "stage")]
+    Staging,
+    Production,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Service {
+    mode: Mode,
+}
+
+#[test]
+fn test_derive_from_env_on_enum() {
+    with_vars(
+        vec![
+            ("APP_MODE", Some("production")),
+            ("SERVICE_MODE", Some("STAGE")),
+        ],
+        || {
+            assert_eq!(Mode::from_env().unwrap(), Mode::Production);
+            assert_eq!(
+                Service::from_env_with_prefix("SERVICE").unwrap(),
+                Service {
+                    mode: Mode::Staging
+                }
+            );
+            assert_eq!("development".parse(), Ok(Mode::Development));
+        },
+    );
+    with_vars(vec![("APP_MODE", Some("test"))], || {
+        assert_eq!(
+            Mode::from_env().unwrap_err().to_string(),
+            "failed to parse APP_MODE=\"test\" as one of: Development, stage, Production (value from process environment)"
+        );
+    });
+}
@@ -0,0 +1,189 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Limit<T: FromStr> {
+    max: T,
+    #[from_env(default = "0")]
+    min: T,
+    step: Option<T>,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Bounded<T, U>
+where
+    T: FromStr + Debug,
+{
+    lower: T,
+    upper: U,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    requests: Limit<u32>,
+    ratio: Limit<f64>,
+}
+
+#[test]
+fn test_generic_fields() {
+    with_vars(
+        vec![
+            ("REQUESTS_MAX", Some("100")),
+            ("REQUESTS_MIN", None),
+            ("REQUESTS_STEP", Some("5")),
+            ("RATIO_MAX", Some("0.5")),
+            ("RATIO_MIN", Some("0.1")),
+            ("RATIO_STEP", None),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap(),
+                Config {
+                    requests: Limit {
+                        max: 100,
+                        min: 0,
+                        step: Some(5),
+                    },
+                    ratio: Limit {
+                        max: 0.5,
+                        min: 0.1,
+                        step: None,
+                    },
+                }
+            );
+        },
+    );
+    with_vars(vec![("LOWER", Some("a")), ("UPPER", Some("3"))], || {
+        assert_eq!(
+            Bounded::<char, u8>::from_env().unwrap(),
+            Bounded {
+                lower: 'a',
+                upper: 3,
+            }
+        );
+    });
+}
+
+#[test]
+fn test_instantiated_type_name() {
+    with_vars(
+        vec![("LIMIT_MAX", Some("many")), ("LIMIT_MIN", None)],
+        || {
+            assert_eq!(
+                Limit::<u16>::from_env_with_prefix("LIMIT").unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "LIMIT_MAX".into(),
+                    str_value: "many".into(),
+                    origin: Origin::ProcessEnv,
+                    expected_type: "u16".into(),
+                }
+            );
+        },
+    );
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(diff)]
+struct Window<T: FromStr + PartialEq + Debug> {
+    size: T,
+}
+
+#[test]
+fn test_generic_diff() {
+    let diffs = Window { size: 1 }.diff(&Window { size: 2 });
+    assert_eq!(diffs.len(), 1);
+    assert_eq!(diffs[0].var_name, "SIZE");
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(merge)]
+struct Postgres {
+    host: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(merge)]
+struct Sqlite {
+    path: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(merge)]
+struct Service<B> {
+    name: String,
+    #[from_env(flatten)]
+    backend: B,
+}
+
+#[test]
+fn test_generic_flatten() {
+    with_vars(
+        vec![
+            ("NAME", Some("api")),
+            ("BACKEND_HOST", Some("db")),
+            ("BACKEND_PATH", Some("app.db")),
+        ],
+        || {
+            assert_eq!(
+                Service::<Postgres>::from_env().unwrap(),
+                Service {
+                    name: "api".to_string(),
+                    backend: Postgres {
+                        host: "db".to_string(),
+                    },
+                }
+            );
+            assert_eq!(
+                Service::<Sqlite>::from_env().unwrap().backend,
+                Sqlite {
+                    path: "app.db".to_string(),
+                }
+            );
+        },
+    );
+    let names = Service::<Postgres>::env_vars()
+        .into_iter()
+        .map(|spec| spec.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["NAME", "BACKEND_HOST"]);
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(diff)]
+struct Pair<K, V, const N: usize>
+where
+    K: FromStr + PartialEq + Debug,
+    K::Err: std::fmt::Display,
+    V: FromStr<Err = std::num::ParseIntError> + PartialEq + Debug,
+{
+    key: K,
+    #[from_env(default = "1")]
+    value: V,
+    values: [u8; N],
+}
+
+#[test]
+fn test_where_clause_and_associated_type_bounds() {
+    with_vars(
+        vec![
+            ("PAIR_KEY", Some("name")),
+            ("PAIR_VALUE", None),
+            ("PAIR_VALUES", Some("1,2")),
+        ],
+        || {
+            let pair = Pair::<String, i64, 2>::from_env_with_prefix("PAIR").unwrap();
+            assert_eq!(
+                pair,
+                Pair {
+                    key: "name".to_string(),
+                    value: 1,
+                    values: [1, 2],
+                }
+            );
+            assert!(pair.diff(&pair).is_empty());
+        },
+    );
+}
@@ -0,0 +1,57 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(hex)]
+    signing_key: [u8; 4],
+    #[from_env(hex, default = "0x00ff")]
+    salt: Vec<u8>,
+    #[from_env(hex)]
+    previous_key: Option<[u8; 4]>,
+}
+
+fn load(signing_key: &str, salt: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![
+            ("SIGNING_KEY", Some(signing_key)),
+            ("SALT", salt),
+            ("PREVIOUS_KEY", None),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_hex() {
+    let config = load("A1b2C3d4", None).unwrap();
+    assert_eq!(config.signing_key, [0xa1, 0xb2, 0xc3, 0xd4]);
+    assert_eq!(config.salt, vec![0x00, 0xff]);
+    assert_eq!(config.previous_key, None);
+    let config = load("0XDEADBEEF", Some("")).unwrap();
+    assert_eq!(config.signing_key, [0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(config.salt, Vec::<u8>::new());
+}
+
+#[test]
+fn test_hex_failures() {
+    assert_eq!(
+        load("a1b2c3", None).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "SIGNING_KEY".into(),
+            expected_type: "hex of 4 bytes, found 3 bytes".into(),
+            str_value: "<redacted>".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+    assert_eq!(
+        load("a1b2c3d4", Some("0x12g4")).unwrap_err().to_string(),
+        "failed to parse SALT=\"<redacted>\" as hex, invalid character at position 5 (value from \
+         process environment)"
+    );
+    assert_eq!(
+        load("a1b2c3d", None).unwrap_err().to_string(),
+        "failed to parse SIGNING_KEY=\"<redacted>\" as hex, odd number of digits 7 (value from \
+         process environment)"
+    );
+}
@@ -0,0 +1,100 @@
+use std::io::Cursor;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin, ReaderSource};
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Upstream {
+    host: String,
+    #[from_env(default = "80")]
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(flatten, indexed)]
+    upstreams: Vec<Upstream>,
+    #[from_env(flatten, indexed, deny_gaps)]
+    replicas: Vec<Upstream>,
+}
+
+fn load(input: &str) -> Result<Config, FromEnvError> {
+    let source = ReaderSource::from_reader(Cursor::new(input.to_string())).unwrap();
+    Config::from_source(&source)
+}
+
+fn upstream(host: &str, port: u16) -> Upstream {
+    Upstream {
+        host: host.to_string(),
+        port,
+    }
+}
+
+#[test]
+fn test_indexed() {
+    let config =
+        load("UPSTREAMS_0_HOST=a\nUPSTREAMS_0_PORT=8080\nUPSTREAMS_1_HOST=b\nREPLICAS_0_HOST=r\n")
+            .unwrap();
+    assert_eq!(config.upstreams, [upstream("a", 8080), upstream("b", 80)]);
+    assert_eq!(config.replicas, [upstream("r", 80)]);
+    assert_eq!(load("").unwrap().upstreams, []);
+}
+
+#[test]
+fn test_indexed_gaps() {
+    // Without deny_gaps, elements after a gap are ignored.
+    let config = load("UPSTREAMS_0_HOST=a\nUPSTREAMS_2_HOST=c\n").unwrap();
+    assert_eq!(config.upstreams, [upstream("a", 80)]);
+    assert_eq!(
+        load("REPLICAS_0_HOST=a\nREPLICAS_2_HOST=c\n").unwrap_err(),
+        FromEnvError::Custom {
+            var_name: Some("REPLICAS_2_HOST".to_string()),
+            message: "element 2 is set but element 1 is missing (REPLICAS_1 has no variable set)"
+                .to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_indexed_element_failure() {
+    let input = "UPSTREAMS_0_HOST=a\nUPSTREAMS_1_HOST=b\nUPSTREAMS_2_HOST=c\n\
+                 UPSTREAMS_3_HOST=d\nUPSTREAMS_3_PORT=http\n";
+    assert_eq!(
+        load(input).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "UPSTREAMS_3_PORT".to_string(),
+            expected_type: "u16".to_string(),
+            str_value: "http".to_string(),
+            origin: Origin::Remote("input line 5".to_string()),
+        }
+    );
+    // An element with only optional variables set still needs its required ones.
+    assert_eq!(
+        load("UPSTREAMS_0_PORT=8080\n").unwrap_err(),
+        FromEnvError::MissingEnvVar {
+            var_name: "UPSTREAMS_0_HOST".to_string(),
+            origin: Origin::Remote("input".to_string()),
+            fallbacks: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn test_indexed_specs() {
+    let names = Config::env_vars()
+        .into_iter()
+        .map(|spec| (spec.field, spec.name))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names[..2],
+        [
+            (
+                "upstreams.0.host".to_string(),
+                "UPSTREAMS_0_HOST".to_string()
+            ),
+            (
+                "upstreams.0.port".to_string(),
+                "UPSTREAMS_0_PORT".to_string()
+            ),
+        ]
+    );
+}
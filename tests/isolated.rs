@@ -0,0 +1,81 @@
+use derive_from_env::test_util::{run_isolated, PLATFORM_VARS};
+use derive_from_env::FromEnv;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(default = "8080")]
+    port: u16,
+    log_level: Option<String>,
+}
+
+/// The variables of the process, without those kept for the platform.
+fn user_vars() -> Vec<(String, String)> {
+    let mut vars = std::env::vars()
+        .filter(|(key, _)| !PLATFORM_VARS.contains(&key.as_str()))
+        .collect::<Vec<_>>();
+    vars.sort();
+    vars
+}
+
+#[test]
+fn test_pristine_environment() {
+    run_isolated("test_pristine_environment", &[], || {
+        assert_eq!(user_vars().len(), 0);
+        assert_eq!(
+            Config::from_env().unwrap(),
+            Config {
+                port: 8080,
+                log_level: None,
+            }
+        );
+    });
+}
+
+#[test]
+fn test_given_environment() {
+    run_isolated(
+        "test_given_environment",
+        &[("PORT", "9090"), ("LOG_LEVEL", "debug")],
+        || {
+            assert_eq!(
+                user_vars(),
+                [
+                    ("LOG_LEVEL".to_string(), "debug".to_string()),
+                    ("PORT".to_string(), "9090".to_string())
+                ]
+            );
+            assert_eq!(Config::from_env().unwrap().port, 9090);
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "isolated test test_failure failed")]
+fn test_failure() {
+    run_isolated("test_failure", &[], || {
+        assert_eq!(Config::from_env().unwrap().port, 9090);
+    });
+}
+
+#[test]
+fn test_spawned_thread() {
+    // Threads spawned by the test are not named after it.
+    std::thread::spawn(|| {
+        run_isolated("test_spawned_thread", &[("PORT", "7070")], || {
+            assert_eq!(Config::from_env().unwrap().port, 7070);
+        });
+    })
+    .join()
+    .unwrap();
+}
+
+mod nested {
+    use super::*;
+
+    #[test]
+    fn test_module_path() {
+        run_isolated("nested::test_module_path", &[], || {
+            assert_eq!(user_vars().len(), 0);
+        });
+    }
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "json")]
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use serde::Deserialize;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct RetryPolicy {
+    max: u32,
+    backoff_ms: u64,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(json)]
+    retry_policy: RetryPolicy,
+    #[from_env(json, var = "FALLBACK_HOSTS", default = r#"["localhost"]"#)]
+    hosts: Vec<String>,
+    #[from_env(json)]
+    limits: Option<RetryPolicy>,
+}
+
+fn load(retry_policy: &str, hosts: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![
+            ("RETRY_POLICY", Some(retry_policy)),
+            ("FALLBACK_HOSTS", hosts),
+            ("LIMITS", None),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_json() {
+    let config = load(r#"{"max": 5, "backoff_ms": 200}"#, None).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            retry_policy: RetryPolicy {
+                max: 5,
+                backoff_ms: 200
+            },
+            hosts: vec!["localhost".into()],
+            limits: None,
+        }
+    );
+    let config = load(r#"{"max": 1, "backoff_ms": 0}"#, Some(r#"["a", "b"]"#)).unwrap();
+    assert_eq!(config.hosts, ["a", "b"]);
+}
+
+#[test]
+fn test_json_failure() {
+    assert_eq!(
+        load(r#"{"max": 5}"#, None).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "RETRY_POLICY".into(),
+            expected_type: "JSON RetryPolicy (missing field `backoff_ms` at line 1 column 10)"
+                .into(),
+            str_value: r#"{"max": 5}"#.into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromEnv)]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct App {
+    #[from_env(json_var = "DB_CONFIG")]
+    db: Database,
+}
+
+#[test]
+fn test_json_var() {
+    let from_json = with_vars(
+        vec![
+            ("DB_CONFIG", Some(r#"{"host": "db1", "port": 5432}"#)),
+            ("DB_HOST", Some("ignored")),
+        ],
+        App::from_env,
+    );
+    assert_eq!(
+        from_json.unwrap().db,
+        Database {
+            host: "db1".into(),
+            port: 5432
+        }
+    );
+    let from_fields = with_vars(
+        vec![
+            ("DB_CONFIG", None),
+            ("DB_HOST", Some("localhost")),
+            ("DB_PORT", Some("5433")),
+        ],
+        App::from_env,
+    );
+    assert_eq!(from_fields.unwrap().db.host, "localhost");
+    assert_eq!(
+        App::env_vars()
+            .iter()
+            .map(|spec| (spec.name.as_str(), spec.type_name.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            ("DB_CONFIG", "JSON Database"),
+            ("DB_HOST", "String"),
+            ("DB_PORT", "u16")
+        ]
+    );
+}
+
+#[test]
+fn test_json_var_failures() {
+    let invalid = with_vars(
+        vec![
+            ("DB_CONFIG", Some(r#"{"host": "db1"}"#)),
+            ("DB_HOST", Some("a")),
+        ],
+        App::from_env,
+    );
+    assert_eq!(
+        invalid.unwrap_err().to_string(),
+        "failed to parse DB_CONFIG=\"{\\\"host\\\": \\\"db1\\\"}\" as JSON Database (missing field \
+         `port` at line 1 column 15) (value from process environment)"
+    );
+    let missing = with_vars(
+        vec![
+            ("DB_CONFIG", None),
+            ("DB_HOST", Some("a")),
+            ("DB_PORT", None),
+        ],
+        App::from_env,
+    );
+    assert_eq!(
+        missing.unwrap_err(),
+        FromEnvError::MissingEnvVar {
+            var_name: "DB_PORT".into(),
+            origin: Origin::ProcessEnv,
+            fallbacks: Vec::new(),
+        }
+    );
+}
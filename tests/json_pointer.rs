@@ -0,0 +1,86 @@
+#![cfg(feature = "serde")]
+
+use derive_from_env::{FromEnv, FromEnvError};
+use temp_env::with_var;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Platform {
+    #[from_env(
+        var = "PLATFORM_JSON",
+        json_pointer = "/services/postgres/0/credentials/uri"
+    )]
+    database_url: String,
+    #[from_env(
+        var = "PLATFORM_JSON",
+        json_pointer = "/services/postgres/0/credentials/port"
+    )]
+    database_port: u16,
+    #[from_env(var = "PLATFORM_JSON", json_pointer = "/instance/debug")]
+    debug: Option<bool>,
+}
+
+const DOCUMENT: &str = r#"{
+    "services": {
+        "postgres": [
+            {"name": "db", "credentials": {"uri": "postgres://db.internal/app", "port": 5432}}
+        ]
+    },
+    "instance": {"debug": true, "index": 0}
+}"#;
+
+fn load(document: &str) -> Result<Platform, FromEnvError> {
+    with_var("PLATFORM_JSON", Some(document), Platform::from_env)
+}
+
+fn custom_message(error: FromEnvError) -> String {
+    match error {
+        FromEnvError::Custom {
+            var_name: Some(var_name),
+            message,
+        } => {
+            assert_eq!(var_name, "PLATFORM_JSON");
+            message
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn test_json_pointer_nested_array_path() {
+    assert_eq!(
+        load(DOCUMENT).unwrap(),
+        Platform {
+            database_url: "postgres://db.internal/app".into(),
+            database_port: 5432,
+            debug: Some(true),
+        }
+    );
+}
+
+#[test]
+fn test_json_pointer_missing_key() {
+    let document = DOCUMENT.replace("\"uri\"", "\"url\"");
+    assert_eq!(
+        custom_message(load(&document).unwrap_err()),
+        "no value at JSON pointer \"/services/postgres/0/credentials/uri\""
+    );
+}
+
+#[test]
+fn test_json_pointer_non_scalar_target() {
+    let document = DOCUMENT.replace("\"debug\": true", "\"debug\": {\"level\": 2}");
+    assert_eq!(
+        custom_message(load(&document).unwrap_err()),
+        "expected a string, number or boolean at JSON pointer \"/instance/debug\", found an object"
+    );
+}
+
+#[test]
+fn test_json_pointer_invalid_leaf() {
+    let document = DOCUMENT.replace("5432", "\"http\"");
+    match load(&document).unwrap_err() {
+        FromEnvError::ParsingFailure { str_value, .. } => assert_eq!(str_value, "http"),
+        error => panic!("unexpected error {:?}", error),
+    }
+    assert!(custom_message(load("not json").unwrap_err()).starts_with("invalid JSON document"));
+}
@@ -0,0 +1,79 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use derive_from_env::{FromEnv, FromEnvError, Lazy, Origin, ReaderSource, SpySource};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Database {
+    host: String,
+    #[from_env(default = "5432")]
+    port: u16,
+}
+
+#[derive(Debug, FromEnv)]
+struct Config {
+    name: String,
+    #[from_env(flatten, lazy)]
+    database: Lazy<Database>,
+}
+
+fn spy(input: &str) -> Arc<SpySource<ReaderSource>> {
+    let source = ReaderSource::from_reader(Cursor::new(input.to_string())).unwrap();
+    Arc::new(SpySource::new(source))
+}
+
+#[test]
+fn test_lazy_section_loaded_on_first_access() {
+    let source = spy("NAME=api\nDATABASE_HOST=db.internal\n");
+    let config = Config::from_source(&source).unwrap();
+    assert_eq!(config.name, "api");
+    assert_eq!(source.keys_looked_up(), ["NAME"]);
+    assert!(!config.database.is_loaded());
+
+    let database = config.database.get().unwrap();
+    assert_eq!(database.host, "db.internal");
+    assert_eq!(database.port, 5432);
+    assert_eq!(
+        source.keys_looked_up(),
+        ["NAME", "DATABASE_HOST", "DATABASE_PORT"]
+    );
+    // Dereferencing reuses the loaded section.
+    assert_eq!(config.database.host, "db.internal");
+    assert_eq!(source.lookups().len(), 3);
+}
+
+#[test]
+fn test_lazy_section_errors_are_memoized() {
+    let source = spy("NAME=api\nDATABASE_PORT=http\n");
+    let config = Config::from_source(&source).unwrap();
+    let error = FromEnvError::MissingEnvVar {
+        var_name: "DATABASE_HOST".to_string(),
+        origin: Origin::Remote("input".to_string()),
+        fallbacks: Vec::new(),
+    };
+    assert_eq!(config.database.get(), Err(&error));
+    let lookups = source.lookups().len();
+    assert_eq!(config.database.get(), Err(&error));
+    assert_eq!(source.lookups().len(), lookups);
+}
+
+#[test]
+fn test_lazy_section_from_process_env() {
+    let config = with_vars(
+        [("NAME", Some("api")), ("DATABASE_HOST", None::<&str>)],
+        || Config::from_env().unwrap(),
+    );
+    with_vars([("DATABASE_HOST", Some("late.internal"))], || {
+        assert_eq!(config.database.get().unwrap().host, "late.internal");
+    });
+}
+
+#[test]
+fn test_lazy_section_of_borrowed_source() {
+    // Sources which can't be shared load the section right away.
+    let source = ReaderSource::from_reader(Cursor::new("NAME=api\n")).unwrap();
+    let config = Config::from_source(&source).unwrap();
+    assert!(config.database.is_loaded());
+    assert!(config.database.get().is_err());
+}
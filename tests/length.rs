@@ -0,0 +1,98 @@
+use derive_from_env::{FromEnv, FromEnvError};
+use std::net::SocketAddr;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "CLUSTER_")]
+struct ClusterConfig {
+    #[from_env(min_len = 8, max_len = 8)]
+    api_key: String,
+    #[from_env(min_len = 1, max_len = 2)]
+    seeds: Vec<SocketAddr>,
+    #[from_env(max_len = 3, default = "cluster")]
+    name: String,
+    #[from_env(min_len = 2)]
+    label: Option<String>,
+}
+
+fn load(vars: [Option<&str>; 4]) -> Result<ClusterConfig, FromEnvError> {
+    with_vars(
+        [
+            ("CLUSTER_API_KEY", vars[0]),
+            ("CLUSTER_SEEDS", vars[1]),
+            ("CLUSTER_NAME", vars[2]),
+            ("CLUSTER_LABEL", vars[3]),
+        ],
+        ClusterConfig::from_env,
+    )
+}
+
+#[test]
+fn test_lengths_within_limits() {
+    assert_eq!(
+        load([Some("é1234567"), Some("10.0.0.1:7000"), Some("eu"), None]).unwrap(),
+        ClusterConfig {
+            api_key: "é1234567".to_string(),
+            seeds: vec!["10.0.0.1:7000".parse().unwrap()],
+            name: "eu".to_string(),
+            label: None,
+        }
+    );
+}
+
+#[test]
+fn test_string_length_out_of_limits() {
+    assert_eq!(
+        load([Some("1234567"), Some("10.0.0.1:7000"), Some("eu"), None]).unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "CLUSTER_API_KEY".to_string(),
+            message: "7 characters, expected at least 8".to_string(),
+        }
+    );
+    assert_eq!(
+        load([
+            Some("12345678"),
+            Some("10.0.0.1:7000"),
+            Some("eu"),
+            Some("a")
+        ])
+        .unwrap_err()
+        .to_string(),
+        "invalid value of CLUSTER_LABEL: 1 characters, expected at least 2"
+    );
+}
+
+#[test]
+fn test_collection_size_out_of_limits() {
+    assert_eq!(
+        load([
+            Some("12345678"),
+            Some("10.0.0.1:7000,10.0.0.2:7000,10.0.0.3:7000"),
+            Some("eu"),
+            None
+        ])
+        .unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "CLUSTER_SEEDS".to_string(),
+            message: "3 elements, expected at most 2".to_string(),
+        }
+    );
+    assert_eq!(
+        load([Some("12345678"), Some(""), Some("eu"), None]).unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "CLUSTER_SEEDS".to_string(),
+            message: "0 elements, expected at least 1".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_default_out_of_limits() {
+    assert_eq!(
+        load([Some("12345678"), Some("10.0.0.1:7000"), None, None]).unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "CLUSTER_NAME".to_string(),
+            message: "7 characters, expected at most 3".to_string(),
+        }
+    );
+}
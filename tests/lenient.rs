@@ -0,0 +1,96 @@
+use derive_from_env::{EnvWarning, EnvWarningKind, FromEnv};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Tuning {
+    #[from_env(lenient, default = "60")]
+    cache_ttl: u64,
+    #[from_env(lenient)]
+    batch_size: Option<usize>,
+    #[from_env(lenient, default)]
+    prefetch: bool,
+    max_connections: u32,
+}
+
+#[test]
+fn test_lenient_fallback_with_warnings() {
+    with_vars(
+        [
+            ("CACHE_TTL", Some("banana")),
+            ("BATCH_SIZE", Some("-1")),
+            ("PREFETCH", Some("sometimes")),
+            ("MAX_CONNECTIONS", Some("10")),
+        ],
+        || {
+            let (tuning, warnings) = Tuning::from_env_with_warnings().unwrap();
+            assert_eq!(
+                tuning,
+                Tuning {
+                    cache_ttl: 60,
+                    batch_size: None,
+                    prefetch: false,
+                    max_connections: 10,
+                }
+            );
+            assert_eq!(
+                warnings
+                    .iter()
+                    .map(|warning| warning.var_name.as_str())
+                    .collect::<Vec<_>>(),
+                ["CACHE_TTL", "BATCH_SIZE", "PREFETCH"]
+            );
+            assert_eq!(
+                warnings[0],
+                EnvWarning {
+                    var_name: "CACHE_TTL".to_string(),
+                    kind: EnvWarningKind::LenientFallback,
+                    message: "failed to parse CACHE_TTL=\"banana\" as u64 (value from process environment), the default is used instead".to_string(),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_lenient_valid_values() {
+    with_vars(
+        [
+            ("CACHE_TTL", Some("5")),
+            ("BATCH_SIZE", Some("100")),
+            ("PREFETCH", Some("true")),
+            ("MAX_CONNECTIONS", Some("10")),
+        ],
+        || {
+            let (tuning, warnings) = Tuning::from_env_with_warnings().unwrap();
+            assert_eq!(
+                tuning,
+                Tuning {
+                    cache_ttl: 5,
+                    batch_size: Some(100),
+                    prefetch: true,
+                    max_connections: 10,
+                }
+            );
+            assert_eq!(warnings, []);
+        },
+    );
+}
+
+#[test]
+fn test_strict_fields_still_fail() {
+    with_vars(
+        [
+            ("CACHE_TTL", Some("banana")),
+            ("BATCH_SIZE", None),
+            ("PREFETCH", None),
+            ("MAX_CONNECTIONS", Some("many")),
+        ],
+        || {
+            assert!(Tuning::from_env_with_warnings().is_err());
+            assert_eq!(
+                Tuning::from_env().unwrap_err().to_string(),
+                "failed to parse MAX_CONNECTIONS=\"many\" as u32 (value from process environment)"
+            );
+        },
+    );
+}
@@ -0,0 +1,71 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(lenient_bool)]
+    debug: bool,
+    #[from_env(lenient_bool)]
+    verbose: Option<bool>,
+    strict: Option<bool>,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(lenient_bool)]
+struct Flags {
+    #[from_env(default = "off")]
+    cache: bool,
+    colors: bool,
+}
+
+fn load(debug: &str) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![
+            ("DEBUG", Some(debug)),
+            ("VERBOSE", Some("On")),
+            ("STRICT", None),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_lenient_bool() {
+    for (value, expected) in [
+        ("1", true),
+        ("0", false),
+        ("yes", true),
+        ("NO", false),
+        ("True", true),
+        (" off ", false),
+    ] {
+        assert_eq!(load(value).unwrap().debug, expected, "{:?}", value);
+    }
+    assert_eq!(load("true").unwrap().verbose, Some(true));
+    with_vars(vec![("COLORS", Some("Yes"))], || {
+        assert_eq!(
+            Flags::from_env().unwrap(),
+            Flags {
+                cache: false,
+                colors: true
+            }
+        );
+    });
+}
+
+#[test]
+fn test_lenient_bool_failures() {
+    assert_eq!(
+        load("enabled").unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "DEBUG".into(),
+            expected_type: "bool (true, false, 1, 0, yes, no, on or off, ignoring case)".into(),
+            str_value: "enabled".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+    // Fields without the attribute stay strict.
+    with_vars(vec![("DEBUG", Some("1")), ("STRICT", Some("yes"))], || {
+        assert!(Config::from_env().is_err())
+    });
+}
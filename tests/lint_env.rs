@@ -0,0 +1,169 @@
+use derive_from_env::{EnvVarSpec, EnvWarningKind, FromEnv, FromEnvValue};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnvValue)]
+enum Mode {
+    Fast,
+}
+
+#[derive(FromEnv)]
+#[from_env(prefix = "DB_")]
+struct Database {
+    url: String,
+    pool: Option<u32>,
+}
+
+#[derive(FromEnv)]
+struct Lint {
+    name: String,
+    #[from_env(default = "8080")]
+    port: u16,
+    #[from_env(var = "LINT_TOKEN")]
+    token: Option<String>,
+    mode: Mode,
+    database: Database,
+}
+
+#[test]
+fn test_env_vars() {
+    let spec = |name: &str, field: &str, type_name: &str, required: bool| EnvVarSpec {
+        name: name.into(),
+        field: field.into(),
+        type_name: type_name.into(),
+        default: None,
+        required,
+        optional: false,
+        hidden: false,
+        secret: false,
+    };
+    assert_eq!(
+        Lint::env_vars_with_prefix("LINT"),
+        vec![
+            spec("LINT_NAME", "name", "String", true),
+            EnvVarSpec {
+                default: Some("8080".into()),
+                ..spec("LINT_PORT", "port", "u16", false)
+            },
+            EnvVarSpec {
+                optional: true,
+                ..spec("LINT_TOKEN", "token", "String", false)
+            },
+            spec("LINT_MODE", "mode", "Mode", true),
+            spec("LINT_DATABASE_DB_URL", "database.url", "String", true),
+            EnvVarSpec {
+                optional: true,
+                ..spec("LINT_DATABASE_DB_POOL", "database.pool", "u32", false)
+            },
+        ]
+    );
+}
+
+fn lint_kinds() -> Vec<(String, EnvWarningKind)> {
+    Lint::lint_env_with_prefix("LINT")
+        .into_iter()
+        .map(|warning| (warning.var_name, warning.kind))
+        .collect()
+}
+
+#[test]
+fn test_lint_clean() {
+    with_vars(
+        vec![
+            ("LINT_NAME", Some("app")),
+            ("LINT_PORT", None),
+            ("LINT_TOKEN", Some("")),
+            ("LINT_MODE", Some("fast")),
+            ("LINT_DATABASE_DB_URL", Some("postgres://db")),
+            ("LINT_DATABASE_DB_POOL", None),
+        ],
+        || {
+            assert_eq!(lint_kinds(), vec![]);
+            let Lint {
+                name,
+                port,
+                token,
+                mode,
+                database: Database { url, pool },
+            } = Lint::from_env_with_prefix("LINT").unwrap();
+            assert_eq!(
+                (name.as_str(), port, token, mode, url.as_str(), pool),
+                (
+                    "app",
+                    8080,
+                    Some(String::new()),
+                    Mode::Fast,
+                    "postgres://db",
+                    None
+                )
+            );
+        },
+    )
+}
+
+#[test]
+fn test_lint_quotes_and_whitespace() {
+    with_vars(
+        vec![
+            ("LINT_NAME", Some("\"app\"")),
+            ("LINT_PORT", Some("8080 ")),
+            ("LINT_TOKEN", Some(" 'secret'")),
+            ("LINT_MODE", Some("fast")),
+            ("LINT_DATABASE_DB_URL", Some("postgres://db")),
+        ],
+        || {
+            assert_eq!(
+                lint_kinds(),
+                vec![
+                    ("LINT_NAME".into(), EnvWarningKind::QuotedValue),
+                    ("LINT_PORT".into(), EnvWarningKind::SurroundingWhitespace),
+                    ("LINT_TOKEN".into(), EnvWarningKind::SurroundingWhitespace),
+                    ("LINT_TOKEN".into(), EnvWarningKind::QuotedValue),
+                ]
+            );
+        },
+    )
+}
+
+#[test]
+fn test_lint_empty_with_default() {
+    with_vars(
+        vec![
+            ("LINT_NAME", Some("app")),
+            ("LINT_PORT", Some("")),
+            ("LINT_MODE", Some("fast")),
+            ("LINT_DATABASE_DB_URL", Some("postgres://db")),
+        ],
+        || {
+            let warnings = Lint::lint_env_with_prefix("LINT");
+            assert_eq!(warnings.len(), 1);
+            assert_eq!(warnings[0].kind, EnvWarningKind::EmptyWithDefault);
+            assert_eq!(
+                warnings[0].to_string(),
+                "LINT_PORT is set to an empty value, which is used instead of the default"
+            );
+        },
+    )
+}
+
+#[test]
+fn test_lint_case_mismatch() {
+    with_vars(
+        vec![
+            ("LINT_NAME", Some("app")),
+            ("LINT_MODE", None),
+            ("Lint_Mode", Some("fast")),
+            ("LINT_DATABASE_DB_URL", Some("postgres://db")),
+        ],
+        || {
+            assert_eq!(
+                lint_kinds(),
+                vec![(
+                    "LINT_MODE".into(),
+                    EnvWarningKind::CaseMismatch {
+                        found: "Lint_Mode".into()
+                    }
+                )]
+            );
+        },
+    )
+}
@@ -0,0 +1,160 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Features {
+    #[from_env(
+        default = "base",
+        append_from = "FEATURES_EXTRA",
+        append_from = "FEATURES_LOCAL"
+    )]
+    features: Vec<String>,
+    #[from_env(separator = ";")]
+    ports: Option<Vec<u16>>,
+}
+
+fn load(
+    features: Option<&str>,
+    extra: Option<&str>,
+    local: Option<&str>,
+) -> Result<Features, FromEnvError> {
+    with_vars(
+        vec![
+            ("FEATURES", features),
+            ("FEATURES_EXTRA", extra),
+            ("FEATURES_LOCAL", local),
+            ("PORTS", Some("80; 443;")),
+        ],
+        Features::from_env,
+    )
+}
+
+#[test]
+fn test_list() {
+    let loaded = load(Some("a, b,,c"), None, None).unwrap();
+    assert_eq!(loaded.features, ["a", "b", "c"]);
+    assert_eq!(loaded.ports, Some(vec![80, 443]));
+    assert_eq!(
+        load(Some(""), None, None).unwrap().features,
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn test_append_from() {
+    assert_eq!(load(None, Some("c"), None).unwrap().features, ["base", "c"]);
+    assert_eq!(
+        load(Some("a,b"), Some("c"), Some("d,e")).unwrap().features,
+        ["a", "b", "c", "d", "e"]
+    );
+    assert_eq!(
+        load(Some("a,b"), None, Some("e")).unwrap().features,
+        ["a", "b", "e"]
+    );
+}
+
+#[derive(Debug, FromEnv)]
+struct Ports {
+    #[from_env(append_from = "PORTS_EXTRA")]
+    ports: Vec<u16>,
+}
+
+#[test]
+fn test_append_from_failure() {
+    with_vars(
+        vec![
+            ("PORTS", Some("80,443")),
+            ("PORTS_EXTRA", Some("8080,http")),
+        ],
+        || {
+            assert_eq!(
+                Ports::from_env().unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "PORTS_EXTRA".into(),
+                    expected_type: "u16 item of a list separated by \",\"".into(),
+                    str_value: "http".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+        },
+    );
+    with_vars(
+        vec![("PORTS", Some("80")), ("PORTS_EXTRA", Some("8080"))],
+        || {
+            assert_eq!(Ports::from_env().unwrap().ports, [80, 8080]);
+        },
+    );
+    let specs = Ports::env_vars();
+    assert_eq!(specs.len(), 2);
+    assert_eq!(specs[1].name, "PORTS_EXTRA");
+    assert!(specs[1].optional);
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(separator = ";")]
+struct Connections {
+    urls: Vec<String>,
+    #[from_env(separator = "\n")]
+    hosts: Vec<String>,
+    #[from_env(separator = " | ")]
+    weights: Vec<u8>,
+}
+
+#[test]
+fn test_separators() {
+    with_vars(
+        vec![
+            ("URLS", Some("http://a?x=1,y=2; http://b")),
+            ("HOSTS", Some("a.internal\r\nb.internal\n")),
+            ("WEIGHTS", Some("1 | 2 | 3")),
+        ],
+        || {
+            assert_eq!(
+                Connections::from_env().unwrap(),
+                Connections {
+                    urls: vec!["http://a?x=1,y=2".into(), "http://b".into()],
+                    hosts: vec!["a.internal".into(), "b.internal".into()],
+                    weights: vec![1, 2, 3],
+                }
+            );
+        },
+    );
+    with_vars(
+        vec![
+            ("URLS", Some("")),
+            ("HOSTS", Some("a.internal")),
+            ("WEIGHTS", Some("1 | 2|3")),
+        ],
+        || {
+            assert_eq!(
+                Connections::from_env().unwrap_err().to_string(),
+                "failed to parse WEIGHTS=\"2|3\" as u8 item of a list separated by \" | \" \
+                 (value from process environment)"
+            );
+        },
+    );
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Cors {
+    cors_origins: Option<Vec<String>>,
+}
+
+#[test]
+fn test_optional_list_unset_or_empty() {
+    let load = |value: Option<&str>| {
+        with_vars(vec![("CORS_ORIGINS", value)], || {
+            Cors::from_env().unwrap().cors_origins
+        })
+    };
+    assert_eq!(load(None), None);
+    assert_eq!(load(Some("")), Some(vec![]));
+    assert_eq!(load(Some(" , ")), Some(vec![]));
+    assert_eq!(
+        load(Some("https://a.example,https://b.example")),
+        Some(vec![
+            "https://a.example".to_string(),
+            "https://b.example".to_string()
+        ])
+    );
+}
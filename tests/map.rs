@@ -0,0 +1,203 @@
+use std::collections::{BTreeMap, HashMap};
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    labels: HashMap<String, String>,
+    #[from_env(separator = ";")]
+    weights: Option<BTreeMap<String, f64>>,
+    #[from_env(default = "")]
+    limits: BTreeMap<u16, u32>,
+}
+
+fn load(labels: &str, weights: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![
+            ("LABELS", Some(labels)),
+            ("WEIGHTS", weights),
+            ("LIMITS", None),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_map() {
+    let config = load("env=prod, team=core,,region=eu", Some("a=0.5; b = 1.5")).unwrap();
+    assert_eq!(
+        config.labels,
+        HashMap::from([
+            ("env".to_string(), "prod".to_string()),
+            ("team".to_string(), "core".to_string()),
+            ("region".to_string(), "eu".to_string()),
+        ])
+    );
+    assert_eq!(
+        config.weights,
+        Some(BTreeMap::from([
+            ("a".to_string(), 0.5),
+            ("b".to_string(), 1.5)
+        ]))
+    );
+    assert!(config.limits.is_empty());
+    // Values may contain `=`, entries being split on the first one.
+    let config = load("query=a=b", None).unwrap();
+    assert_eq!(config.labels["query"], "a=b");
+    assert_eq!(config.weights, None);
+}
+
+#[test]
+fn test_map_failures() {
+    assert_eq!(
+        load("env=prod,team", None).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "LABELS".into(),
+            expected_type: "key=value (entry 2 of a map separated by \",\")".into(),
+            str_value: "team".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+    assert_eq!(
+        load("", Some("a=heavy")).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "WEIGHTS".into(),
+            expected_type: "f64 value (entry 1 of a map separated by \";\")".into(),
+            str_value: "a=heavy".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+    assert_eq!(
+        load("env=prod,env=dev", None).unwrap_err().to_string(),
+        "LABELS: duplicate key \"env\" in entry 2 \"env=dev\""
+    );
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Services {
+    services: BTreeMap<u16, String>,
+}
+
+#[test]
+fn test_ordered_map() {
+    with_vars(vec![("SERVICES", Some("443=https,80=http,22=ssh"))], || {
+        let services = Services::from_env().unwrap().services;
+        assert_eq!(
+            services.into_iter().collect::<Vec<_>>(),
+            [
+                (22, "ssh".to_string()),
+                (80, "http".to_string()),
+                (443, "https".to_string())
+            ]
+        );
+    });
+    // Positions count empty entries, to match the raw value.
+    with_vars(vec![("SERVICES", Some("443=https,,http=80"))], || {
+        assert_eq!(
+            Services::from_env().unwrap_err().to_string(),
+            "failed to parse SERVICES=\"http=80\" as u16 key (entry 3 of a map separated by \",\") \
+             (value from process environment)"
+        );
+    });
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(separator = ";", kv_separator = ":")]
+struct Platform {
+    labels: BTreeMap<String, String>,
+    #[from_env(kv_separator = "=>", separator = "|")]
+    routes: BTreeMap<String, String>,
+}
+
+#[test]
+fn test_map_kv_separator() {
+    with_vars(
+        vec![
+            ("LABELS", Some("env:prod;url:http://a:80;")),
+            ("ROUTES", Some("/a => http://b|")),
+        ],
+        || {
+            let platform = Platform::from_env().unwrap();
+            assert_eq!(
+                platform.labels,
+                BTreeMap::from([
+                    ("env".to_string(), "prod".to_string()),
+                    ("url".to_string(), "http://a:80".to_string()),
+                ])
+            );
+            assert_eq!(platform.routes["/a"], "http://b");
+        },
+    );
+    with_vars(
+        vec![("LABELS", Some("env=prod")), ("ROUTES", Some(""))],
+        || {
+            assert_eq!(
+                Platform::from_env().unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "LABELS".into(),
+                    expected_type: "key:value (entry 1 of a map separated by \";\")".into(),
+                    str_value: "env=prod".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+        },
+    );
+    with_vars(
+        vec![("LABELS", Some("env:prod; :x")), ("ROUTES", Some(""))],
+        || {
+            assert_eq!(
+                Platform::from_env().unwrap_err().to_string(),
+                "LABELS: empty key in entry 2 \":x\""
+            );
+        },
+    );
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Quotas {
+    #[from_env(type_name = "quota {bytes}")]
+    quotas: BTreeMap<String, u64>,
+}
+
+#[test]
+fn test_map_type_name_with_braces() {
+    with_vars(vec![("QUOTAS", Some("a=1,b=lots"))], || {
+        assert_eq!(
+            Quotas::from_env().unwrap_err(),
+            FromEnvError::ParsingFailure {
+                var_name: "QUOTAS".into(),
+                expected_type: "quota {bytes} value (entry 2 of a map separated by \",\")".into(),
+                str_value: "b=lots".into(),
+                origin: Origin::ProcessEnv,
+            }
+        );
+    });
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Braced {
+    #[from_env(kv_separator = "{", separator = "}")]
+    pairs: BTreeMap<String, u8>,
+}
+
+#[test]
+fn test_map_separators_with_braces() {
+    with_vars(vec![("PAIRS", Some("a{1}b{2}"))], || {
+        assert_eq!(
+            Braced::from_env().unwrap().pairs,
+            BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+        );
+    });
+    with_vars(vec![("PAIRS", Some("a{1}b"))], || {
+        assert_eq!(
+            Braced::from_env().unwrap_err(),
+            FromEnvError::ParsingFailure {
+                var_name: "PAIRS".into(),
+                expected_type: "key{value (entry 2 of a map separated by \"}\")".into(),
+                str_value: "b".into(),
+                origin: Origin::ProcessEnv,
+            }
+        );
+    });
+}
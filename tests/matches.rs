@@ -0,0 +1,65 @@
+#![cfg(feature = "regex")]
+use derive_from_env::{FromEnv, FromEnvError};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "STORE_")]
+struct StoreConfig {
+    #[from_env(matches = "^[a-z][a-z0-9-]{2,29}$")]
+    tenant: String,
+    #[from_env(matches = "^[a-z0-9.-]+$")]
+    buckets: Vec<String>,
+    #[from_env(matches = "^eu-|^us-", default = "eu-west-1")]
+    region: Option<String>,
+}
+
+fn load(vars: [Option<&str>; 3]) -> Result<StoreConfig, FromEnvError> {
+    with_vars(
+        [
+            ("STORE_TENANT", vars[0]),
+            ("STORE_BUCKETS", vars[1]),
+            ("STORE_REGION", vars[2]),
+        ],
+        StoreConfig::from_env,
+    )
+}
+
+#[test]
+fn test_matching_values() {
+    assert_eq!(
+        load([Some("acme-corp"), Some("logs,backups.eu"), None]).unwrap(),
+        StoreConfig {
+            tenant: "acme-corp".to_string(),
+            buckets: vec!["logs".to_string(), "backups.eu".to_string()],
+            region: Some("eu-west-1".to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_value_not_matching() {
+    assert_eq!(
+        load([Some("Acme"), Some("logs"), None]).unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "STORE_TENANT".to_string(),
+            message: "\"Acme\" doesn't match the pattern ^[a-z][a-z0-9-]{2,29}$".to_string(),
+        }
+    );
+    assert_eq!(
+        load([Some("acme"), Some("logs"), Some("ap-south-1")])
+            .unwrap_err()
+            .to_string(),
+        "invalid value of STORE_REGION: \"ap-south-1\" doesn't match the pattern ^eu-|^us-"
+    );
+}
+
+#[test]
+fn test_list_item_not_matching() {
+    assert_eq!(
+        load([Some("acme"), Some("logs,Backups"), None]).unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "STORE_BUCKETS".to_string(),
+            message: "\"Backups\" doesn't match the pattern ^[a-z0-9.-]+$".to_string(),
+        }
+    );
+}
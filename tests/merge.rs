@@ -0,0 +1,77 @@
+use derive_from_env::{FromEnv, MergePolicy};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(merge)]
+struct Database {
+    #[from_env(default = "localhost")]
+    host: String,
+    #[from_env(default = "5432")]
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(merge)]
+struct Config {
+    name: String,
+    #[from_env(default = "4")]
+    workers: u8,
+    replica: Option<String>,
+    #[from_env(flatten)]
+    database: Database,
+}
+
+fn load(vars: Vec<(&str, Option<&str>)>) -> Config {
+    let mut all = vec![
+        ("NAME", None),
+        ("WORKERS", None),
+        ("REPLICA", None),
+        ("DATABASE_HOST", None),
+        ("DATABASE_PORT", None),
+    ];
+    all.extend(vars);
+    with_vars(all, || Config::from_env().unwrap())
+}
+
+fn base() -> Config {
+    load(vec![
+        ("NAME", Some("file")),
+        ("WORKERS", Some("8")),
+        ("REPLICA", Some("replica.internal")),
+        ("DATABASE_HOST", Some("db.internal")),
+    ])
+}
+
+fn env() -> Config {
+    load(vec![("NAME", Some("env")), ("DATABASE_PORT", Some("6543"))])
+}
+
+#[test]
+fn test_merge_prefer_self_and_other() {
+    assert_eq!(base().merge(env(), MergePolicy::PreferSelf), base());
+    assert_eq!(base().merge(env(), MergePolicy::PreferOther), env());
+}
+
+#[test]
+fn test_merge_prefer_non_default() {
+    assert_eq!(
+        base().merge(env(), MergePolicy::PreferNonDefault),
+        Config {
+            name: "env".into(),
+            workers: 8,
+            replica: Some("replica.internal".into()),
+            database: Database {
+                host: "db.internal".into(),
+                port: 6543,
+            },
+        }
+    );
+    // A value explicitly set to the default can't be told apart from an unset one.
+    let explicit_default = load(vec![("NAME", Some("env")), ("WORKERS", Some("4"))]);
+    assert_eq!(
+        base()
+            .merge(explicit_default, MergePolicy::PreferNonDefault)
+            .workers,
+        8
+    );
+}
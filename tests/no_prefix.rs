@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use derive_from_env::FromEnv;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct Paths {
+    #[from_env(no_prefix)]
+    home: PathBuf,
+    #[from_env(no_prefix, alias = "TMP")]
+    tmpdir: Option<String>,
+    data_dir: PathBuf,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    paths: Paths,
+}
+
+#[test]
+fn test_no_prefix_value_field() {
+    with_vars(
+        [
+            ("HOME", Some("/home/app")),
+            ("APP_HOME", Some("/ignored")),
+            ("TMPDIR", None),
+            ("TMP", Some("/tmp")),
+            ("APP_DATA_DIR", Some("/var/lib/app")),
+            ("PATHS_APP_DATA_DIR", Some("/srv/app")),
+        ],
+        || {
+            assert_eq!(
+                Paths::from_env().unwrap(),
+                Paths {
+                    home: PathBuf::from("/home/app"),
+                    tmpdir: Some("/tmp".to_string()),
+                    data_dir: PathBuf::from("/var/lib/app"),
+                }
+            );
+            assert_eq!(
+                Config::from_env().unwrap().paths,
+                Paths {
+                    home: PathBuf::from("/home/app"),
+                    tmpdir: Some("/tmp".to_string()),
+                    data_dir: PathBuf::from("/srv/app"),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_no_prefix_env_vars() {
+    let names = Config::env_vars()
+        .into_iter()
+        .map(|spec| spec.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["HOME", "TMPDIR", "TMP", "PATHS_APP_DATA_DIR"]);
+}
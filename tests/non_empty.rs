@@ -0,0 +1,79 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Cache {
+    #[from_env(non_empty)]
+    redis_url: String,
+    #[from_env(non_empty, default = "60")]
+    ttl: u64,
+    #[from_env(non_empty = "trim")]
+    namespace: Option<String>,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(non_empty = "trim")]
+struct Service {
+    host: Option<String>,
+    #[from_env(default = "8080")]
+    port: u16,
+}
+
+#[test]
+fn test_empty_values_are_unset() {
+    with_vars(
+        vec![
+            ("REDIS_URL", Some("redis://cache")),
+            ("TTL", Some("")),
+            ("NAMESPACE", Some("  ")),
+            ("NAME", Some("")),
+        ],
+        || {
+            assert_eq!(
+                Cache::from_env().unwrap(),
+                Cache {
+                    redis_url: "redis://cache".to_string(),
+                    ttl: 60,
+                    namespace: None,
+                    name: String::new(),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_empty_required_value_is_missing() {
+    with_vars(
+        vec![
+            ("REDIS_URL", Some("")),
+            ("TTL", None),
+            ("NAMESPACE", None),
+            ("NAME", Some("cache")),
+        ],
+        || {
+            assert_eq!(
+                Cache::from_env().unwrap_err(),
+                FromEnvError::MissingEnvVar {
+                    var_name: "REDIS_URL".into(),
+                    origin: Origin::ProcessEnv,
+                    fallbacks: Vec::new(),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_struct_non_empty() {
+    with_vars(vec![("HOST", Some(" \t")), ("PORT", Some(" "))], || {
+        assert_eq!(
+            Service::from_env().unwrap(),
+            Service {
+                host: None,
+                port: 8080,
+            }
+        );
+    });
+}
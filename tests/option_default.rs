@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+
+use derive_from_env::{FromEnv, FromEnvError, MergePolicy, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(merge)]
+struct Config {
+    #[from_env(default = "info")]
+    log_level: Option<String>,
+    #[from_env(default = "30")]
+    timeout: Option<u64>,
+    #[from_env(default = "/var/cache/app")]
+    cache_dir: Option<PathBuf>,
+    #[from_env(default = "8", non_empty)]
+    workers: Option<usize>,
+}
+
+const VARS: [&str; 4] = ["LOG_LEVEL", "TIMEOUT", "CACHE_DIR", "WORKERS"];
+
+#[test]
+fn test_option_default_when_unset() {
+    with_vars(VARS.map(|name| (name, None::<&str>)), || {
+        assert_eq!(
+            Config::from_env().unwrap(),
+            Config {
+                log_level: Some("info".to_string()),
+                timeout: Some(30),
+                cache_dir: Some(PathBuf::from("/var/cache/app")),
+                workers: Some(8),
+            }
+        );
+    });
+}
+
+#[test]
+fn test_option_default_overridden() {
+    with_vars(
+        [
+            ("LOG_LEVEL", Some("debug")),
+            ("TIMEOUT", Some("5")),
+            ("CACHE_DIR", Some("/tmp/app")),
+            ("WORKERS", Some("")),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap(),
+                Config {
+                    log_level: Some("debug".to_string()),
+                    timeout: Some(5),
+                    cache_dir: Some(PathBuf::from("/tmp/app")),
+                    workers: Some(8),
+                }
+            );
+        },
+    );
+    with_vars([("TIMEOUT", Some("soon"))], || {
+        assert_eq!(
+            Config::from_env().unwrap_err(),
+            FromEnvError::ParsingFailure {
+                var_name: "TIMEOUT".to_string(),
+                str_value: "soon".to_string(),
+                origin: Origin::ProcessEnv,
+                expected_type: "u64".to_string(),
+            }
+        );
+    });
+}
+
+#[test]
+fn test_option_default_merge() {
+    let base = Config {
+        log_level: Some("warn".to_string()),
+        timeout: Some(10),
+        cache_dir: None,
+        workers: Some(2),
+    };
+    let other = Config {
+        log_level: Some("info".to_string()),
+        timeout: Some(60),
+        cache_dir: Some(PathBuf::from("/var/cache/app")),
+        workers: None,
+    };
+    assert_eq!(
+        base.merge(other, MergePolicy::PreferNonDefault),
+        Config {
+            log_level: Some("warn".to_string()),
+            timeout: Some(60),
+            cache_dir: None,
+            workers: Some(2),
+        }
+    );
+}
@@ -0,0 +1,223 @@
+use derive_from_env::{
+    DirSource, DotenvSource, FromEnv, FromEnvError, Layered, Origin, ProcessEnv, Report,
+};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Server {
+    host: String,
+    port: u16,
+    #[from_env(default = "4")]
+    workers: u8,
+}
+
+fn dotenv(content: &str) -> (tempfile::TempDir, DotenvSource) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(".env.local");
+    std::fs::write(&path, content).unwrap();
+    let source = DotenvSource::from_path(path).unwrap();
+    (dir, source)
+}
+
+#[test]
+fn test_process_env_origin() {
+    with_vars(
+        vec![("ORIGIN_HOST", Some("localhost")), ("ORIGIN_PORT", None)],
+        || {
+            let error = Server::from_env_with_prefix("ORIGIN").unwrap_err();
+            assert_eq!(
+                error,
+                FromEnvError::MissingEnvVar {
+                    var_name: "ORIGIN_PORT".into(),
+                    origin: Origin::ProcessEnv,
+                    fallbacks: Vec::new(),
+                }
+            );
+            assert_eq!(
+                error.to_string(),
+                "missing environment variable ORIGIN_PORT"
+            );
+        },
+    )
+}
+
+#[test]
+fn test_dotenv_origin() {
+    let (dir, source) = dotenv(concat!(
+        "# server\n",
+        "\n",
+        "export HOST='local host'\n",
+        "PORT=\"http\" # quoted\n",
+    ));
+    let path = dir.path().join(".env.local");
+    let error = Server::from_source(&source).unwrap_err();
+    assert_eq!(
+        error,
+        FromEnvError::ParsingFailure {
+            var_name: "PORT".into(),
+            expected_type: "u16".into(),
+            str_value: "http".into(),
+            origin: Origin::DotenvFile {
+                path: path.clone(),
+                line: Some(4),
+            },
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "failed to parse PORT=\"http\" as u16 (value from {}:4)",
+            path.display()
+        )
+    );
+    assert_eq!(
+        Report(error).to_string(),
+        format!(
+            concat!(
+                "failed to load the configuration from the environment\n",
+                "  invalid value\n",
+                "    variable: PORT\n",
+                "    value:    \"http\"\n",
+                "    expected: u16\n",
+                "    origin:   {}:4",
+            ),
+            path.display()
+        )
+    );
+
+    let (dir, source) = dotenv("HOST=localhost\n");
+    assert_eq!(
+        Server::from_source(&source).unwrap_err(),
+        FromEnvError::MissingEnvVar {
+            var_name: "PORT".into(),
+            origin: Origin::DotenvFile {
+                path: dir.path().join(".env.local"),
+                line: None,
+            },
+            fallbacks: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn test_dotenv_values() {
+    let (_dir, source) = dotenv(concat!(
+        "HOST=\"db\\\"1\\\"\"\n",
+        "export PORT = 5432 # comment\n",
+        "WORKERS=8\n",
+        "WORKERS=9\n",
+    ));
+    assert_eq!(
+        Server::from_source(&source).unwrap(),
+        Server {
+            host: "db\"1\"".into(),
+            port: 5432,
+            workers: 9,
+        }
+    );
+}
+
+#[test]
+fn test_dotenv_syntax_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(".env");
+    std::fs::write(&path, "HOST=localhost\nPORT 8080\n").unwrap();
+    assert_eq!(
+        DotenvSource::from_path(&path).unwrap_err(),
+        FromEnvError::SourceFailure {
+            var_name: None,
+            message: format!("{} line 2: expected KEY=VALUE", path.display()),
+        }
+    );
+}
+
+#[test]
+fn test_layered_origin() {
+    let (dotenv_dir, source) = dotenv("HOST=localhost\nPORT=8080\nWORKERS=many\n");
+    let files = tempfile::tempdir().unwrap();
+    std::fs::write(files.path().join("LAYERED_PORT"), "http\n").unwrap();
+    let layered = Layered::new().layer(ProcessEnv).layer(source);
+    with_vars(
+        vec![("HOST", None::<&str>), ("PORT", None), ("WORKERS", None)],
+        || {
+            assert_eq!(
+                Server::from_source(&layered).unwrap_err().origin(),
+                Some(&Origin::DotenvFile {
+                    path: dotenv_dir.path().join(".env.local"),
+                    line: Some(3),
+                })
+            );
+        },
+    );
+    let layered = Layered::new()
+        .layer(ProcessEnv)
+        .layer(DirSource::new(files.path()));
+    with_vars(
+        vec![("LAYERED_HOST", Some("localhost")), ("LAYERED_PORT", None)],
+        || {
+            assert_eq!(
+                Server::from_source_with_prefix(&layered, "LAYERED")
+                    .unwrap_err()
+                    .origin(),
+                Some(&Origin::File(files.path().join("LAYERED_PORT")))
+            );
+        },
+    );
+}
+
+#[test]
+fn test_default_origin() {
+    #[derive(Debug, FromEnv)]
+    #[allow(dead_code)]
+    struct BadDefault {
+        #[from_env(default = "many")]
+        workers: u8,
+    }
+    with_vars(vec![("BAD_DEFAULT_WORKERS", None::<&str>)], || {
+        let error = BadDefault::from_env_with_prefix("BAD_DEFAULT").unwrap_err();
+        assert_eq!(error.origin(), Some(&Origin::Default));
+        assert_eq!(
+            error.to_string(),
+            "failed to parse BAD_DEFAULT_WORKERS=\"many\" as u8 (value from default attribute)"
+        );
+    });
+}
+
+#[test]
+fn test_provenance_text() {
+    let files = tempfile::tempdir().unwrap();
+    std::fs::write(files.path().join("APP_HOST"), "localhost\n").unwrap();
+    std::fs::write(files.path().join("APP_PORT"), "http\n").unwrap();
+    let error = Server::from_source_with_prefix(&DirSource::new(files.path()), "APP").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "failed to parse APP_PORT=\"http\" as u16 (value from {})",
+            files.path().join("APP_PORT").display()
+        )
+    );
+
+    let (dir, source) = dotenv("HOST=localhost\n");
+    assert_eq!(
+        Server::from_source(&source).unwrap_err().to_string(),
+        format!(
+            "missing environment variable PORT (looked up in {})",
+            dir.path().join(".env.local").display()
+        )
+    );
+
+    with_vars(
+        vec![
+            ("PROVENANCE_HOST", Some("localhost")),
+            ("PROVENANCE_PORT", Some("http")),
+        ],
+        || {
+            assert_eq!(
+                Server::from_env_with_prefix("PROVENANCE")
+                    .unwrap_err()
+                    .to_string(),
+                "failed to parse PROVENANCE_PORT=\"http\" as u16 (value from process environment)"
+            );
+        },
+    );
+}
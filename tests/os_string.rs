@@ -0,0 +1,117 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    label: OsString,
+    #[from_env(default = "/tmp")]
+    work_dir: OsString,
+    extra: Option<std::ffi::OsString>,
+}
+
+#[test]
+fn test_os_string() {
+    with_vars(
+        vec![("LABEL", Some("café")), ("WORK_DIR", None), ("EXTRA", None)],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap(),
+                Config {
+                    label: "café".into(),
+                    work_dir: "/tmp".into(),
+                    extra: None,
+                }
+            );
+        },
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_os_string_not_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let value = OsStr::from_bytes(b"caf\xe9");
+    with_vars(
+        vec![
+            ("LABEL", Some(value)),
+            ("WORK_DIR", Some(value)),
+            ("EXTRA", Some(value)),
+        ],
+        || {
+            let config = Config::from_env().unwrap();
+            assert_eq!(config.label, value);
+            assert_eq!(config.work_dir, value);
+            assert_eq!(config.extra.as_deref(), Some(value));
+        },
+    );
+}
+
+#[test]
+fn test_os_string_missing() {
+    with_vars(vec![("LABEL", None::<&str>)], || {
+        assert_eq!(
+            Config::from_env().unwrap_err(),
+            FromEnvError::MissingEnvVar {
+                var_name: "LABEL".into(),
+                origin: Origin::ProcessEnv,
+                fallbacks: Vec::new(),
+            }
+        );
+    });
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Paths {
+    home: PathBuf,
+    #[from_env(var = "XDG_CACHE", default = "/var/cache")]
+    cache: PathBuf,
+    config: Option<PathBuf>,
+}
+
+#[cfg(unix)]
+#[test]
+fn test_path_buf_not_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let home = OsStr::from_bytes(b"/home/j\xf6rg");
+    with_vars(
+        vec![
+            ("HOME", Some(home)),
+            ("XDG_CACHE", None),
+            ("CONFIG", Some(home)),
+        ],
+        || {
+            assert_eq!(
+                Paths::from_env().unwrap(),
+                Paths {
+                    home: home.into(),
+                    cache: "/var/cache".into(),
+                    config: Some(home.into()),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_path_buf_missing() {
+    with_vars(
+        vec![("HOME", None::<&str>), ("XDG_CACHE", Some("/cache"))],
+        || {
+            assert_eq!(
+                Paths::from_env().unwrap_err(),
+                FromEnvError::MissingEnvVar {
+                    var_name: "HOME".into(),
+                    origin: Origin::ProcessEnv,
+                    fallbacks: Vec::new(),
+                }
+            );
+        },
+    );
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq)]
+struct Bytes(u64);
+
+#[derive(Debug)]
+struct SizeError(String);
+
+impl fmt::Display for SizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown unit {:?}, expected K, M or G", self.0)
+    }
+}
+
+mod parsers {
+    use super::{Bytes, SizeError};
+
+    /// Parses sizes such as `512`, `64K` or `2G`.
+    pub fn size(value: &str) -> Result<Bytes, SizeError> {
+        let (digits, unit) = value.split_at(
+            value
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(value.len()),
+        );
+        let number = digits
+            .parse::<u64>()
+            .map_err(|_| SizeError(value.to_string()))?;
+        let factor = match unit {
+            "" => 1,
+            "K" => 1 << 10,
+            "M" => 1 << 20,
+            "G" => 1 << 30,
+            unit => return Err(SizeError(unit.to_string())),
+        };
+        Ok(Bytes(number * factor))
+    }
+
+    pub fn port(value: &str) -> Result<u16, String> {
+        value
+            .strip_prefix(':')
+            .ok_or_else(|| "missing leading ':'".to_string())?
+            .parse()
+            .map_err(|error| format!("{}", error))
+    }
+
+    /// Expands a leading `~/` to the home directory of the service.
+    pub fn home_path(value: &str) -> Result<std::path::PathBuf, String> {
+        match value.strip_prefix("~/") {
+            Some(rest) => Ok(std::path::Path::new("/home/app").join(rest)),
+            None if value.starts_with('/') => Ok(value.into()),
+            None => Err("expected an absolute path or one starting with ~/".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(parse_with = "parsers::size")]
+    cache_size: Bytes,
+    #[from_env(parse_with = "parsers::size", default = "1M")]
+    upload_limit: Bytes,
+    #[from_env(parse_with = "parsers::size", var = "MAX_BODY")]
+    body_limit: Option<Bytes>,
+    #[from_env(parse_with = "parsers::port")]
+    ports: Vec<u16>,
+    #[from_env(parse_with = "parsers::size")]
+    quotas: HashMap<String, Bytes>,
+}
+
+#[test]
+fn test_parse_with() {
+    with_vars(
+        vec![
+            ("CACHE_SIZE", Some("64K")),
+            ("UPLOAD_LIMIT", None),
+            ("MAX_BODY", Some("2M")),
+            ("PORTS", Some(":80,:443")),
+            ("QUOTAS", Some("alice=1G,bob=512")),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap(),
+                Config {
+                    cache_size: Bytes(64 << 10),
+                    upload_limit: Bytes(1 << 20),
+                    body_limit: Some(Bytes(2 << 20)),
+                    ports: vec![80, 443],
+                    quotas: HashMap::from([
+                        ("alice".to_string(), Bytes(1 << 30)),
+                        ("bob".to_string(), Bytes(512)),
+                    ]),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_parse_with_unset_option() {
+    with_vars(
+        vec![
+            ("CACHE_SIZE", Some("1")),
+            ("UPLOAD_LIMIT", Some("3G")),
+            ("MAX_BODY", None),
+            ("PORTS", Some(":8080")),
+            ("QUOTAS", Some("")),
+        ],
+        || {
+            let config = Config::from_env().unwrap();
+            assert_eq!(config.upload_limit, Bytes(3 << 30));
+            assert_eq!(config.body_limit, None);
+        },
+    );
+}
+
+#[test]
+fn test_parse_with_error_message() {
+    with_vars(
+        vec![
+            ("CACHE_SIZE", Some("64T")),
+            ("UPLOAD_LIMIT", None),
+            ("MAX_BODY", None),
+            ("PORTS", Some(":80")),
+            ("QUOTAS", Some("")),
+        ],
+        || {
+            let error = Config::from_env().unwrap_err();
+            assert_eq!(
+                error,
+                FromEnvError::ParsingFailure {
+                    var_name: "CACHE_SIZE".to_string(),
+                    str_value: "64T".to_string(),
+                    origin: Origin::ProcessEnv,
+                    expected_type: "Bytes (unknown unit \"T\", expected K, M or G)".to_string(),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_parse_with_item_error_message() {
+    with_vars(
+        vec![
+            ("CACHE_SIZE", Some("1")),
+            ("UPLOAD_LIMIT", None),
+            ("MAX_BODY", None),
+            ("PORTS", Some(":80,443")),
+            ("QUOTAS", Some("")),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "PORTS".to_string(),
+                    str_value: "443".to_string(),
+                    origin: Origin::ProcessEnv,
+                    expected_type: "u16 item of a list separated by \",\" (missing leading ':')"
+                        .to_string(),
+                }
+            );
+        },
+    );
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Paths {
+    #[from_env(parse_with = "parsers::home_path")]
+    data_dir: PathBuf,
+}
+
+#[test]
+fn test_parse_with_path_buf() {
+    with_vars([("DATA_DIR", Some("~/data"))], || {
+        assert_eq!(
+            Paths::from_env().unwrap().data_dir,
+            PathBuf::from("/home/app/data")
+        );
+    });
+    with_vars([("DATA_DIR", Some("data"))], || {
+        assert_eq!(
+            Paths::from_env().unwrap_err(),
+            FromEnvError::ParsingFailure {
+                var_name: "DATA_DIR".to_string(),
+                str_value: "data".to_string(),
+                origin: Origin::ProcessEnv,
+                expected_type: "PathBuf (expected an absolute path or one starting with ~/)"
+                    .to_string(),
+            }
+        );
+    });
+}
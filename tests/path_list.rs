@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Plugins {
+    #[from_env(path_list)]
+    plugin_dirs: Vec<PathBuf>,
+    #[from_env(path_list)]
+    extra_dirs: Option<Vec<PathBuf>>,
+    #[from_env(path_list, default = "")]
+    search_dirs: Vec<PathBuf>,
+}
+
+#[cfg(unix)]
+#[test]
+fn test_path_list_unix() {
+    with_vars(
+        vec![
+            ("PLUGIN_DIRS", Some("/usr/lib/plugins:/opt/my plugins;v2")),
+            ("EXTRA_DIRS", Some("::/extra:")),
+            ("SEARCH_DIRS", None),
+        ],
+        || {
+            assert_eq!(
+                Plugins::from_env().unwrap(),
+                Plugins {
+                    plugin_dirs: vec![
+                        PathBuf::from("/usr/lib/plugins"),
+                        PathBuf::from("/opt/my plugins;v2")
+                    ],
+                    extra_dirs: Some(vec![PathBuf::from("/extra")]),
+                    search_dirs: vec![],
+                }
+            );
+        },
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn test_path_list_non_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let value = OsStr::from_bytes(b"/tmp/caf\xe9:/tmp/b");
+    with_vars(
+        vec![
+            ("PLUGIN_DIRS", Some(value)),
+            ("EXTRA_DIRS", None),
+            ("SEARCH_DIRS", None),
+        ],
+        || {
+            assert_eq!(
+                Plugins::from_env().unwrap().plugin_dirs,
+                vec![
+                    PathBuf::from(OsStr::from_bytes(b"/tmp/caf\xe9")),
+                    PathBuf::from("/tmp/b")
+                ]
+            );
+        },
+    )
+}
+
+#[cfg(windows)]
+#[test]
+fn test_path_list_windows() {
+    with_vars(
+        vec![
+            ("PLUGIN_DIRS", Some(r#"C:\plugins;;"D:\my;plugins""#)),
+            ("EXTRA_DIRS", None),
+            ("SEARCH_DIRS", Some(r"C:\search")),
+        ],
+        || {
+            assert_eq!(
+                Plugins::from_env().unwrap(),
+                Plugins {
+                    plugin_dirs: vec![
+                        PathBuf::from(r"C:\plugins"),
+                        PathBuf::from(r"D:\my;plugins")
+                    ],
+                    extra_dirs: None,
+                    search_dirs: vec![PathBuf::from(r"C:\search")],
+                }
+            );
+        },
+    )
+}
+
+#[test]
+fn test_path_list_missing() {
+    with_vars(
+        vec![
+            ("PLUGIN_DIRS", None::<&str>),
+            ("EXTRA_DIRS", None),
+            ("SEARCH_DIRS", None),
+        ],
+        || {
+            assert_eq!(
+                Plugins::from_env().unwrap_err(),
+                FromEnvError::MissingEnvVar {
+                    var_name: "PLUGIN_DIRS".into(),
+                    origin: Origin::ProcessEnv,
+                    fallbacks: Vec::new(),
+                }
+            );
+        },
+    )
+}
+
+#[test]
+fn test_split_path_list_platform_separator() {
+    use derive_from_env::_inner_helpers::split_path_list;
+
+    let separator = if cfg!(windows) { ";" } else { ":" };
+    let value = ["a", "", "b"].join(separator);
+    assert_eq!(
+        split_path_list(value.as_ref()),
+        vec![PathBuf::from("a"), PathBuf::from("b")]
+    );
+    assert_eq!(split_path_list("".as_ref()), Vec::<PathBuf>::new());
+}
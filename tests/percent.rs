@@ -0,0 +1,80 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Cache {
+    #[from_env(percent)]
+    hit_target: f64,
+    #[from_env(percent = "require_sign", default = "50%")]
+    eviction_threshold: f32,
+}
+
+#[test]
+fn test_percent() {
+    with_vars(
+        vec![
+            ("CACHE_HIT_TARGET", Some("95%")),
+            ("CACHE_EVICTION_THRESHOLD", None),
+        ],
+        || {
+            assert_eq!(
+                Cache::from_env_with_prefix("CACHE").unwrap(),
+                Cache {
+                    hit_target: 0.95,
+                    eviction_threshold: 0.5
+                }
+            );
+        },
+    );
+    with_vars(
+        vec![
+            ("CACHE_HIT_TARGET", Some(" 0.9 ")),
+            ("CACHE_EVICTION_THRESHOLD", Some(" 80 % ")),
+        ],
+        || {
+            assert_eq!(
+                Cache::from_env_with_prefix("CACHE").unwrap(),
+                Cache {
+                    hit_target: 0.9,
+                    eviction_threshold: 0.8
+                }
+            );
+        },
+    )
+}
+
+#[test]
+fn test_percent_errors() {
+    with_vars(
+        vec![
+            ("CACHE_HIT_TARGET", Some("95%%")),
+            ("CACHE_EVICTION_THRESHOLD", None),
+        ],
+        || {
+            assert_eq!(
+                Cache::from_env_with_prefix("CACHE").unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "CACHE_HIT_TARGET".into(),
+                    expected_type: "f64".into(),
+                    str_value: "95%%".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+        },
+    );
+    with_vars(
+        vec![
+            ("CACHE_HIT_TARGET", Some("95%")),
+            ("CACHE_EVICTION_THRESHOLD", Some("0.8")),
+        ],
+        || {
+            assert_eq!(
+                Cache::from_env_with_prefix("CACHE").unwrap_err(),
+                FromEnvError::Custom {
+                    var_name: Some("CACHE_EVICTION_THRESHOLD".into()),
+                    message: "expected a percentage such as \"95%\", got \"0.8\"".into()
+                }
+            );
+        },
+    )
+}
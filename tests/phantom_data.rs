@@ -0,0 +1,35 @@
+use std::marker::PhantomData;
+
+use derive_from_env::FromEnv;
+use temp_env::with_vars;
+
+struct Production;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(diff, merge, cli_args)]
+struct Config<Env> {
+    port: u16,
+    _marker: PhantomData<Env>,
+    _optional_marker: Option<PhantomData<Env>>,
+}
+
+#[test]
+fn test_phantom_data_reads_nothing() {
+    with_vars(
+        vec![
+            ("PORT", Some("8080")),
+            ("_MARKER", None),
+            ("_OPTIONAL_MARKER", None),
+        ],
+        || {
+            let config = Config::<Production>::from_env().unwrap();
+            assert_eq!(config.port, 8080);
+            assert_eq!(config._optional_marker, Some(PhantomData));
+        },
+    );
+    let names = Config::<Production>::env_vars()
+        .into_iter()
+        .map(|spec| spec.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["PORT"]);
+}
@@ -0,0 +1,104 @@
+use derive_from_env::FromEnv;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "DB_")]
+struct Database {
+    host: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "DB_", collapse_prefix)]
+struct CollapsedDatabase {
+    host: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "DB_PRIMARY_", collapse_prefix)]
+struct PrimaryDatabase {
+    host: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "DATABASE_", collapse_prefix)]
+struct LongDatabase {
+    host: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    storage: Database,
+    database: CollapsedDatabase,
+    db: CollapsedDatabase,
+    main_db: CollapsedDatabase,
+    cache: CollapsedDatabase,
+    xdb: CollapsedDatabase,
+    #[from_env(flatten)]
+    primary: PrimaryDatabase,
+}
+
+#[test]
+fn test_struct_prefix() {
+    with_vars(
+        vec![("DB_HOST", Some("local")), ("APP_DB_HOST", Some("app"))],
+        || {
+            assert_eq!(Database::from_env().unwrap().host, "local");
+            assert_eq!(Database::from_env_with_prefix("APP").unwrap().host, "app");
+            assert_eq!(CollapsedDatabase::from_env().unwrap().host, "local");
+        },
+    )
+}
+
+#[test]
+fn test_collapse_prefix() {
+    with_vars(
+        vec![
+            ("STORAGE_DB_HOST", Some("storage")),
+            ("DATABASE_HOST", Some("database")),
+            ("DB_HOST", Some("db")),
+            ("MAIN_DB_HOST", Some("main")),
+            ("CACHE_DB_HOST", Some("cache")),
+            ("XDB_DB_HOST", Some("xdb")),
+            ("PRIMARY_DB_PRIMARY_HOST", Some("primary")),
+        ],
+        || {
+            let config = Config::from_env().unwrap();
+            // The struct prefix is simply appended to the field name...
+            assert_eq!(config.storage.host, "storage");
+            // ...unless collapsed: identical segments,
+            assert_eq!(config.db.host, "db");
+            // the child prefix abbreviating the incoming one,
+            assert_eq!(config.database.host, "database");
+            // the child prefix ending the incoming one,
+            assert_eq!(config.main_db.host, "main");
+            // unrelated segments are left untouched,
+            assert_eq!(config.cache.host, "cache");
+            // and segments are compared as a whole.
+            assert_eq!(config.xdb.host, "xdb");
+            assert_eq!(config.primary.host, "primary");
+        },
+    )
+}
+
+#[test]
+fn test_collapse_prefix_overlap() {
+    with_vars(vec![("APP_DB_PRIMARY_HOST", Some("primary"))], || {
+        // The incoming prefix ending with the start of the child prefix.
+        assert_eq!(
+            PrimaryDatabase::from_env_with_prefix("APP_DB")
+                .unwrap()
+                .host,
+            "primary"
+        );
+        assert_eq!(
+            PrimaryDatabase::from_env_with_prefix("APP_DB_PRIMARY")
+                .unwrap()
+                .host,
+            "primary"
+        );
+    });
+    with_vars(vec![("DB_HOST", Some("db"))], || {
+        // The incoming prefix abbreviating the child prefix.
+        assert_eq!(LongDatabase::from_env_with_prefix("DB").unwrap().host, "db");
+    })
+}
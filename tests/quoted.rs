@@ -0,0 +1,65 @@
+use std::collections::BTreeSet;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(quoted)]
+    headers: Vec<String>,
+    #[from_env(quoted, separator = ";", deny_duplicates)]
+    dsns: Option<BTreeSet<String>>,
+}
+
+fn load(headers: &str, dsns: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![("HEADERS", Some(headers)), ("DSNS", dsns)],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_quoted() {
+    let config = load(r#"X-Trace,"X-Forwarded, For", , "say ""hi""","""#, None).unwrap();
+    assert_eq!(
+        config.headers,
+        ["X-Trace", "X-Forwarded, For", "say \"hi\"", ""]
+    );
+    assert_eq!(config.dsns, None);
+    let config = load("a,b\"c", Some(r#""host=a;port=1" ; b"#)).unwrap();
+    assert_eq!(config.headers, ["a", "b\"c"]);
+    assert_eq!(
+        config.dsns,
+        Some(BTreeSet::from([
+            "host=a;port=1".to_string(),
+            "b".to_string()
+        ]))
+    );
+}
+
+#[test]
+fn test_quoted_failures() {
+    assert_eq!(
+        load(r#"X-Trace,"X-Forwarded, For"#, None).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "HEADERS".into(),
+            expected_type: "closing quote (item 2 of a list separated by \",\")".into(),
+            str_value: "\"X-Forwarded, For".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+    assert_eq!(
+        load(r#""a"b,c"#, None).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "HEADERS".into(),
+            expected_type: "separator after closing quote (item 1 of a list separated by \",\")"
+                .into(),
+            str_value: "\"a\"b".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+    assert_eq!(
+        load("a", Some(r#""x";x"#)).unwrap_err().to_string(),
+        "DSNS: duplicate item \"x\""
+    );
+}
@@ -0,0 +1,87 @@
+use std::ops::{Range, RangeInclusive};
+
+use derive_from_env::{FromEnv, FromEnvError};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Windows {
+    port_range: RangeInclusive<u16>,
+    batch_window: Range<u32>,
+    offsets: Option<RangeInclusive<i32>>,
+    #[from_env(default = "1..4")]
+    retries: Range<u8>,
+}
+
+fn load(
+    port_range: &str,
+    batch_window: &str,
+    offsets: Option<&str>,
+) -> Result<Windows, FromEnvError> {
+    with_vars(
+        vec![
+            ("WINDOWS_PORT_RANGE", Some(port_range)),
+            ("WINDOWS_BATCH_WINDOW", Some(batch_window)),
+            ("WINDOWS_OFFSETS", offsets),
+            ("WINDOWS_RETRIES", None),
+        ],
+        || Windows::from_env_with_prefix("WINDOWS"),
+    )
+}
+
+#[test]
+fn test_range_forms() {
+    assert_eq!(
+        load("8000-8999", "0..60", None).unwrap(),
+        Windows {
+            port_range: 8000..=8999,
+            batch_window: 0..60,
+            offsets: None,
+            retries: 1..4,
+        }
+    );
+    let windows = load("8000..=8999", "10-20", Some("-5--1")).unwrap();
+    assert_eq!(windows.port_range, 8000..=8999);
+    assert_eq!(windows.batch_window, 10..20);
+    assert_eq!(windows.offsets, Some(-5..=-1));
+    assert_eq!(
+        load("1 - 2", "0..0", Some("-5..=3")).unwrap().offsets,
+        Some(-5..=3)
+    );
+}
+
+fn custom_message(error: FromEnvError) -> (Option<String>, String) {
+    match error {
+        FromEnvError::Custom { var_name, message } => (var_name, message),
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn test_range_errors() {
+    assert_eq!(
+        custom_message(load("8999-8000", "0..60", None).unwrap_err()),
+        (
+            Some("WINDOWS_PORT_RANGE".into()),
+            "the start of the range \"8999-8000\" is greater than its end".into()
+        )
+    );
+    assert_eq!(
+        custom_message(load("8000-http", "0..60", None).unwrap_err()),
+        (
+            Some("WINDOWS_PORT_RANGE".into()),
+            "invalid end bound \"http\", expected u16".into()
+        )
+    );
+    assert_eq!(
+        custom_message(load("8000-8999", "0..=60", None).unwrap_err()).1,
+        "expected an exclusive range such as \"1..10\", got \"0..=60\""
+    );
+    assert_eq!(
+        custom_message(load("8000..8999", "0..60", None).unwrap_err()).1,
+        "expected an inclusive range such as \"1..=10\", got \"8000..8999\""
+    );
+    assert_eq!(
+        custom_message(load("8000", "0..60", None).unwrap_err()).1,
+        "expected a range such as \"1-10\" or \"1..=10\", got \"8000\""
+    );
+}
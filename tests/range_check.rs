@@ -0,0 +1,75 @@
+use derive_from_env::{FromEnv, FromEnvError};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct Config {
+    #[from_env(range = "1..=65535", default = "0")]
+    port: u32,
+    #[from_env(range = "0.0..1.0")]
+    ratio: Option<f64>,
+    #[from_env(range = "1024..")]
+    buffers: Vec<usize>,
+    #[from_env(range = "-10..10", default = "0")]
+    offset: i8,
+}
+
+fn load(vars: [Option<&str>; 4]) -> Result<Config, FromEnvError> {
+    with_vars(
+        [
+            ("APP_PORT", vars[0]),
+            ("APP_RATIO", vars[1]),
+            ("APP_BUFFERS", vars[2]),
+            ("APP_OFFSET", vars[3]),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_values_in_range() {
+    assert_eq!(
+        load([Some("65535"), Some("0.5"), Some("1024,4096"), Some("-10")]).unwrap(),
+        Config {
+            port: 65535,
+            ratio: Some(0.5),
+            buffers: vec![1024, 4096],
+            offset: -10,
+        }
+    );
+}
+
+#[test]
+fn test_values_out_of_range() {
+    assert_eq!(
+        load([Some("65536"), None, Some(""), None]).unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "APP_PORT".to_string(),
+            message: "65536 is out of the range 1..=65535".to_string(),
+        }
+    );
+    assert_eq!(
+        load([Some("80"), Some("1"), Some(""), None])
+            .unwrap_err()
+            .to_string(),
+        "invalid value of APP_RATIO: 1 is out of the range 0.0..1.0"
+    );
+    assert_eq!(
+        load([Some("80"), None, Some("2048,512"), Some("10")]).unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "APP_BUFFERS".to_string(),
+            message: "512 is out of the range 1024..".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_default_out_of_range() {
+    assert_eq!(
+        load([None, None, Some(""), None]).unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "APP_PORT".to_string(),
+            message: "0 is out of the range 1..=65535".to_string(),
+        }
+    );
+}
@@ -0,0 +1,53 @@
+use derive_from_env::FromEnv;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Definition {
+    name: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct Config {
+    r#type: String,
+    r#async: bool,
+    #[from_env(var = "LOOP_KIND")]
+    r#loop: String,
+    #[from_env(flatten)]
+    r#struct: Definition,
+}
+
+#[test]
+fn test_raw_identifiers() {
+    with_vars(
+        vec![
+            ("APP_TYPE", Some("worker")),
+            ("APP_ASYNC", Some("true")),
+            ("LOOP_KIND", Some("event")),
+            ("APP_STRUCT_NAME", Some("jobs")),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap(),
+                Config {
+                    r#type: "worker".to_string(),
+                    r#async: true,
+                    r#loop: "event".to_string(),
+                    r#struct: Definition {
+                        name: "jobs".to_string(),
+                    },
+                }
+            );
+        },
+    );
+    let specs = Config::env_vars();
+    let names = specs
+        .iter()
+        .map(|spec| spec.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["APP_TYPE", "APP_ASYNC", "LOOP_KIND", "APP_STRUCT_NAME"]
+    );
+    assert_eq!(specs[3].field, "struct.name");
+}
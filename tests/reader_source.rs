@@ -0,0 +1,81 @@
+use std::io::Cursor;
+
+use derive_from_env::{EnvSource, FromEnv, FromEnvError, Layered, Origin, ReaderSource};
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Secrets {
+    api_token: String,
+    db_password: String,
+    greeting: String,
+    port: u16,
+}
+
+const INPUT: &str = r#"
+# secrets passed through a pipe
+
+export API_TOKEN='tok#en with "quotes"'
+DB_PASSWORD="line\nbreak \"quoted\""   # trailing comment
+GREETING = hello world # comment
+PORT=8080
+
+PORT=9090
+"#;
+
+#[test]
+fn test_reader_source() {
+    let source = ReaderSource::from_reader(Cursor::new(INPUT)).unwrap();
+    assert_eq!(
+        Secrets::from_source(&source).unwrap(),
+        Secrets {
+            api_token: "tok#en with \"quotes\"".into(),
+            db_password: "line\nbreak \"quoted\"".into(),
+            greeting: "hello world".into(),
+            port: 9090,
+        }
+    );
+    assert_eq!(source.origin("PORT"), Origin::Remote("input line 9".into()));
+    assert_eq!(
+        source.keys().unwrap(),
+        vec!["API_TOKEN", "DB_PASSWORD", "GREETING", "PORT"]
+    );
+}
+
+#[test]
+fn test_reader_source_layered() {
+    let overrides = ReaderSource::from_reader(Cursor::new("PORT=7070\n")).unwrap();
+    let base = ReaderSource::from_reader(Cursor::new(INPUT)).unwrap();
+    let source = Layered::new().layer(overrides).layer(base);
+    let secrets = Secrets::from_source(&source).unwrap();
+    assert_eq!(secrets.port, 7070);
+    assert_eq!(secrets.greeting, "hello world");
+}
+
+fn source_failure(error: FromEnvError) -> String {
+    match error {
+        FromEnvError::SourceFailure { message, .. } => message,
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn test_reader_source_errors() {
+    assert_eq!(
+        source_failure(ReaderSource::from_reader_strict(Cursor::new(INPUT)).unwrap_err()),
+        "line 9: PORT is already set on line 7"
+    );
+    let malformed = "API_TOKEN=abc\n\n# comment\njust some text\nPORT=1\n";
+    assert_eq!(
+        source_failure(ReaderSource::from_reader_strict(Cursor::new(malformed)).unwrap_err()),
+        "line 4: expected KEY=VALUE"
+    );
+    assert_eq!(
+        source_failure(ReaderSource::from_reader(Cursor::new("A='unterminated\n")).unwrap_err()),
+        "line 1: unterminated single quote"
+    );
+    assert_eq!(
+        source_failure(
+            ReaderSource::from_reader_strict(Cursor::new(vec![b'A', b'=', 0xff])).unwrap_err()
+        ),
+        "failed to read the input: stream did not contain valid UTF-8"
+    );
+}
@@ -0,0 +1,55 @@
+use derive_from_env::{FromEnv, FromEnvError, MainResult, Origin, Report};
+use temp_env::with_vars;
+
+#[derive(Debug, FromEnv)]
+struct Config {
+    #[allow(dead_code)]
+    port: u16,
+}
+
+fn run() -> MainResult<Config> {
+    Ok(Config::from_env_with_prefix("APP")?)
+}
+
+#[test]
+fn test_report_missing_var() {
+    with_vars(vec![("APP_PORT", None::<&str>)], || {
+        let report = run().unwrap_err();
+        assert_eq!(
+            format!("{:?}", report),
+            "failed to load the configuration from the environment\n  missing environment variable\n    variable: APP_PORT"
+        );
+    })
+}
+
+#[test]
+fn test_report_parsing_failure() {
+    with_vars(vec![("APP_PORT", Some("http"))], || {
+        let report = run().unwrap_err();
+        assert_eq!(
+            report.error(),
+            &FromEnvError::ParsingFailure {
+                var_name: "APP_PORT".into(),
+                expected_type: "u16".into(),
+                str_value: "http".into(),
+                origin: Origin::ProcessEnv,
+            }
+        );
+        assert_eq!(
+            report.to_string(),
+            "failed to load the configuration from the environment\n  invalid value\n    variable: APP_PORT\n    value:    \"http\"\n    expected: u16"
+        );
+    })
+}
+
+#[test]
+fn test_report_custom() {
+    let report = Report::from(FromEnvError::Custom {
+        var_name: Some("APP_MODE".into()),
+        message: "unknown variant `slow`".into(),
+    });
+    assert_eq!(
+        format!("{:?}", report),
+        "failed to load the configuration from the environment\n  invalid value\n    variable: APP_MODE\n    error:    unknown variant `slow`"
+    );
+}
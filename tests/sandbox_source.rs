@@ -0,0 +1,67 @@
+use derive_from_env::{EnvSource, FromEnv, FromEnvError, ProcessEnv, SandboxSource};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    app_name: String,
+    #[from_env(var = "HOME")]
+    home: Option<String>,
+}
+
+fn with_env<R>(f: impl FnOnce() -> R) -> R {
+    with_vars(
+        vec![
+            ("APP_NAME", Some("demo")),
+            ("HOME", Some("/home/demo")),
+            ("SECRET_TOKEN", Some("s3cr3t")),
+        ],
+        f,
+    )
+}
+
+#[test]
+fn test_sandbox_blocks_unlisted_variables() {
+    with_env(|| {
+        let source = SandboxSource::new(ProcessEnv, &["APP_*"]);
+        assert_eq!(
+            Config::from_source(&source).unwrap(),
+            Config {
+                app_name: "demo".into(),
+                home: None,
+            }
+        );
+        assert_eq!(source.get("SECRET_TOKEN").unwrap(), None);
+        assert!(!source.keys().unwrap().contains(&"SECRET_TOKEN".to_string()));
+
+        let error = Config::from_source(&source.strict()).unwrap_err();
+        assert_eq!(
+            error,
+            FromEnvError::SourceFailure {
+                var_name: Some("HOME".into()),
+                message: "HOME is not in the allowlist of the source".into(),
+            }
+        );
+    })
+}
+
+#[test]
+fn test_sandbox_allows_listed_variables() {
+    with_env(|| {
+        let source = SandboxSource::new(ProcessEnv, &["APP_*", "HOME"]).strict();
+        assert_eq!(
+            Config::from_source(&source).unwrap(),
+            Config {
+                app_name: "demo".into(),
+                home: Some("/home/demo".into()),
+            }
+        );
+        assert!(source.get("SECRET_TOKEN").is_err());
+
+        let source = SandboxSource::from_specs(ProcessEnv, &Config::env_vars()).strict();
+        assert_eq!(
+            Config::from_source(&source).unwrap().home.as_deref(),
+            Some("/home/demo")
+        );
+        assert!(source.get("APP_OTHER").is_err());
+    })
+}
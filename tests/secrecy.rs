@@ -0,0 +1,51 @@
+#![cfg(feature = "secrecy")]
+
+use derive_from_env::secrecy::{ExposeSecret, SecretBox, SecretString};
+use derive_from_env::{FromEnv, FromEnvError, Location, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, FromEnv)]
+struct Config {
+    api_key: SecretString,
+    pin: SecretBox<u32>,
+    previous_key: Option<SecretString>,
+}
+
+#[test]
+fn test_secrecy() {
+    with_vars(
+        vec![
+            ("API_KEY", Some("hunter2")),
+            ("PIN", Some("1234")),
+            ("PREVIOUS_KEY", None),
+        ],
+        || {
+            let config = Config::from_env().unwrap();
+            assert_eq!(config.api_key.expose_secret(), "hunter2");
+            assert_eq!(*config.pin.expose_secret(), 1234);
+            assert!(config.previous_key.is_none());
+            assert!(!format!("{:?}", config).contains("hunter2"));
+            let plan = Config::explain("api_key").unwrap();
+            assert_eq!(plan.steps[0].location, Location::Variable("API_KEY".into()));
+            assert_eq!(plan.steps[0].value.as_deref(), Some("<redacted>"));
+        },
+    );
+}
+
+#[test]
+fn test_secrecy_failure_hides_value() {
+    with_vars(
+        vec![("API_KEY", Some("hunter2")), ("PIN", Some("12x4"))],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "PIN".into(),
+                    expected_type: "u32".into(),
+                    str_value: "<redacted>".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+        },
+    );
+}
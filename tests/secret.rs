@@ -0,0 +1,260 @@
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
+
+use derive_from_env::{EnvWarningKind, FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+mod parsers {
+    pub fn pin(value: &str) -> Result<u32, String> {
+        value
+            .parse()
+            .map_err(|_| format!("{:?} is not a PIN", value))
+    }
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "DB_")]
+struct DbConfig {
+    #[from_env(secret)]
+    port: u16,
+    #[from_env(secret, parse_with = "parsers::pin")]
+    pin: Option<u32>,
+    #[from_env(secret, range = "1..=10", default = "5")]
+    pool: u8,
+    #[from_env(secret, min_len = 8)]
+    password: String,
+    user: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "VAULT_")]
+struct VaultConfig {
+    #[from_env(secret, default = "")]
+    passwords: HashMap<String, String>,
+    #[from_env(secret, deny_duplicates, default = "")]
+    tokens: BTreeSet<String>,
+    #[from_env(secret, count_var = "VAULT_PIN_COUNT")]
+    pins: Option<Vec<u16>>,
+    #[from_env(secret, default = "0..1")]
+    window: Range<u32>,
+}
+
+fn load(vars: [Option<&str>; 5]) -> Result<DbConfig, FromEnvError> {
+    with_vars(
+        [
+            ("DB_PORT", vars[0]),
+            ("DB_PIN", vars[1]),
+            ("DB_POOL", vars[2]),
+            ("DB_PASSWORD", vars[3]),
+            ("DB_USER", vars[4]),
+        ],
+        DbConfig::from_env,
+    )
+}
+
+#[test]
+fn test_parsing_failure_is_redacted() {
+    let error = load([Some("hunter2"), None, None, Some("12345678"), Some("app")]).unwrap_err();
+    assert_eq!(
+        error,
+        FromEnvError::ParsingFailure {
+            var_name: "DB_PORT".to_string(),
+            expected_type: "u16".to_string(),
+            str_value: "<redacted>".to_string(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+    assert!(!error.to_string().contains("hunter2"));
+    let error = load([
+        Some("5432"),
+        Some("hunter2"),
+        None,
+        Some("12345678"),
+        Some("app"),
+    ])
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("<redacted>"), "{}", error);
+    assert!(!error.contains("hunter2"), "{}", error);
+}
+
+#[test]
+fn test_quoted_value_is_redacted() {
+    let error = load([
+        Some("5432"),
+        Some("hun\"ter2"),
+        None,
+        Some("12345678"),
+        Some("app"),
+    ])
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("(\"<redacted>\" is not a PIN)"), "{}", error);
+    assert!(!error.contains("ter2"), "{}", error);
+}
+
+#[test]
+fn test_collection_failures_are_redacted() {
+    let load = |passwords, tokens| {
+        with_vars(
+            [
+                ("VAULT_PASSWORDS", passwords),
+                ("VAULT_TOKENS", tokens),
+                ("VAULT_PIN_COUNT", None),
+            ],
+            VaultConfig::from_env,
+        )
+    };
+    assert_eq!(
+        load(Some("user=hunter2,user=hunter3"), None).unwrap_err(),
+        FromEnvError::Custom {
+            var_name: Some("VAULT_PASSWORDS".to_string()),
+            message: "duplicate key <redacted> in entry 2 <redacted>".to_string(),
+        }
+    );
+    assert_eq!(
+        load(Some("user=hunter2,=hunter3"), None).unwrap_err(),
+        FromEnvError::Custom {
+            var_name: Some("VAULT_PASSWORDS".to_string()),
+            message: "empty key in entry 2 <redacted>".to_string(),
+        }
+    );
+    assert_eq!(
+        load(None, Some("hunter2,hunter2")).unwrap_err(),
+        FromEnvError::Custom {
+            var_name: Some("VAULT_TOKENS".to_string()),
+            message: "duplicate item <redacted>".to_string(),
+        }
+    );
+    let error = with_vars(
+        [
+            ("VAULT_PASSWORDS", None),
+            ("VAULT_TOKENS", None),
+            ("VAULT_PIN_COUNT", Some("2")),
+            ("VAULT_PINS_0", Some("1234")),
+            ("VAULT_PINS_1", Some("s3cr3t")),
+        ],
+        VaultConfig::from_env,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        FromEnvError::ParsingFailure {
+            var_name: "VAULT_PINS_1".to_string(),
+            expected_type: "u16".to_string(),
+            str_value: "<redacted>".to_string(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+}
+
+#[test]
+fn test_range_failures_are_redacted() {
+    let load = |window| {
+        with_vars(
+            [
+                ("VAULT_PASSWORDS", None),
+                ("VAULT_TOKENS", None),
+                ("VAULT_PIN_COUNT", None),
+                ("VAULT_WINDOW", Some(window)),
+            ],
+            VaultConfig::from_env,
+        )
+    };
+    assert_eq!(
+        load("pass..word").unwrap_err(),
+        FromEnvError::Custom {
+            var_name: Some("VAULT_WINDOW".to_string()),
+            message: "invalid start bound <redacted>, expected u32".to_string(),
+        }
+    );
+    assert_eq!(
+        load("9..1").unwrap_err(),
+        FromEnvError::Custom {
+            var_name: Some("VAULT_WINDOW".to_string()),
+            message: "the start of the range <redacted> is greater than its end".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_validation_failure_is_redacted() {
+    assert_eq!(
+        load([
+            Some("5432"),
+            None,
+            Some("11"),
+            Some("12345678"),
+            Some("app")
+        ])
+        .unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "DB_POOL".to_string(),
+            message: "<redacted> is out of the range 1..=10".to_string(),
+        }
+    );
+    assert_eq!(
+        load([Some("5432"), None, None, Some("hunter2"), Some("app")]).unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "DB_PASSWORD".to_string(),
+            message: "7 characters, expected at least 8".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_specs_mark_secret_fields() {
+    let secrets = DbConfig::env_vars()
+        .into_iter()
+        .map(|spec| (spec.name, spec.secret))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        secrets,
+        vec![
+            ("DB_PORT".to_string(), true),
+            ("DB_PIN".to_string(), true),
+            ("DB_POOL".to_string(), true),
+            ("DB_PASSWORD".to_string(), true),
+            ("DB_USER".to_string(), false),
+        ]
+    );
+}
+
+#[test]
+fn test_lint_warnings_are_redacted() {
+    with_vars(
+        [
+            ("DB_PORT", Some("5432")),
+            ("DB_PIN", None),
+            ("DB_POOL", None),
+            ("DB_PASSWORD", Some(" \"hunter2\" ")),
+            ("DB_USER", Some(" app ")),
+        ],
+        || {
+            let warnings = DbConfig::lint_env()
+                .into_iter()
+                .map(|warning| (warning.kind, warning.message))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                warnings,
+                [
+                    (
+                        EnvWarningKind::SurroundingWhitespace,
+                        "DB_PASSWORD=<redacted> has surrounding whitespace, kept as part of the value"
+                            .to_string()
+                    ),
+                    (
+                        EnvWarningKind::QuotedValue,
+                        "DB_PASSWORD=<redacted> is wrapped in quotes, kept as part of the value"
+                            .to_string()
+                    ),
+                    (
+                        EnvWarningKind::SurroundingWhitespace,
+                        "DB_USER=\" app \" has surrounding whitespace, kept as part of the value"
+                            .to_string()
+                    ),
+                ]
+            );
+        },
+    );
+}
@@ -0,0 +1,150 @@
+#![cfg(feature = "serde")]
+
+use derive_from_env::{Deserializer, FromEnv, FromEnvError, Origin};
+use serde::Deserialize;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Mode {
+    Fast,
+    Safe,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Database {
+    host: String,
+    port: u16,
+    #[serde(default)]
+    pool_size: u32,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Settings {
+    name: String,
+    mode: Mode,
+    replicas: Vec<String>,
+    timeout: Option<f64>,
+    database: Database,
+}
+
+#[test]
+fn test_deserialize_two_levels() {
+    with_vars(
+        vec![
+            ("APP_NAME", Some("service")),
+            ("APP_MODE", Some("safe")),
+            ("APP_REPLICAS", Some("a,b")),
+            ("APP_DATABASE_HOST", Some("localhost")),
+            ("APP_DATABASE_PORT", Some("5432")),
+        ],
+        || {
+            let settings = Settings::deserialize(Deserializer::from_prefix("APP")).unwrap();
+            assert_eq!(
+                settings,
+                Settings {
+                    name: "service".into(),
+                    mode: Mode::Safe,
+                    replicas: vec!["a".into(), "b".into()],
+                    timeout: None,
+                    database: Database {
+                        host: "localhost".into(),
+                        port: 5432,
+                        pool_size: 0
+                    }
+                }
+            );
+        },
+    )
+}
+
+#[test]
+fn test_deserialize_errors_carry_full_var_name() {
+    with_vars(
+        vec![
+            ("APP_NAME", Some("service")),
+            ("APP_MODE", Some("safe")),
+            ("APP_REPLICAS", Some("")),
+            ("APP_DATABASE_HOST", Some("localhost")),
+            ("APP_DATABASE_PORT", Some("port")),
+        ],
+        || {
+            assert_eq!(
+                Settings::deserialize(Deserializer::from_prefix("APP")).unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "APP_DATABASE_PORT".into(),
+                    expected_type: "u16".into(),
+                    str_value: "port".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+        },
+    );
+    with_vars(
+        vec![
+            ("APP_NAME", Some("service")),
+            ("APP_MODE", Some("safe")),
+            ("APP_REPLICAS", Some("")),
+            ("APP_DATABASE_HOST", Some("localhost")),
+            ("APP_DATABASE_PORT", None),
+        ],
+        || {
+            assert_eq!(
+                Settings::deserialize(Deserializer::from_prefix("APP")).unwrap_err(),
+                FromEnvError::MissingEnvVar {
+                    var_name: "APP_DATABASE_PORT".into(),
+                    origin: Origin::ProcessEnv,
+                    fallbacks: Vec::new(),
+                }
+            );
+        },
+    );
+    with_vars(
+        vec![
+            ("APP_NAME", Some("service")),
+            ("APP_MODE", Some("slow")),
+            ("APP_REPLICAS", Some("")),
+            ("APP_DATABASE_HOST", Some("localhost")),
+            ("APP_DATABASE_PORT", Some("5432")),
+        ],
+        || {
+            let error = Settings::deserialize(Deserializer::from_prefix("APP")).unwrap_err();
+            assert!(matches!(
+                error,
+                FromEnvError::Custom { var_name: Some(ref var_name), .. } if var_name == "APP_MODE"
+            ));
+        },
+    )
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct ServiceConfig {
+    name: String,
+    #[from_env(flatten, deserialize)]
+    database: Database,
+}
+
+#[test]
+fn test_flatten_deserialize() {
+    with_vars(
+        vec![
+            ("SVC_NAME", Some("service")),
+            ("SVC_DATABASE_HOST", Some("localhost")),
+            ("SVC_DATABASE_PORT", Some("5432")),
+            ("SVC_DATABASE_POOL_SIZE", Some("4")),
+        ],
+        || {
+            assert_eq!(
+                ServiceConfig::from_env_with_prefix("SVC").unwrap(),
+                ServiceConfig {
+                    name: "service".into(),
+                    database: Database {
+                        host: "localhost".into(),
+                        port: 5432,
+                        pool_size: 4
+                    }
+                }
+            );
+        },
+    )
+}
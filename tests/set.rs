@@ -0,0 +1,72 @@
+use std::collections::{BTreeSet, HashSet};
+
+use derive_from_env::{FromEnv, FromEnvError};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Flag {
+    Search,
+    Export,
+}
+
+impl std::str::FromStr for Flag {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "search" => Ok(Flag::Search),
+            "export" => Ok(Flag::Export),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    tags: HashSet<String>,
+    #[from_env(deny_duplicates)]
+    feature_flags: Option<BTreeSet<Flag>>,
+    #[from_env(default = "80,443,80")]
+    ports: BTreeSet<u16>,
+}
+
+fn load(tags: &str, feature_flags: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![
+            ("TAGS", Some(tags)),
+            ("FEATURE_FLAGS", feature_flags),
+            ("PORTS", None),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_set() {
+    let config = load("a,b,a", Some("search, export")).unwrap();
+    assert_eq!(
+        config.tags,
+        HashSet::from(["a".to_string(), "b".to_string()])
+    );
+    assert_eq!(
+        config.feature_flags,
+        Some(BTreeSet::from([Flag::Search, Flag::Export]))
+    );
+    assert_eq!(config.ports, BTreeSet::from([80, 443]));
+}
+
+#[test]
+fn test_deny_duplicates() {
+    assert_eq!(
+        load("", Some("search,export,search")).unwrap_err(),
+        FromEnvError::Custom {
+            var_name: Some("FEATURE_FLAGS".into()),
+            message: "duplicate item \"search\"".into(),
+        }
+    );
+    assert_eq!(
+        load("", Some("search,serach")).unwrap_err().to_string(),
+        "failed to parse FEATURE_FLAGS=\"serach\" as Flag item of a list separated by \",\" \
+         (value from process environment)"
+    );
+}
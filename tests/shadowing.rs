@@ -0,0 +1,42 @@
+use std::io::Cursor;
+
+use derive_from_env::{EnvWarningKind, FromEnv, Layered, Origin, ReaderSource};
+
+#[derive(FromEnv)]
+#[allow(dead_code)]
+struct Server {
+    host: String,
+    port: u16,
+    token: String,
+}
+
+fn reader(input: &str) -> ReaderSource {
+    ReaderSource::from_reader(Cursor::new(input.to_string())).unwrap()
+}
+
+#[test]
+fn test_detect_shadowing() {
+    let layered = Layered::new()
+        .layer(reader("PORT=9090\nTOKEN=same\n"))
+        .layer(reader("HOST=localhost\nPORT=8080\nTOKEN=same\n"));
+    let warnings = layered
+        .detect_shadowing(Server::env_vars().iter().map(|spec| &spec.name))
+        .unwrap();
+    assert_eq!(warnings.len(), 1);
+    let warning = &warnings[0];
+    assert_eq!(warning.var_name, "PORT");
+    assert_eq!(
+        warning.kind,
+        EnvWarningKind::Shadowed {
+            winner: Origin::Remote("input line 1".into()),
+            shadowed: vec![Origin::Remote("input line 2".into())],
+        }
+    );
+    assert_eq!(
+        warning.to_string(),
+        "PORT has different values in several layers: input line 1 is used, input line 2 ignored"
+    );
+    assert!(!warning.message.contains("8080"));
+
+    assert_eq!(layered.detect_shadowing(["HOST", "MISSING"]).unwrap(), []);
+}
@@ -4,7 +4,7 @@ use std::{
     str::FromStr,
 };
 
-use derive_from_env::{FromEnv, FromEnvError};
+use derive_from_env::{from_env_test, FromEnv, FromEnvError, Origin};
 use temp_env::with_vars;
 
 #[derive(Debug, PartialEq, FromEnv)]
@@ -109,44 +109,39 @@ struct Config {
 }
 
 #[test]
+#[from_env_test(
+    vars("PARAM_3" = "/test/path", "PARAM_4" = "1"),
+    unset("PARAM_1", "PARAM_2")
+)]
 fn test_1() {
-    with_vars(
-        vec![("PARAM_3", Some("/test/path")), ("PARAM_4", Some("1"))],
-        || {
-            let test = Config::from_env().unwrap();
-            assert_eq!(
-                test,
-                Config {
-                    param_1: 0.,
-                    param_2: None,
-                    sub: SubConfig {
-                        param_3: PathBuf::from("/test/path"),
-                        param_4: 1
-                    }
-                }
-            );
-        },
-    )
+    let test = Config::from_env().unwrap();
+    assert_eq!(
+        test,
+        Config {
+            param_1: 0.,
+            param_2: None,
+            sub: SubConfig {
+                param_3: PathBuf::from("/test/path"),
+                param_4: 1
+            }
+        }
+    );
 }
 
 #[test]
+#[from_env_test(
+    vars("PARAM_2" = "0", "PARAM_3" = "/test/path", "PARAM_4" = "wrong"),
+    unset("PARAM_1")
+)]
 fn test_2() {
-    with_vars(
-        vec![
-            ("PARAM_2", Some("0")),
-            ("PARAM_3", Some("/test/path")),
-            ("PARAM_4", Some("wrong")),
-        ],
-        || {
-            let test = Config::from_env().unwrap_err();
-            assert_eq!(
-                test,
-                FromEnvError::ParsingFailure {
-                    var_name: "PARAM_4".into(),
-                    expected_type: "i32".into(),
-                    str_value: "wrong".into()
-                }
-            );
-        },
-    )
+    let test = Config::from_env().unwrap_err();
+    assert_eq!(
+        test,
+        FromEnvError::ParsingFailure {
+            var_name: "PARAM_4".into(),
+            expected_type: "i32".into(),
+            str_value: "wrong".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
 }
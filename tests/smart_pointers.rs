@@ -0,0 +1,121 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct SharedConfig {
+    region: String,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Service {
+    name: String,
+    #[from_env(flatten)]
+    shared: Arc<SharedConfig>,
+    local: Rc<SharedConfig>,
+    boxed: Option<u8>,
+}
+
+#[test]
+fn test_arc_from_env() {
+    with_vars(vec![("REGION", Some("eu-west-1"))], || {
+        let config = Arc::<SharedConfig>::from_env().unwrap();
+        assert_eq!(config.region, "eu-west-1");
+        assert_eq!(
+            *Box::<SharedConfig>::from_env().unwrap(),
+            SharedConfig {
+                region: "eu-west-1".into()
+            }
+        );
+    })
+}
+
+#[test]
+fn test_flattened_pointers() {
+    with_vars(
+        vec![
+            ("APP_NAME", Some("api")),
+            ("APP_SHARED_REGION", Some("eu-west-1")),
+            ("APP_LOCAL_REGION", Some("us-east-1")),
+            ("APP_BOXED", None),
+        ],
+        || {
+            let service = Service::from_env_with_prefix("APP").unwrap();
+            assert_eq!(service.name, "api");
+            assert_eq!(service.shared.region, "eu-west-1");
+            assert_eq!(service.local.region, "us-east-1");
+            assert_eq!(
+                Service::env_vars()
+                    .iter()
+                    .map(|spec| spec.name.as_str())
+                    .collect::<Vec<_>>(),
+                ["NAME", "SHARED_REGION", "LOCAL_REGION", "BOXED"]
+            );
+        },
+    )
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Shared {
+    api_key: Arc<String>,
+    region: Arc<str>,
+    #[from_env(default = "8")]
+    workers: Box<u8>,
+    hosts: Rc<Vec<String>>,
+    token: Option<Arc<String>>,
+    limits: Box<SharedConfig>,
+}
+
+#[test]
+fn test_pointer_values() {
+    with_vars(
+        vec![
+            ("API_KEY", Some("secret")),
+            ("REGION", Some("eu-west-1")),
+            ("HOSTS", Some("a,b")),
+            ("TOKEN", Some("t0k3n")),
+            ("LIMITS_REGION", Some("us-east-1")),
+        ],
+        || {
+            assert_eq!(
+                Shared::from_env().unwrap(),
+                Shared {
+                    api_key: Arc::new("secret".into()),
+                    region: "eu-west-1".into(),
+                    workers: Box::new(8),
+                    hosts: Rc::new(vec!["a".into(), "b".into()]),
+                    token: Some(Arc::new("t0k3n".into())),
+                    limits: Box::new(SharedConfig {
+                        region: "us-east-1".into()
+                    }),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_pointer_values_failures() {
+    with_vars(
+        vec![
+            ("API_KEY", Some("secret")),
+            ("REGION", Some("eu-west-1")),
+            ("WORKERS", Some("many")),
+            ("HOSTS", Some("a,b")),
+            ("LIMITS_REGION", Some("us-east-1")),
+        ],
+        || {
+            assert_eq!(
+                Shared::from_env().unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "WORKERS".into(),
+                    expected_type: "u8".into(),
+                    str_value: "many".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+        },
+    );
+}
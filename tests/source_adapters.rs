@@ -0,0 +1,76 @@
+use std::io::Cursor;
+
+use derive_from_env::{EnvSource, FromEnv, Origin, ReaderSource};
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    db_host: String,
+    db_port: u16,
+    log_level: String,
+}
+
+fn reader(input: &str) -> ReaderSource {
+    ReaderSource::from_reader(Cursor::new(input.to_string())).unwrap()
+}
+
+#[test]
+fn test_composed_adapters() {
+    // `APP_DB_PORT` is read from `STAGING_APP_DB_PORT`, then from the file-like key `db.port`,
+    // and finally from the unprefixed `DB_PORT`.
+    let staging = reader("STAGING_APP_DB_PORT=6543\nAPP_DB_PORT=1\n");
+    let files = reader("db.host=files.internal\ndb.port=2\nAPP_LOG_LEVEL=3\n");
+    let base = reader("DB_HOST=base.internal\nLOG_LEVEL=debug\nEXTRA=1\n");
+    let source = staging
+        .with_prefix("STAGING_")
+        .or(files.map_keys(|key| {
+            let key = key.strip_prefix("APP_").unwrap_or(key);
+            key.to_lowercase().replace('_', ".")
+        }))
+        .or(base.strip_prefix("APP_"));
+
+    assert_eq!(
+        Config::from_source_with_prefix(&source, "APP").unwrap(),
+        Config {
+            db_host: "files.internal".into(),
+            db_port: 6543,
+            log_level: "debug".into(),
+        }
+    );
+    assert_eq!(
+        source.lookup("APP_DB_PORT").unwrap(),
+        Some(("6543".into(), Origin::Remote("input line 1".into())))
+    );
+    assert_eq!(
+        source.lookup("APP_DB_HOST").unwrap(),
+        Some((
+            "files.internal".into(),
+            Origin::Remote("input line 1".into())
+        ))
+    );
+    assert_eq!(source.get("EXTRA").unwrap(), None);
+    assert_eq!(source.get("APP_EXTRA").unwrap(), Some("1".into()));
+    assert_eq!(
+        source.lookup("APP_LOG_LEVEL").unwrap(),
+        Some(("debug".into(), Origin::Remote("input line 2".into())))
+    );
+    assert_eq!(
+        source.keys().unwrap(),
+        vec!["APP_DB_PORT", "APP_DB_HOST", "APP_EXTRA", "APP_LOG_LEVEL"]
+    );
+}
+
+#[test]
+fn test_fallback_order() {
+    let source = reader("DB_HOST=first\n")
+        .or(reader("DB_HOST=second\nDB_PORT=2\n"))
+        .or(reader("\n\nDB_PORT=3\nLOG_LEVEL=warn\n"));
+    let config = Config::from_source(&source).unwrap();
+    assert_eq!(config.db_host, "first");
+    assert_eq!(config.db_port, 2);
+    assert_eq!(config.log_level, "warn");
+    assert_eq!(
+        source.origin("LOG_LEVEL"),
+        Origin::Remote("input line 4".into())
+    );
+    assert_eq!(source.origin("MISSING"), Origin::Remote("input".into()));
+}
@@ -0,0 +1,57 @@
+use std::collections::HashSet;
+use std::net::SocketAddr;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(separator = ";")]
+struct Config {
+    #[from_env(split = "whitespace")]
+    seeds: Vec<SocketAddr>,
+    #[from_env(split = "whitespace", default = "  ")]
+    tags: HashSet<String>,
+    #[from_env(split = "whitespace")]
+    rgb: Option<[u8; 3]>,
+}
+
+fn load(seeds: &str, tags: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![
+            ("SEEDS", Some(seeds)),
+            ("TAGS", tags),
+            ("RGB", Some("1\t2\n3")),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_split_whitespace() {
+    let config = load("  10.0.0.1:80 \t 10.0.0.2:80\n", Some("a b  a")).unwrap();
+    assert_eq!(
+        config.seeds,
+        [
+            "10.0.0.1:80".parse::<SocketAddr>().unwrap(),
+            "10.0.0.2:80".parse().unwrap()
+        ]
+    );
+    assert_eq!(config.tags, HashSet::from(["a".into(), "b".into()]));
+    assert_eq!(config.rgb, Some([1, 2, 3]));
+    let config = load(" \t ", None).unwrap();
+    assert!(config.seeds.is_empty());
+    assert!(config.tags.is_empty());
+}
+
+#[test]
+fn test_split_whitespace_failure() {
+    assert_eq!(
+        load("10.0.0.1:80 node2", None).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "SEEDS".into(),
+            expected_type: "SocketAddr item of a list separated by whitespace".into(),
+            str_value: "node2".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+}
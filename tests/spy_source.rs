@@ -0,0 +1,49 @@
+use std::io::Cursor;
+
+use derive_from_env::{FromEnv, Lookup, ReaderSource, SpySource};
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Database {
+    host: String,
+    #[from_env(default = "5432")]
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    name: String,
+    db: Database,
+    replica: Option<String>,
+    #[from_env(var = "RUST_LOG")]
+    log: String,
+}
+
+fn lookup(key: &str, hit: bool) -> Lookup {
+    Lookup {
+        key: key.into(),
+        hit,
+    }
+}
+
+#[test]
+fn test_spy_source_records_lookups() {
+    let input = "APP_NAME=demo\nAPP_DB_HOST=db.internal\nRUST_LOG=info\nAPP_UNUSED=1\n";
+    let spy = SpySource::new(ReaderSource::from_reader(Cursor::new(input)).unwrap());
+    let config = Config::from_source_with_prefix(&spy, "APP").unwrap();
+    assert_eq!(config.db.port, 5432);
+    assert_eq!(
+        spy.lookups(),
+        [
+            lookup("APP_NAME", true),
+            lookup("APP_DB_HOST", true),
+            lookup("APP_DB_PORT", false),
+            lookup("APP_REPLICA", false),
+            lookup("RUST_LOG", true),
+        ]
+    );
+
+    spy.clear();
+    assert_eq!(spy.lookups(), []);
+    assert!(Config::from_source(&spy).is_err());
+    assert_eq!(spy.keys_looked_up(), ["NAME"]);
+}
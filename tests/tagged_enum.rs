@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(tag = "TYPE")]
+enum Storage {
+    S3 {
+        bucket: String,
+        #[from_env(default = "us-east-1")]
+        region: String,
+    },
+    #[from_env(alias = "fs")]
+    Local {
+        path: PathBuf,
+    },
+    Memory,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    storage: Storage,
+}
+
+#[test]
+fn test_select_variant() {
+    with_vars(
+        vec![
+            ("STORAGE_TYPE", Some("s3")),
+            ("STORAGE_BUCKET", Some("assets")),
+            ("STORAGE_REGION", None),
+            ("STORAGE_PATH", None),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap().storage,
+                Storage::S3 {
+                    bucket: "assets".to_string(),
+                    region: "us-east-1".to_string(),
+                }
+            );
+        },
+    );
+    with_vars(
+        vec![
+            ("STORAGE_TYPE", Some("FS")),
+            ("STORAGE_PATH", Some("/var/data")),
+            ("STORAGE_BUCKET", None),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap().storage,
+                Storage::Local {
+                    path: PathBuf::from("/var/data"),
+                }
+            );
+        },
+    );
+    with_vars(vec![("TYPE", Some("memory"))], || {
+        assert_eq!(Storage::from_env().unwrap(), Storage::Memory);
+    });
+}
+
+#[test]
+fn test_invalid_tag() {
+    with_vars(vec![("STORAGE_TYPE", Some("gcs"))], || {
+        assert_eq!(
+            Config::from_env().unwrap_err(),
+            FromEnvError::ParsingFailure {
+                var_name: "STORAGE_TYPE".into(),
+                str_value: "gcs".into(),
+                origin: Origin::ProcessEnv,
+                expected_type: "one of: S3, Local, Memory".into(),
+            }
+        );
+    });
+    with_vars(vec![("STORAGE_TYPE", None::<&str>)], || {
+        assert!(matches!(
+            Config::from_env().unwrap_err(),
+            FromEnvError::MissingEnvVar { var_name, .. } if var_name == "STORAGE_TYPE"
+        ));
+    });
+}
+
+#[test]
+fn test_missing_field_of_selected_variant() {
+    with_vars(
+        vec![("STORAGE_TYPE", Some("local")), ("STORAGE_PATH", None)],
+        || {
+            assert!(matches!(
+                Config::from_env().unwrap_err(),
+                FromEnvError::MissingEnvVar { var_name, .. } if var_name == "STORAGE_PATH"
+            ));
+        },
+    );
+}
+
+#[test]
+fn test_tagged_enum_specs() {
+    let specs = Config::env_vars();
+    let names = specs
+        .iter()
+        .map(|spec| spec.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "STORAGE_TYPE",
+            "STORAGE_BUCKET",
+            "STORAGE_REGION",
+            "STORAGE_PATH"
+        ]
+    );
+    assert!(specs[0].required);
+    assert_eq!(specs[1].field, "storage.S3.bucket");
+    assert!(!specs[1].required);
+}
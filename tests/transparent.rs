@@ -0,0 +1,98 @@
+use derive_from_env::{EnvVarSpec, FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(transparent)]
+struct Port {
+    value: u16,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(transparent)]
+struct Hostname(String);
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(transparent)]
+struct Workers(#[from_env(default = "4")] u8);
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Server {
+    host: Hostname,
+    http_port: Port,
+    workers: Workers,
+}
+
+#[test]
+fn test_transparent_standalone() {
+    with_vars(
+        vec![("PORT", Some("8080")), ("HOSTNAME", Some("localhost"))],
+        || {
+            assert_eq!(Port::from_env().unwrap(), Port { value: 8080 });
+            assert_eq!(
+                Port::from_env_with_prefix("HOSTNAME").unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "HOSTNAME".into(),
+                    expected_type: "u16".into(),
+                    str_value: "localhost".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+            assert_eq!(Hostname::from_env().unwrap(), Hostname("localhost".into()));
+        },
+    )
+}
+
+#[test]
+fn test_transparent_embedded() {
+    with_vars(
+        vec![
+            ("APP_HOST", Some("localhost")),
+            ("APP_HTTP_PORT", Some("8080")),
+            ("APP_WORKERS", None),
+        ],
+        || {
+            assert_eq!(
+                Server::from_env_with_prefix("APP").unwrap(),
+                Server {
+                    host: Hostname("localhost".into()),
+                    http_port: Port { value: 8080 },
+                    workers: Workers(4),
+                }
+            );
+        },
+    );
+    assert_eq!(
+        Server::env_vars()[1],
+        EnvVarSpec {
+            name: "HTTP_PORT".into(),
+            field: "http_port".into(),
+            type_name: "u16".into(),
+            default: None,
+            required: true,
+            optional: false,
+            hidden: false,
+            secret: false,
+        }
+    );
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct UserId(u64);
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Session {
+    user_id: UserId,
+}
+
+#[test]
+fn test_newtype_implied_transparent() {
+    with_vars(vec![("USER_ID", Some("42"))], || {
+        assert_eq!(UserId::from_env().unwrap(), UserId(42));
+        assert_eq!(
+            Session::from_env().unwrap(),
+            Session {
+                user_id: UserId(42),
+            }
+        );
+    });
+}
@@ -0,0 +1,80 @@
+use derive_from_env::{DotenvSource, FromEnv, FromEnvError};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "SRV_")]
+struct Server {
+    #[from_env(trim)]
+    port: u16,
+    #[from_env(trim, non_empty, default = "localhost")]
+    host: String,
+    banner: Option<String>,
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "CI_", trim)]
+struct CiConfig {
+    workers: usize,
+    token: String,
+    #[from_env(raw)]
+    motd: String,
+    tags: Vec<String>,
+}
+
+#[test]
+fn test_trimmed_field() {
+    with_vars(
+        [
+            ("SRV_PORT", Some(" 8080\r\n")),
+            ("SRV_HOST", Some(" \r\n")),
+            ("SRV_BANNER", Some(" hello\n")),
+        ],
+        || {
+            assert_eq!(
+                Server::from_env().unwrap(),
+                Server {
+                    port: 8080,
+                    host: "localhost".to_string(),
+                    banner: Some(" hello\n".to_string()),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_untrimmed_field_fails() {
+    #[derive(Debug, FromEnv)]
+    #[from_env(prefix = "SRV_")]
+    struct Untrimmed {
+        #[allow(dead_code)]
+        port: u16,
+    }
+    with_vars([("SRV_PORT", Some(" 8080\r\n"))], || {
+        assert!(matches!(
+            Untrimmed::from_env().unwrap_err(),
+            FromEnvError::ParsingFailure { .. }
+        ));
+    });
+}
+
+#[test]
+fn test_struct_trim_from_windows_dotenv() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(".env");
+    std::fs::write(
+        &path,
+        "CI_WORKERS=4 \r\nCI_TOKEN=\" abc \"\r\nCI_MOTD=\" welcome \"\r\nCI_TAGS=\"a, b \"\r\n",
+    )
+    .unwrap();
+    let source = DotenvSource::from_path(&path).unwrap();
+    assert_eq!(
+        CiConfig::from_source(&source).unwrap(),
+        CiConfig {
+            workers: 4,
+            token: "abc".to_string(),
+            motd: " welcome ".to_string(),
+            tags: vec!["a".to_string(), "b".to_string()],
+        }
+    );
+}
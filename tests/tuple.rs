@@ -0,0 +1,49 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(tuple_separator = "x")]
+    resolution: (u32, u32),
+    #[from_env(default = "0.0.0.0, 8080, true")]
+    bind: (String, u16, bool),
+    #[from_env(tuple_separator = ":")]
+    offset: Option<(i32, i32)>,
+}
+
+fn load(resolution: &str, offset: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        vec![("RESOLUTION", Some(resolution)), ("OFFSET", offset)],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_tuple() {
+    let config = load("1920x1080", None).unwrap();
+    assert_eq!(config.resolution, (1920, 1080));
+    assert_eq!(config.bind, ("0.0.0.0".to_string(), 8080, true));
+    assert_eq!(config.offset, None);
+    assert_eq!(load("1 x 2", Some("-5:10")).unwrap().offset, Some((-5, 10)));
+}
+
+#[test]
+fn test_tuple_failures() {
+    assert_eq!(
+        load("1920xtall", None).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "RESOLUTION".into(),
+            expected_type: "u32 (component 2 of a tuple separated by \"x\")".into(),
+            str_value: "tall".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+    assert_eq!(
+        load("1920x1080x32", None).unwrap_err().to_string(),
+        "RESOLUTION: expected 2 components separated by \"x\", found 3 in \"1920x1080x32\""
+    );
+    assert_eq!(
+        load("1920x1080", Some("-5")).unwrap_err().to_string(),
+        "OFFSET: expected 2 components separated by \":\", found 1 in \"-5\""
+    );
+}
@@ -0,0 +1,74 @@
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Bounds(u32, #[from_env(default = "100")] u32);
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Point(
+    #[from_env(var = "ORIGIN_X")] i32,
+    #[from_env(var = "ORIGIN_Y")] i32,
+);
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct Config {
+    #[from_env(flatten)]
+    range: Bounds,
+    origin: Point,
+}
+
+#[test]
+fn test_positional_vars() {
+    with_vars(
+        vec![
+            ("APP_RANGE_0", Some("10")),
+            ("APP_RANGE_1", Some("20")),
+            ("ORIGIN_X", Some("-1")),
+            ("ORIGIN_Y", Some("2")),
+        ],
+        || {
+            let config = Config::from_env().unwrap();
+            assert_eq!(config.range, Bounds(10, 20));
+            assert_eq!(config.origin, Point(-1, 2));
+        },
+    )
+}
+
+#[test]
+fn test_positional_default() {
+    with_vars(
+        vec![
+            ("APP_RANGE_0", Some("10")),
+            ("APP_RANGE_1", None),
+            ("ORIGIN_X", Some("0")),
+            ("ORIGIN_Y", Some("0")),
+        ],
+        || {
+            assert_eq!(Config::from_env().unwrap().range, Bounds(10, 100));
+        },
+    )
+}
+
+#[test]
+fn test_positional_missing() {
+    with_vars(vec![("RANGE_0", None), ("RANGE_1", Some("1"))], || {
+        assert_eq!(
+            Bounds::from_env_with_prefix("RANGE").unwrap_err(),
+            FromEnvError::MissingEnvVar {
+                var_name: "RANGE_0".into(),
+                origin: Origin::ProcessEnv,
+                fallbacks: Vec::new(),
+            }
+        );
+    })
+}
+
+#[test]
+fn test_positional_specs() {
+    let specs = Config::env_vars();
+    assert_eq!(specs[0].name, "APP_RANGE_0");
+    assert_eq!(specs[0].field, "range.0");
+    assert_eq!(specs[1].name, "APP_RANGE_1");
+    assert_eq!(specs[1].default.as_deref(), Some("100"));
+}
@@ -0,0 +1,87 @@
+use derive_from_env::{EnvVarSpec, FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq)]
+struct MaxRps(u32);
+
+impl std::str::FromStr for MaxRps {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(MaxRps)
+    }
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(from_str, type_name = "requests per second (integer)")]
+    max_rps: MaxRps,
+    #[from_env(type_name = "number of seconds")]
+    timeout: Option<u64>,
+    #[from_env(type_name = "port number")]
+    ports: Vec<u16>,
+}
+
+#[test]
+fn test_type_name_in_errors() {
+    let cases = [
+        ("MAX_RPS", "fast", "requests per second (integer)"),
+        ("TIMEOUT", "1m", "number of seconds"),
+        ("PORTS", "80,http", "port number"),
+    ];
+    for (var_name, value, type_name) in cases {
+        let mut vars = vec![
+            ("MAX_RPS", Some("100")),
+            ("TIMEOUT", Some("60")),
+            ("PORTS", Some("80")),
+        ];
+        vars.retain(|(name, _)| *name != var_name);
+        vars.push((var_name, Some(value)));
+        with_vars(vars, || {
+            let error = Config::from_env().unwrap_err().to_string();
+            assert!(error.contains(&format!("as {} ", type_name)), "{}", error);
+        });
+    }
+}
+
+#[test]
+fn test_type_name_in_specs() {
+    let specs = Config::env_vars();
+    let type_names = specs
+        .iter()
+        .map(|spec: &EnvVarSpec| spec.type_name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        type_names,
+        [
+            "requests per second (integer)",
+            "number of seconds",
+            "port number"
+        ]
+    );
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Braced {
+    #[from_env(quoted, separator = "{", type_name = "port {number}")]
+    ports: Vec<u16>,
+}
+
+#[test]
+fn test_type_name_with_braces() {
+    with_vars(vec![("PORTS", Some("80{\"http"))], || {
+        assert_eq!(
+            Braced::from_env().unwrap_err(),
+            FromEnvError::ParsingFailure {
+                var_name: "PORTS".into(),
+                expected_type: "closing quote (item 2 of a list separated by \"{\")".into(),
+                str_value: "\"http".into(),
+                origin: Origin::ProcessEnv,
+            }
+        );
+    });
+    with_vars(vec![("PORTS", Some("80{http"))], || {
+        let error = Braced::from_env().unwrap_err().to_string();
+        assert!(error.contains("as port {number} item"), "{}", error);
+    });
+}
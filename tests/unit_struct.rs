@@ -0,0 +1,32 @@
+use derive_from_env::FromEnv;
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(diff, merge, cli_args, check_defaults)]
+struct NoAuth;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    port: u16,
+    auth: NoAuth,
+}
+
+fn load<T: FromEnv>(prefix: &str) -> T {
+    T::from_env_with_prefix(prefix).unwrap()
+}
+
+#[test]
+fn test_unit_struct() {
+    with_vars(vec![("PORT", Some("8080"))], || {
+        assert_eq!(NoAuth::from_env().unwrap(), NoAuth);
+        assert_eq!(load::<NoAuth>("APP"), NoAuth);
+        assert_eq!(
+            Config::from_env().unwrap(),
+            Config {
+                port: 8080,
+                auth: NoAuth,
+            }
+        );
+        assert!(NoAuth::env_vars().is_empty());
+    })
+}
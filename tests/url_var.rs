@@ -0,0 +1,98 @@
+use std::str::FromStr;
+
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+impl FromStr for Database {
+    type Err = String;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        let authority = url
+            .strip_prefix("postgres://")
+            .ok_or("not a postgres URL")?;
+        let (host, port) = authority.split_once(':').ok_or("missing port")?;
+        Ok(Database {
+            host: host.to_string(),
+            port: port.parse().map_err(|_| "invalid port")?,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct App {
+    #[from_env(url_var = "DATABASE_URL")]
+    db: Database,
+    #[from_env(url_var = "REPLICA_URL", deny_conflicts)]
+    replica: Database,
+}
+
+fn load(vars: Vec<(&str, Option<&str>)>) -> Result<App, FromEnvError> {
+    let mut all = vec![
+        ("DATABASE_URL", None),
+        ("DB_HOST", None),
+        ("DB_PORT", None),
+        ("REPLICA_URL", Some("postgres://replica:5433")),
+        ("REPLICA_HOST", None),
+        ("REPLICA_PORT", None),
+    ];
+    all.extend(vars);
+    with_vars(all, App::from_env)
+}
+
+#[test]
+fn test_url_var() {
+    let app = load(vec![
+        ("DATABASE_URL", Some("postgres://db1:5432")),
+        ("DB_HOST", Some("ignored")),
+    ])
+    .unwrap();
+    assert_eq!(
+        app.db,
+        Database {
+            host: "db1".into(),
+            port: 5432
+        }
+    );
+    assert_eq!(app.replica.host, "replica");
+    let app = load(vec![
+        ("DB_HOST", Some("localhost")),
+        ("DB_PORT", Some("5432")),
+    ])
+    .unwrap();
+    assert_eq!(app.db.host, "localhost");
+    assert_eq!(
+        App::env_vars()
+            .iter()
+            .map(|spec| (spec.name.as_str(), spec.type_name.as_str()))
+            .collect::<Vec<_>>()[..2],
+        [("DATABASE_URL", "Database URL"), ("DB_HOST", "String")]
+    );
+}
+
+#[test]
+fn test_url_var_failures() {
+    assert_eq!(
+        load(vec![("DATABASE_URL", Some("mysql://db1"))]).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "DATABASE_URL".into(),
+            expected_type: "Database".into(),
+            str_value: "mysql://db1".into(),
+            origin: Origin::ProcessEnv,
+        }
+    );
+    assert_eq!(
+        load(vec![
+            ("DATABASE_URL", Some("postgres://db1:5432")),
+            ("REPLICA_HOST", Some("replica2")),
+        ])
+        .unwrap_err()
+        .to_string(),
+        "REPLICA_URL: conflicts with REPLICA_HOST, which should not be set along with it"
+    );
+}
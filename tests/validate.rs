@@ -0,0 +1,86 @@
+use derive_from_env::{FromEnv, FromEnvError};
+use temp_env::with_vars;
+
+mod checks {
+    pub fn port(port: &u16) -> Result<(), String> {
+        match port {
+            0 => Err("port 0 is reserved".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn workers(workers: &usize) -> Result<(), String> {
+        if *workers > 1024 {
+            return Err(format!("{} workers is more than 1024", workers));
+        }
+        Ok(())
+    }
+
+    pub fn hosts(hosts: &[String]) -> Result<(), String> {
+        if hosts.is_empty() {
+            return Err("at least one host is needed".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct Config {
+    #[from_env(validate = "checks::port", default = "0")]
+    port: u16,
+    #[from_env(validate = "checks::workers")]
+    workers: Option<usize>,
+    #[from_env(validate = "checks::hosts")]
+    hosts: Vec<String>,
+}
+
+fn load(vars: [Option<&str>; 3]) -> Result<Config, FromEnvError> {
+    with_vars(
+        [
+            ("APP_PORT", vars[0]),
+            ("APP_WORKERS", vars[1]),
+            ("APP_HOSTS", vars[2]),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_valid_values() {
+    assert_eq!(
+        load([Some("8080"), None, Some("a,b")]).unwrap(),
+        Config {
+            port: 8080,
+            workers: None,
+            hosts: vec!["a".to_string(), "b".to_string()],
+        }
+    );
+}
+
+#[test]
+fn test_invalid_value() {
+    assert_eq!(
+        load([Some("8080"), Some("1000000"), Some("a")]).unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "APP_WORKERS".to_string(),
+            message: "1000000 workers is more than 1024".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_invalid_default_and_collection() {
+    let error = load([None, None, Some("a")]).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid value of APP_PORT: port 0 is reserved"
+    );
+    assert_eq!(
+        load([Some("80"), None, Some("")]).unwrap_err(),
+        FromEnvError::ValidationFailure {
+            var_name: "APP_HOSTS".to_string(),
+            message: "at least one host is needed".to_string(),
+        }
+    );
+}
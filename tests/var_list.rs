@@ -0,0 +1,94 @@
+use derive_from_env::{FromEnv, FromEnvError, Location, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(prefix = "APP_")]
+struct Config {
+    #[from_env(var = ["SERVICE_TOKEN", "GITHUB_TOKEN", "GH_TOKEN"])]
+    token: String,
+    #[from_env(var = ["SERVICE_RETRIES", "RETRIES"], default = "3")]
+    retries: u32,
+}
+
+fn vars<'a>(
+    token: [Option<&'a str>; 3],
+    retries: Option<&'a str>,
+) -> Vec<(&'static str, Option<&'a str>)> {
+    vec![
+        ("SERVICE_TOKEN", token[0]),
+        ("GITHUB_TOKEN", token[1]),
+        ("GH_TOKEN", token[2]),
+        ("SERVICE_RETRIES", None),
+        ("RETRIES", retries),
+    ]
+}
+
+#[test]
+fn test_first_name_set_wins() {
+    with_vars(vars([None, Some("ghp_1"), Some("ghp_2")], None), || {
+        assert_eq!(
+            Config::from_env().unwrap(),
+            Config {
+                token: "ghp_1".to_string(),
+                retries: 3,
+            }
+        );
+    });
+    with_vars(vars([Some("svc"), Some("ghp_1"), None], Some("5")), || {
+        assert_eq!(
+            Config::from_env().unwrap(),
+            Config {
+                token: "svc".to_string(),
+                retries: 5,
+            }
+        );
+    });
+}
+
+#[test]
+fn test_missing_lists_every_name() {
+    with_vars(vars([None; 3], None), || {
+        assert_eq!(
+            Config::from_env().unwrap_err(),
+            FromEnvError::MissingEnvVar {
+                var_name: "SERVICE_TOKEN".to_string(),
+                origin: Origin::ProcessEnv,
+                fallbacks: vec!["GITHUB_TOKEN".to_string(), "GH_TOKEN".to_string()],
+            }
+        );
+    });
+}
+
+#[test]
+fn test_parsing_failure_names_the_variable_used() {
+    with_vars(vars([None, None, Some("ghp_2")], Some("many")), || {
+        assert_eq!(
+            Config::from_env().unwrap_err(),
+            FromEnvError::ParsingFailure {
+                var_name: "RETRIES".to_string(),
+                str_value: "many".to_string(),
+                origin: Origin::ProcessEnv,
+                expected_type: "u32".to_string(),
+            }
+        );
+    });
+}
+
+#[test]
+fn test_explain_marks_the_variable_used() {
+    with_vars(vars([None, None, Some("ghp_2")], None), || {
+        let plan = Config::explain("token").unwrap();
+        assert_eq!(
+            plan.steps
+                .iter()
+                .map(|step| step.location.clone())
+                .collect::<Vec<_>>(),
+            [
+                Location::Variable("SERVICE_TOKEN".to_string()),
+                Location::Variable("GITHUB_TOKEN".to_string()),
+                Location::Variable("GH_TOKEN".to_string()),
+            ]
+        );
+        assert_eq!(plan.steps[2].value.as_deref(), Some("ghp_2"));
+    });
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use derive_from_env::{ArgStyle, FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq)]
+struct Level(u8);
+
+/// Levels written as `low`, `medium` or `high`.
+mod level {
+    use super::Level;
+
+    pub fn parse(value: &str) -> Result<Level, String> {
+        match value {
+            "low" => Ok(Level(1)),
+            "medium" => Ok(Level(2)),
+            "high" => Ok(Level(3)),
+            _ => Err(format!("expected low, medium or high, found {:?}", value)),
+        }
+    }
+
+    pub fn to_string(level: &Level) -> String {
+        match level.0 {
+            1 => "low",
+            2 => "medium",
+            _ => "high",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Debug, PartialEq, FromEnv)]
+#[from_env(cli_args)]
+struct Config {
+    #[from_env(with = "level")]
+    log_level: Level,
+    #[from_env(with = "level", default = "low")]
+    trace_level: Level,
+    #[from_env(with = "level")]
+    audit_level: Option<Level>,
+    #[from_env(with = "level")]
+    alert_levels: Vec<Level>,
+    #[from_env(with = "level")]
+    service_levels: BTreeMap<String, Level>,
+}
+
+fn load(audit_level: Option<&str>) -> Result<Config, FromEnvError> {
+    with_vars(
+        [
+            ("LOG_LEVEL", Some("medium")),
+            ("TRACE_LEVEL", None),
+            ("AUDIT_LEVEL", audit_level),
+            ("ALERT_LEVELS", Some("high,low")),
+            ("SERVICE_LEVELS", Some("api=high,worker=low")),
+        ],
+        Config::from_env,
+    )
+}
+
+#[test]
+fn test_with_parse() {
+    assert_eq!(
+        load(Some("high")).unwrap(),
+        Config {
+            log_level: Level(2),
+            trace_level: Level(1),
+            audit_level: Some(Level(3)),
+            alert_levels: vec![Level(3), Level(1)],
+            service_levels: BTreeMap::from([
+                ("api".to_string(), Level(3)),
+                ("worker".to_string(), Level(1)),
+            ]),
+        }
+    );
+}
+
+#[test]
+fn test_with_parse_error() {
+    assert_eq!(
+        load(Some("max")).unwrap_err(),
+        FromEnvError::ParsingFailure {
+            var_name: "AUDIT_LEVEL".to_string(),
+            str_value: "max".to_string(),
+            origin: Origin::ProcessEnv,
+            expected_type: "Level (expected low, medium or high, found \"max\")".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_with_to_string_round_trip() {
+    let config = load(None).unwrap();
+    assert_eq!(
+        config.to_cli_args(ArgStyle::default()),
+        [
+            "--log-level=medium",
+            "--trace-level=low",
+            "--alert-levels=high,low",
+            "--service-levels=api=high,worker=low",
+        ]
+    );
+}
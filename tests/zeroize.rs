@@ -0,0 +1,61 @@
+#![cfg(feature = "zeroize")]
+
+use derive_from_env::zeroize::Zeroizing;
+use derive_from_env::{FromEnv, FromEnvError, Origin};
+use temp_env::with_vars;
+
+#[derive(Debug, PartialEq, FromEnv)]
+struct Config {
+    #[from_env(zeroize, credential = "api_key")]
+    api_key: Zeroizing<String>,
+    #[from_env(zeroize, base64)]
+    hmac_key: Zeroizing<Vec<u8>>,
+    #[from_env(zeroize, hex)]
+    previous_key: Option<Zeroizing<Vec<u8>>>,
+}
+
+#[test]
+fn test_zeroize() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("api_key"), "hunter2\n").unwrap();
+    with_vars(
+        vec![
+            ("CREDENTIALS_DIRECTORY", dir.path().to_str()),
+            ("HMAC_KEY", Some("AAEC/w==")),
+            ("PREVIOUS_KEY", Some("0xdead")),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap(),
+                Config {
+                    api_key: Zeroizing::new("hunter2".into()),
+                    hmac_key: Zeroizing::new(vec![0, 1, 2, 255]),
+                    previous_key: Some(Zeroizing::new(vec![0xde, 0xad])),
+                }
+            );
+        },
+    );
+}
+
+#[test]
+fn test_zeroize_failure() {
+    with_vars(
+        vec![
+            ("CREDENTIALS_DIRECTORY", None),
+            ("API_KEY", Some("hunter2")),
+            ("HMAC_KEY", Some("AAEC/w==")),
+            ("PREVIOUS_KEY", Some("0xdeadbeefz")),
+        ],
+        || {
+            assert_eq!(
+                Config::from_env().unwrap_err(),
+                FromEnvError::ParsingFailure {
+                    var_name: "PREVIOUS_KEY".into(),
+                    expected_type: "hex, invalid character at position 11".into(),
+                    str_value: "<redacted>".into(),
+                    origin: Origin::ProcessEnv,
+                }
+            );
+        },
+    );
+}